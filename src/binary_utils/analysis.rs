@@ -0,0 +1,23 @@
+pub mod aliases;
+pub mod budget;
+pub mod call_graph;
+pub mod code_hash;
+pub mod compare;
+pub mod components;
+pub mod constructors;
+pub mod diff;
+pub mod gaps;
+pub mod history;
+pub mod icf;
+pub mod instruction_mix;
+pub mod kinds;
+pub mod pareto;
+pub mod relocations;
+pub mod size;
+pub mod stack;
+pub mod summary;
+pub mod symbol_keys;
+pub mod tls;
+pub mod unreachable;
+pub mod unwind;
+pub mod weak;
@@ -0,0 +1,277 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{cmp::Ordering, collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::symbol_table::{
+    SymbolScope, SymbolTable, SymbolTableEntry, SymbolType, SymbolWeakness,
+};
+use crate::binary_utils::report::{csv_field, size_string, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Symbols that share the same address in the same section
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AliasGroup {
+    pub section: String,
+    pub address: u32,
+    /// Size of the largest symbol in the group
+    pub size: u32,
+    /// Whether the size was inferred from the next symbol
+    pub size_inferred: bool,
+    /// The symbol that best names the group, preferring sized, typed, global and strong symbols
+    pub canonical: String,
+    pub aliases: Vec<String>,
+}
+
+/// Two sized symbols at different addresses whose bytes overlap
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Overlap {
+    pub section: String,
+    /// The symbol with the lower address
+    pub first: String,
+    /// The symbol that starts inside the first one
+    pub second: String,
+    /// Address where the overlap starts
+    pub address: u32,
+    /// Number of bytes both symbols cover
+    pub size: u32,
+}
+
+/// Summed and de-duplicated symbol sizes of one section
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionTotal {
+    pub section: String,
+    /// Plain sum of every symbol size, counting shared bytes more than once
+    pub summed: u32,
+    /// Bytes covered by at least one symbol
+    pub deduplicated: u32,
+}
+
+/// Aliased and overlapping symbols of a symbol table, along with how much they inflate section sums
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AliasReport {
+    pub groups: Vec<AliasGroup>,
+    pub overlaps: Vec<Overlap>,
+    pub sections: Vec<SectionTotal>,
+}
+
+impl AliasReport {
+    /// Groups symbols that share an address and finds sized symbols that overlap
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to analyze
+    pub fn new(symbol_table: &SymbolTable) -> Self {
+        let mut by_address: BTreeMap<(&str, u32), Vec<&SymbolTableEntry>> = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated() && !x.is_section_symbol()) {
+            by_address.entry((&entry.section, entry.address)).or_default().push(entry);
+        }
+
+        let mut groups = Vec::new();
+        let mut canonical: BTreeMap<&str, Vec<&SymbolTableEntry>> = BTreeMap::new();
+        for ((section, address), mut entries) in by_address {
+            entries.sort_by(|x, y| compare_canonical(x, y));
+            canonical.entry(section).or_default().push(entries[0]);
+            if entries.len() > 1 {
+                groups.push(AliasGroup {
+                    section: section.to_string(),
+                    address,
+                    size: entries[0].alignment_or_size,
+                    size_inferred: entries[0].size_inferred,
+                    canonical: entries[0].name.clone(),
+                    aliases: entries[1..].iter().map(|x| x.name.clone()).collect(),
+                });
+            }
+        }
+
+        // Canonical symbols are already in address order, so only later symbols can start inside
+        let mut overlaps = Vec::new();
+        for (section, symbols) in &canonical {
+            for (i, first) in symbols.iter().enumerate() {
+                let end = first.address as u64 + first.alignment_or_size as u64;
+                for second in symbols[i + 1..].iter().take_while(|x| (x.address as u64) < end) {
+                    if second.alignment_or_size == 0 {
+                        continue;
+                    }
+                    let second_end = second.address as u64 + second.alignment_or_size as u64;
+                    overlaps.push(Overlap {
+                        section: section.to_string(),
+                        first: first.name.clone(),
+                        second: second.name.clone(),
+                        address: second.address,
+                        size: (end.min(second_end) - second.address as u64) as u32,
+                    });
+                }
+            }
+        }
+
+        let mut summed: BTreeMap<&str, u32> = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated()) {
+            *summed.entry(&entry.section).or_insert(0) += entry.alignment_or_size;
+        }
+        let sections = symbol_table
+            .section_sizes()
+            .into_iter()
+            .map(|(section, deduplicated)| SectionTotal {
+                summed: summed.get(section.as_str()).copied().unwrap_or(0),
+                section,
+                deduplicated,
+            })
+            .collect();
+
+        AliasReport {
+            groups,
+            overlaps,
+            sections,
+        }
+    }
+
+    /// Gets the number of bytes that summing symbol sizes would count more than once
+    pub fn double_counted(&self) -> u32 {
+        self.sections.iter().map(|x| x.summed - x.deduplicated).sum()
+    }
+}
+
+/// Orders symbols at the same address so the best name for the group comes first
+fn compare_canonical(x: &SymbolTableEntry, y: &SymbolTableEntry) -> Ordering {
+    let typed = |x: &SymbolTableEntry| matches!(x.flags.symbol_type, SymbolType::Function | SymbolType::Object);
+    let global = |x: &SymbolTableEntry| x.flags.scope == SymbolScope::Global;
+    let strong = |x: &SymbolTableEntry| x.flags.weakness == SymbolWeakness::Strong;
+    let underscores = |x: &SymbolTableEntry| x.name.chars().take_while(|&c| c == '_').count();
+    y.alignment_or_size
+        .cmp(&x.alignment_or_size)
+        .then(typed(y).cmp(&typed(x)))
+        .then(global(y).cmp(&global(x)))
+        .then(strong(y).cmp(&strong(x)))
+        .then(underscores(x).cmp(&underscores(y)))
+        .then(x.name.cmp(&y.name))
+}
+
+impl fmt::Display for AliasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Aliases:")?;
+        writeln!(f, "  {:8} {:>8} {:10} {:30} Aliases", "Address", "Size", "Section", "Canonical")?;
+        for group in &self.groups {
+            writeln!(
+                f,
+                "  {:08x} {:>8} {:10} {:30} {}",
+                group.address,
+                size_string(group.size, group.size_inferred),
+                group.section,
+                group.canonical,
+                group.aliases.join(", ")
+            )?;
+        }
+        writeln!(f, "Overlaps:")?;
+        writeln!(f, "  {:8} {:>8} {:10} Symbols", "Address", "Size", "Section")?;
+        for overlap in &self.overlaps {
+            writeln!(
+                f,
+                "  {:08x} {:>8} {:10} {} / {}",
+                overlap.address, overlap.size, overlap.section, overlap.first, overlap.second
+            )?;
+        }
+        for section in self.sections.iter().filter(|x| x.summed != x.deduplicated) {
+            writeln!(
+                f,
+                "{}: {} bytes summed, {} bytes placed",
+                section.section, section.summed, section.deduplicated
+            )?;
+        }
+        writeln!(f, "Total double counted: {} bytes", self.double_counted())
+    }
+}
+
+impl Report for AliasReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Aliased Symbols";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Aliased Symbols";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Address";
+                                th: "Size";
+                                th: "Section";
+                                th: "Canonical";
+                                th: "Aliases";
+                            }
+                        }
+                        tbody {
+                            @ for group in &self.groups {
+                                tr {
+                                    td: format!("{:08x}", group.address);
+                                    td: size_string(group.size, group.size_inferred);
+                                    td: &group.section;
+                                    td: &group.canonical;
+                                    td: group.aliases.join(", ");
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Overlapping Symbols";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Address";
+                                th: "Size";
+                                th: "Section";
+                                th: "First";
+                                th: "Second";
+                            }
+                        }
+                        tbody {
+                            @ for overlap in &self.overlaps {
+                                tr {
+                                    td: format!("{:08x}", overlap.address);
+                                    td: overlap.size;
+                                    td: &overlap.section;
+                                    td: &overlap.first;
+                                    td: &overlap.second;
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Total double counted: {} bytes", self.double_counted());
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,section,address,size,symbol,other,size_inferred\n");
+        for group in &self.groups {
+            for alias in &group.aliases {
+                csv += &format!(
+                    "alias,{},{:08x},{},{},{},{}\n",
+                    csv_field(&group.section),
+                    group.address,
+                    group.size,
+                    csv_field(&group.canonical),
+                    csv_field(alias),
+                    group.size_inferred
+                );
+            }
+        }
+        for overlap in &self.overlaps {
+            csv += &format!(
+                "overlap,{},{:08x},{},{},{},false\n",
+                csv_field(&overlap.section),
+                overlap.address,
+                overlap.size,
+                csv_field(&overlap.first),
+                csv_field(&overlap.second)
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_alias_groups() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00004000 l    d  .data	00000000 .data",
+        "00004000  w      .data	00000000 data_start",
+        "00004000 g       .data	00000000 __data_start",
+        "00004008 g     O .data	00000004 counter",
+        "00004008 l     O .data	00000004 _counter_alias",
+    ]));
+    let report = AliasReport::new(&symbol_table);
+    assert_eq!(
+        report.groups,
+        vec![
+            AliasGroup {
+                section: ".data".to_string(),
+                address: 0x4000,
+                size: 0,
+                size_inferred: false,
+                canonical: "__data_start".to_string(),
+                aliases: vec!["data_start".to_string()],
+            },
+            AliasGroup {
+                section: ".data".to_string(),
+                address: 0x4008,
+                size: 4,
+                size_inferred: false,
+                canonical: "counter".to_string(),
+                aliases: vec!["_counter_alias".to_string()],
+            },
+        ]
+    );
+    assert!(report.overlaps.is_empty());
+    assert_eq!(
+        report.sections,
+        vec![SectionTotal {
+            section: ".data".to_string(),
+            summed: 8,
+            deduplicated: 4
+        }]
+    );
+    assert_eq!(report.double_counted(), 4);
+}
+
+#[test]
+fn test_canonical_prefers_sized_symbols() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00001000 g       .text	00000000 __text_start",
+        "00001000 l     F .text	00000020 reset_handler",
+    ]));
+    let report = AliasReport::new(&symbol_table);
+    assert_eq!(report.groups[0].canonical, "reset_handler");
+    assert_eq!(report.groups[0].aliases, vec!["__text_start".to_string()]);
+}
+
+#[test]
+fn test_overlapping_symbols() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00001000 g     F .text	00000040 outer",
+        "00001010 l     F .text	00000010 inner",
+        "00001030 g     F .text	00000020 straddling",
+        "00001060 g     F .text	00000010 after",
+    ]));
+    let report = AliasReport::new(&symbol_table);
+    assert!(report.groups.is_empty());
+    assert_eq!(
+        report.overlaps,
+        vec![
+            Overlap {
+                section: ".text".to_string(),
+                first: "outer".to_string(),
+                second: "inner".to_string(),
+                address: 0x1010,
+                size: 0x10
+            },
+            Overlap {
+                section: ".text".to_string(),
+                first: "outer".to_string(),
+                second: "straddling".to_string(),
+                address: 0x1030,
+                size: 0x10
+            },
+        ]
+    );
+    assert_eq!(report.sections[0].summed, 0x80);
+    assert_eq!(report.sections[0].deduplicated, 0x60);
+}
+
+#[test]
+fn test_csv_output() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00004000  w      .data	00000000 data_start",
+        "00004000 g       .data	00000000 __data_start",
+    ]));
+    let report = AliasReport::new(&symbol_table);
+    assert_eq!(
+        report.to_csv(),
+        "kind,section,address,size,symbol,other,size_inferred\nalias,.data,00004000,0,__data_start,data_start,false\n"
+    );
+}
@@ -0,0 +1,271 @@
+use serde::Deserialize;
+
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use crate::binary_utils::parser::symbol_table::{covered_size, SymbolTable, SymbolTableEntry};
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// Size limits that a symbol table is checked against, typically loaded from a TOML budget file:
+///
+/// ```toml
+/// exit_code = 2
+/// image = 0x40000
+///
+/// [sections]
+/// ".text" = 0x30000
+/// ".bss" = 0x4000
+///
+/// [[regions]]
+/// name = "RAM"
+/// origin = 0x20000000
+/// length = 0x5000
+///
+/// [[symbols]]
+/// pattern = "print_*"
+/// max_size = 1024
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// Process exit code to use when any limit is exceeded
+    #[serde(default = "default_exit_code")]
+    pub exit_code: i32,
+    /// Maximum size of the whole image
+    pub image: Option<u32>,
+    /// Maximum size per section name
+    #[serde(default)]
+    pub sections: BTreeMap<String, u32>,
+    /// Maximum size per memory region
+    #[serde(default)]
+    pub regions: Vec<RegionBudget>,
+    /// Maximum sizes for symbols matching a wildcard pattern
+    #[serde(default)]
+    pub symbols: Vec<SymbolBudget>,
+}
+
+/// A memory region covering the address range [origin, origin + length)
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RegionBudget {
+    pub name: String,
+    pub origin: u32,
+    pub length: u32,
+    /// Maximum size used inside the region. Defaults to the region length
+    pub max_size: Option<u32>,
+}
+
+/// Limits for all symbols whose name matches a wildcard pattern
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SymbolBudget {
+    pub pattern: String,
+    /// Maximum size of each individual matching symbol
+    pub max_size: Option<u32>,
+    /// Maximum combined size of all matching symbols
+    pub max_total: Option<u32>,
+}
+
+fn default_exit_code() -> i32 {
+    2
+}
+
+/// The kind of limit that a budget check was evaluated for
+#[derive(Debug, PartialEq)]
+pub enum BudgetLimit {
+    Image,
+    Section(String),
+    Region(String),
+    Symbol(String),
+    SymbolPattern(String),
+}
+
+/// The outcome of evaluating a single budget limit
+#[derive(Debug, PartialEq)]
+pub struct BudgetCheck {
+    pub limit: BudgetLimit,
+    pub used: u32,
+    pub max: u32,
+    /// Whether the used size includes symbol sizes inferred from the next symbol
+    pub inferred: bool,
+}
+
+/// The result of evaluating a symbol table against a budget
+#[derive(Debug, PartialEq)]
+pub struct BudgetReport {
+    pub checks: Vec<BudgetCheck>,
+    pub exit_code: i32,
+}
+
+impl Budget {
+    /// Loads a budget from a TOML file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename of the budget file
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let budget = Budget::from_file("budgets.toml")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(filename)?.parse()
+    }
+
+    /// Evaluates a symbol table against every limit in the budget
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to check
+    pub fn evaluate(&self, symbol_table: &SymbolTable) -> BudgetReport {
+        let mut checks = Vec::new();
+        if let Some(max) = self.image {
+            checks.push(BudgetCheck {
+                limit: BudgetLimit::Image,
+                used: symbol_table.total_size(),
+                max,
+                inferred: symbol_table
+                    .iter()
+                    .any(|x| x.is_allocated() && !x.is_thread_local() && x.size_inferred),
+            });
+        }
+
+        let section_sizes = symbol_table.section_sizes();
+        for (section, &max) in &self.sections {
+            checks.push(BudgetCheck {
+                limit: BudgetLimit::Section(section.clone()),
+                used: section_sizes.get(section).copied().unwrap_or(0),
+                max,
+                inferred: symbol_table.iter().any(|x| &x.section == section && x.size_inferred),
+            });
+        }
+
+        for region in &self.regions {
+            let end = region.origin as u64 + region.length as u64;
+            let symbols: Vec<&SymbolTableEntry> = symbol_table
+                .iter()
+                .filter(|x| x.is_allocated() && !x.is_thread_local())
+                .filter(|x| x.address >= region.origin && (x.address as u64) < end)
+                .collect();
+            checks.push(BudgetCheck {
+                limit: BudgetLimit::Region(region.name.clone()),
+                used: covered_size(symbols.iter().copied()),
+                max: region.max_size.unwrap_or(region.length),
+                inferred: symbols.iter().any(|x| x.size_inferred),
+            });
+        }
+
+        for symbols in &self.symbols {
+            let matching = symbol_table
+                .iter()
+                .filter(|x| x.is_allocated())
+                .filter(|x| string_utils::matches_wildcard(&symbols.pattern, &x.name));
+            let mut matched = Vec::new();
+            for entry in matching {
+                matched.push(entry);
+                if let Some(max) = symbols.max_size {
+                    checks.push(BudgetCheck {
+                        limit: BudgetLimit::Symbol(entry.name.clone()),
+                        used: entry.alignment_or_size,
+                        max,
+                        inferred: entry.size_inferred,
+                    });
+                }
+            }
+            if let Some(max) = symbols.max_total {
+                checks.push(BudgetCheck {
+                    limit: BudgetLimit::SymbolPattern(symbols.pattern.clone()),
+                    inferred: matched.iter().any(|x| x.size_inferred),
+                    used: covered_size(matched),
+                    max,
+                });
+            }
+        }
+
+        BudgetReport {
+            checks,
+            exit_code: self.exit_code,
+        }
+    }
+}
+
+impl std::str::FromStr for Budget {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl BudgetCheck {
+    /// Checks if the used size exceeds the limit
+    pub fn is_exceeded(&self) -> bool {
+        self.used > self.max
+    }
+}
+
+impl BudgetReport {
+    /// Gets all checks whose limit was exceeded
+    pub fn violations(&self) -> impl Iterator<Item = &BudgetCheck> {
+        self.checks.iter().filter(|x| x.is_exceeded())
+    }
+
+    /// Checks if every limit in the budget was met
+    pub fn passed(&self) -> bool {
+        self.violations().next().is_none()
+    }
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Image => write!(f, "image"),
+            BudgetLimit::Section(name) => write!(f, "section {}", name),
+            BudgetLimit::Region(name) => write!(f, "region {}", name),
+            BudgetLimit::Symbol(name) => write!(f, "symbol {}", name),
+            BudgetLimit::SymbolPattern(pattern) => write!(f, "symbols {}", pattern),
+        }
+    }
+}
+
+impl fmt::Display for BudgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .checks
+            .iter()
+            .map(|x| x.limit.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for check in &self.checks {
+            let status = if check.is_exceeded() { "FAIL" } else { "PASS" };
+            writeln!(
+                f,
+                "{} {:width$} {:>10} / {:>10} ({:.1}%){}",
+                status,
+                check.limit.to_string(),
+                check.used,
+                check.max,
+                usage_percent(check.used, check.max),
+                if check.inferred { " (inferred)" } else { "" },
+                width = width
+            )?;
+        }
+        let violations = self.violations().count();
+        if violations == 0 {
+            writeln!(f, "All {} budget checks passed", self.checks.len())
+        } else {
+            writeln!(f, "{} of {} budget checks exceeded", violations, self.checks.len())
+        }
+    }
+}
+
+/// Calculates how much of a limit is used. Unlike `report::percent`, which treats
+/// an empty total as zero, any use of a zero sized limit counts as fully used so
+/// that it never reads as being within budget.
+fn usage_percent(used: u32, max: u32) -> f64 {
+    if max == 0 {
+        if used == 0 { 0.0 } else { 100.0 }
+    } else {
+        used as f64 * 100.0 / max as f64
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+fn sample_table() -> SymbolTable {
+    let lines: Vec<String> = vec![
+        "08000000 g     F .text	00000100 main",
+        "08000100 l     F .text	00000040 print_hello",
+        "08000140 l     F .text	00000020 print_world",
+        "20000000 l     O .bss	00000400 print_buffer",
+        "20000400 g     O .data	00000010 counter",
+        "00000000       F *UND*	00000000 printf",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    SymbolTable::from_lines(&lines)
+}
+
+#[test]
+fn test_parse_budget() {
+    let budget: Budget = "
+        image = 4096
+
+        [sections]
+        \".text\" = 1024
+
+        [[regions]]
+        name = \"RAM\"
+        origin = 0x20000000
+        length = 0x800
+
+        [[symbols]]
+        pattern = \"print_*\"
+        max_total = 256
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(budget.exit_code, 2);
+    assert_eq!(budget.image, Some(4096));
+    assert_eq!(budget.sections.get(".text"), Some(&1024));
+    assert_eq!(
+        budget.regions,
+        vec![RegionBudget {
+            name: "RAM".to_string(),
+            origin: 0x20000000,
+            length: 0x800,
+            max_size: None
+        }]
+    );
+    assert_eq!(budget.symbols[0].max_size, None);
+    assert_eq!(budget.symbols[0].max_total, Some(256));
+}
+
+#[test]
+fn test_parse_budget_rejects_unknown_keys() {
+    assert!("imag = 4096".parse::<Budget>().is_err());
+}
+
+#[test]
+fn test_budget_within_limits_passes() {
+    let budget: Budget = "
+        image = 0x1000
+        [sections]
+        \".text\" = 0x200
+        \".bss\" = 0x400
+    "
+    .parse()
+    .unwrap();
+    let report = budget.evaluate(&sample_table());
+    assert!(report.passed());
+    assert_eq!(
+        report.checks[0],
+        BudgetCheck {
+            limit: BudgetLimit::Image,
+            used: 0x570,
+            max: 0x1000,
+            inferred: false
+        }
+    );
+}
+
+#[test]
+fn test_budget_reports_exceeded_limits() {
+    let budget: Budget = "
+        exit_code = 3
+        [sections]
+        \".text\" = 0x100
+        [[regions]]
+        name = \"RAM\"
+        origin = 0x20000000
+        length = 0x1000
+        max_size = 0x400
+        [[symbols]]
+        pattern = \"print_*\"
+        max_size = 0x30
+    "
+    .parse()
+    .unwrap();
+    let report = budget.evaluate(&sample_table());
+    assert!(!report.passed());
+    assert_eq!(report.exit_code, 3);
+    let violations: Vec<&BudgetLimit> = report.violations().map(|x| &x.limit).collect();
+    assert_eq!(
+        violations,
+        vec![
+            &BudgetLimit::Section(".text".to_string()),
+            &BudgetLimit::Region("RAM".to_string()),
+            &BudgetLimit::Symbol("print_hello".to_string()),
+            &BudgetLimit::Symbol("print_buffer".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_region_ignores_thread_local_offsets() {
+    let lines: Vec<String> = vec![
+        "00000000 g       .tbss	00000080 scratch",
+        "00000010 g     O .data	00000010 flags",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let budget: Budget = "
+        [[regions]]
+        name = \"LOW\"
+        origin = 0
+        length = 0x100
+    "
+    .parse()
+    .unwrap();
+    let report = budget.evaluate(&SymbolTable::from_lines(&lines));
+    assert_eq!(report.checks[0].used, 0x10);
+}
+
+#[test]
+fn test_zero_limit_is_fully_used() {
+    assert_eq!(usage_percent(0, 0), 0.0);
+    assert_eq!(usage_percent(4, 0), 100.0);
+    assert_eq!(usage_percent(1, 4), 25.0);
+}
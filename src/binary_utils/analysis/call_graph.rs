@@ -0,0 +1,441 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::parser::call_graph_info::{CallGraphInfo, INDIRECT_CALL};
+use crate::binary_utils::parser::disassembly::Disassembly;
+use crate::binary_utils::parser::stack_usage::same_file;
+use crate::binary_utils::parser::symbol_table::{SymbolTable, SymbolTableEntry, SymbolType};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// A function in the call graph along with the functions it calls and is called by
+#[derive(Debug)]
+pub struct FunctionNode<'a> {
+    /// Position of the node in the graph, which callers and callees refer to
+    pub index: usize,
+    /// Label of the function. Local functions in different files can share a name, so only the
+    /// section and address tell them apart
+    pub name: String,
+    pub section: String,
+    pub address: u32,
+    /// Size from the symbol table, or of the disassembled instructions if the symbol has no size
+    pub size: u32,
+    /// The symbol table entry of the function, if one was found
+    pub entry: Option<&'a SymbolTableEntry>,
+    pub callees: BTreeSet<usize>,
+    pub callers: BTreeSet<usize>,
+}
+
+/// Static call graph built from the direct calls and tail calls in a disassembly, optionally
+/// combined with GCC call graph info. Calls through a register or a function pointer table can't
+/// be followed and are not part of the graph
+#[derive(Debug)]
+pub struct CallGraph<'a> {
+    nodes: Vec<FunctionNode<'a>>,
+    /// Nodes by address. Sections of an object file all start at zero, so several can share one
+    by_address: BTreeMap<u32, Vec<usize>>,
+    by_name: BTreeMap<String, Vec<usize>>,
+    /// Nodes of the GCC call graph info titles, see `add_call_graph_info`
+    by_title: BTreeMap<String, usize>,
+}
+
+impl<'a> CallGraph<'a> {
+    /// Builds the call graph of a disassembly. Call targets are resolved by address, so a target
+    /// that objdump labels with an offset from the nearest symbol still finds the right function
+    ///
+    /// # Arguments
+    /// * 'disassembly' - The disassembly to read calls from
+    /// * 'symbol_table' - Symbol table of the same binary, used to find each function's entry and size
+    pub fn new(disassembly: &Disassembly, symbol_table: &'a SymbolTable) -> Self {
+        let mut entries: BTreeMap<(&str, u32), Vec<&SymbolTableEntry>> = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated()) {
+            entries.entry((&entry.section, entry.address)).or_default().push(entry);
+        }
+
+        let mut graph = CallGraph {
+            nodes: Vec::new(),
+            by_address: BTreeMap::new(),
+            by_name: BTreeMap::new(),
+            by_title: BTreeMap::new(),
+        };
+        for function in disassembly.iter() {
+            let entry = entries
+                .get(&(function.section.as_str(), function.address))
+                .and_then(|x| {
+                    x.iter()
+                        .find(|x| x.name == function.name || x.flags.symbol_type == SymbolType::Function)
+                })
+                .copied();
+            let size = match entry {
+                Some(x) if x.alignment_or_size > 0 => x.alignment_or_size,
+                _ => function.size(),
+            };
+            graph.add_node(&function.name, &function.section, function.address, size, entry);
+        }
+
+        for (caller, function) in disassembly.iter().enumerate() {
+            for instruction in function.instructions.iter().filter(|x| x.is_call() || x.is_jump()) {
+                for (address, _) in instruction.references() {
+                    let callee = match graph.resolve(&function.section, address) {
+                        Some(x) => x,
+                        None => continue,
+                    };
+                    // Jumps back to the start of the function are loops rather than recursion
+                    if callee == caller && !instruction.is_call() {
+                        continue;
+                    }
+                    graph.add_call(caller, callee);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Adds the calls from GCC `-fcallgraph-info` files to the graph. Nodes are matched to
+    /// functions by name and, for static functions, source file, the same way `.su` files are
+    /// attached to the symbol table. Functions that aren't in the disassembly are added with the
+    /// size of their symbol, so the graph can also be built from the compiler output alone. Calls
+    /// through function pointers are left out
+    ///
+    /// # Arguments
+    /// * 'info' - Call graph info of the compiled source files
+    /// * 'symbol_table' - Symbol table of the binary, used to find each new function's entry and size
+    pub fn add_call_graph_info(&mut self, info: &CallGraphInfo, symbol_table: &'a SymbolTable) {
+        let mut functions: BTreeMap<&str, Vec<(Option<&str>, &'a SymbolTableEntry)>> = BTreeMap::new();
+        for (file, entry) in symbol_table.with_files() {
+            if entry.is_allocated() && entry.flags.symbol_type == SymbolType::Function {
+                functions.entry(&entry.name).or_default().push((file, entry));
+            }
+        }
+        // Every defined function that is already in the graph, even without calls, so its frame
+        // can be found by title
+        for node in info.nodes.iter().filter(|x| !x.external) {
+            self.add_title(&node.title, info, &functions, false);
+        }
+        for edge in info.edges.iter().filter(|x| x.callee != INDIRECT_CALL) {
+            let caller = self.add_title(&edge.caller, info, &functions, true);
+            let callee = self.add_title(&edge.callee, info, &functions, true);
+            if let (Some(caller), Some(callee)) = (caller, callee) {
+                self.add_call(caller, callee);
+            }
+        }
+    }
+
+    /// Finds the node of a call graph info title, or adds it if the function isn't in the graph yet
+    fn add_title(
+        &mut self,
+        title: &str,
+        info: &CallGraphInfo,
+        functions: &BTreeMap<&str, Vec<(Option<&str>, &'a SymbolTableEntry)>>,
+        create: bool,
+    ) -> Option<usize> {
+        if let Some(&index) = self.by_title.get(title) {
+            return Some(index);
+        }
+        let node = info.find(title);
+        let name = node.map_or(title, |x| x.name.as_str());
+        let file = node.and_then(|x| x.file());
+        let candidates = functions.get(name).map_or(&[][..], |x| x.as_slice());
+        let entry = match file {
+            Some(file) => candidates.iter().find(|(x, _)| x.is_some_and(|x| same_file(x, file))),
+            None => candidates.iter().find(|(x, _)| x.is_none()),
+        }
+        .or_else(|| candidates.first())
+        .map(|&(_, entry)| entry);
+
+        let existing = match entry {
+            Some(entry) => self.find_in_section(&entry.section, entry.address).map(|x| x.index),
+            None => self.by_name.get(name).map(|x| x[0]),
+        };
+        let index = match existing {
+            Some(index) => index,
+            None if create => {
+                let (section, address) = entry.map_or(("", 0), |x| (x.section.as_str(), x.address));
+                self.add_node(name, section, address, entry.map_or(0, |x| x.alignment_or_size), entry)
+            }
+            None => return None,
+        };
+        self.by_title.insert(title.to_string(), index);
+        Some(index)
+    }
+
+    /// Adds a function without any calls and gets its index
+    fn add_node(&mut self, name: &str, section: &str, address: u32, size: u32, entry: Option<&'a SymbolTableEntry>) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(FunctionNode {
+            index,
+            name: name.to_string(),
+            section: section.to_string(),
+            address,
+            size,
+            entry,
+            callees: BTreeSet::new(),
+            callers: BTreeSet::new(),
+        });
+        self.by_address.entry(address).or_default().push(index);
+        self.by_name.entry(name.to_string()).or_default().push(index);
+        index
+    }
+
+    /// Finds the function a call goes to, preferring one in the same section as the caller
+    fn resolve(&self, section: &str, address: u32) -> Option<usize> {
+        let nodes = self.by_address.get(&address)?;
+        nodes
+            .iter()
+            .find(|&&x| self.nodes[x].section == section)
+            .or_else(|| nodes.first())
+            .copied()
+    }
+
+    /// Adds an edge from a caller to a callee
+    fn add_call(&mut self, caller: usize, callee: usize) {
+        self.nodes[caller].callees.insert(callee);
+        self.nodes[callee].callers.insert(caller);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FunctionNode<'a>> {
+        self.nodes.iter()
+    }
+
+    /// Gets a function by its index
+    pub fn node(&self, index: usize) -> &FunctionNode<'a> {
+        &self.nodes[index]
+    }
+
+    /// Finds a function by name. If several local functions share the name, the first one in the
+    /// disassembly is used
+    pub fn find(&self, name: &str) -> Option<&FunctionNode<'a>> {
+        self.by_name.get(name).map(|x| &self.nodes[x[0]])
+    }
+
+    /// Finds the function that starts at an address of a section
+    pub fn find_in_section(&self, section: &str, address: u32) -> Option<&FunctionNode<'a>> {
+        self.by_address
+            .get(&address)?
+            .iter()
+            .map(|&x| &self.nodes[x])
+            .find(|x| x.section == section)
+    }
+
+    /// Finds the node of a title in the call graph info added with `add_call_graph_info`
+    pub fn find_title(&self, title: &str) -> Option<&FunctionNode<'a>> {
+        self.by_title.get(title).map(|&x| &self.nodes[x])
+    }
+
+    /// Finds the function that starts at an address, or else the one that covers it
+    pub fn find_address(&self, address: u32) -> Option<&FunctionNode<'a>> {
+        if let Some(nodes) = self.by_address.get(&address) {
+            return Some(&self.nodes[nodes[0]]);
+        }
+        let (_, nodes) = self.by_address.range(..address).next_back()?;
+        nodes
+            .iter()
+            .map(|&x| &self.nodes[x])
+            .find(|x| (address as u64) < x.address as u64 + x.size as u64)
+    }
+
+    /// Gets the functions a function calls directly
+    pub fn callees(&self, node: &FunctionNode) -> Vec<&FunctionNode<'a>> {
+        node.callees.iter().map(|&x| &self.nodes[x]).collect()
+    }
+
+    /// Gets the functions that call a function directly
+    pub fn callers(&self, node: &FunctionNode) -> Vec<&FunctionNode<'a>> {
+        node.callers.iter().map(|&x| &self.nodes[x]).collect()
+    }
+
+    /// Gets the index of every function that can be reached by following calls from a function.
+    /// The function itself is only included if it is recursive
+    pub fn reachable(&self, node: &FunctionNode) -> BTreeSet<usize> {
+        let mut reachable = BTreeSet::new();
+        let mut pending: Vec<usize> = node.callees.iter().copied().collect();
+        while let Some(next) = pending.pop() {
+            if reachable.insert(next) {
+                pending.extend(self.nodes[next].callees.iter().copied());
+            }
+        }
+        reachable
+    }
+
+    /// Gets the total size of a function and every function it can reach, counting each once
+    ///
+    /// # Arguments
+    /// * 'node' - The function to start from
+    /// * 'reachable' - The functions it can reach, see `reachable`
+    pub fn cumulative_size(&self, node: &FunctionNode, reachable: &BTreeSet<usize>) -> u32 {
+        node.size
+            + reachable
+                .iter()
+                .filter(|&&x| x != node.index)
+                .map(|&x| self.nodes[x].size)
+                .sum::<u32>()
+    }
+
+    /// Gets the sorted names of a set of functions
+    pub fn names(&self, nodes: &BTreeSet<usize>) -> Vec<String> {
+        let mut names: Vec<String> = nodes.iter().map(|&x| self.nodes[x].name.clone()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// Call counts and reachable code size of a single function
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FunctionCalls {
+    pub function: String,
+    pub size: u32,
+    pub callers: Vec<String>,
+    pub callees: Vec<String>,
+    /// Number of functions reachable through calls
+    pub reachable: usize,
+    /// Size of the function plus every function it can reach
+    pub cumulative_size: u32,
+}
+
+/// The call graph as a table of functions, largest cumulative size first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CallGraphReport {
+    pub functions: Vec<FunctionCalls>,
+}
+
+impl CallGraphReport {
+    /// Creates a report of every function in the call graph, or only of the given function and
+    /// everything it can reach
+    ///
+    /// # Arguments
+    /// * 'graph' - The call graph to report on
+    /// * 'root' - Optional name of the function to start from
+    pub fn new(graph: &CallGraph, root: Option<&str>) -> Self {
+        let nodes: Vec<&FunctionNode> = match root.map(|x| graph.find(x)) {
+            Some(Some(root)) => std::iter::once(root)
+                .chain(graph.reachable(root).into_iter().filter(|&x| x != root.index).map(|x| graph.node(x)))
+                .collect(),
+            Some(None) => Vec::new(),
+            None => graph.iter().collect(),
+        };
+        let mut functions: Vec<FunctionCalls> = nodes
+            .into_iter()
+            .map(|node| {
+                let reachable = graph.reachable(node);
+                FunctionCalls {
+                    function: node.name.clone(),
+                    size: node.size,
+                    callers: graph.names(&node.callers),
+                    callees: graph.names(&node.callees),
+                    reachable: reachable.len(),
+                    cumulative_size: graph.cumulative_size(node, &reachable),
+                }
+            })
+            .collect();
+        // Keep the root function first when reporting on a single function
+        let skip = root.map_or(0, |_| 1).min(functions.len());
+        functions[skip..].sort_by(|x, y| {
+            y.cumulative_size
+                .cmp(&x.cumulative_size)
+                .then(x.function.cmp(&y.function))
+        });
+        CallGraphReport { functions }
+    }
+}
+
+impl fmt::Display for CallGraphReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .functions
+            .iter()
+            .map(|x| x.function.len())
+            .max()
+            .unwrap_or(0)
+            .max("Function".len());
+        writeln!(
+            f,
+            "{:w$} {:>8} {:>10} {:>9} {:>8} Callees",
+            "Function",
+            "Size",
+            "Cumulative",
+            "Reachable",
+            "Callers",
+            w = width
+        )?;
+        for function in &self.functions {
+            writeln!(
+                f,
+                "{:w$} {:>8} {:>10} {:>9} {:>8} {}",
+                function.function,
+                function.size,
+                function.cumulative_size,
+                function.reachable,
+                function.callers.len(),
+                function.callees.join(", "),
+                w = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for CallGraphReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Call Graph";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Call Graph";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Function";
+                                th: "Size";
+                                th: "Cumulative Size";
+                                th: "Reachable";
+                                th: "Callers";
+                                th: "Callees";
+                            }
+                        }
+                        tbody {
+                            @ for function in &self.functions {
+                                tr {
+                                    td: &function.function;
+                                    td: function.size;
+                                    td: function.cumulative_size;
+                                    td: function.reachable;
+                                    td: function.callers.join(", ");
+                                    td: function.callees.join(", ");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("function,size,cumulative_size,reachable,callers,callees\n");
+        for function in &self.functions {
+            csv += &format!(
+                "{},{},{},{},{},{}\n",
+                csv_field(&function.function),
+                function.size,
+                function.cumulative_size,
+                function.reachable,
+                csv_field(&function.callers.join(" ")),
+                csv_field(&function.callees.join(" "))
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,128 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn disassembly() -> Disassembly {
+    Disassembly::from_lines(&lines(&[
+        "Disassembly of section .text:",
+        "",
+        "00008000 <main>:",
+        "    8000:	b580      	push	{r7, lr}",
+        "    8002:	f000 f805 	bl	8010 <log_message>",
+        "    8006:	f000 f80b 	bl	8020 <format>",
+        "    800a:	e7f9      	b.n	8000 <main>",
+        "",
+        "00008010 <log_message>:",
+        "    8010:	b508      	push	{r3, lr}",
+        "    8012:	f000 f805 	bl	8020 <format>",
+        "    8016:	e8bd 4008 	ldmia.w	sp!, {r3, lr}",
+        "    801a:	f000 b809 	b.w	8030 <write>",
+        "",
+        "00008020 <format>:",
+        "    8020:	b508      	push	{r3, lr}",
+        "    8022:	f7ff fffd 	bl	8020 <format>",
+        "    8026:	bd08      	pop	{r3, pc}",
+        "",
+        "00008030 <write>:",
+        "    8030:	4770      	bx	lr",
+    ]))
+}
+
+fn symbol_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "00008000 g     F .text	0000000c main",
+        "00008010 g     F .text	00000010 log_message",
+        "00008020 g     F .text	00000008 format",
+        "00008030 g     F .text	00000002 write",
+    ]))
+}
+
+#[test]
+fn test_callers_and_callees() {
+    let disassembly = disassembly();
+    let symbol_table = symbol_table();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+
+    let main = graph.find("main").unwrap();
+    assert_eq!(graph.names(&main.callees), vec!["format", "log_message"]);
+    let format = graph.find("format").unwrap();
+    assert_eq!(graph.names(&format.callers), vec!["format", "log_message", "main"]);
+    let callers: Vec<&str> = graph.callers(graph.find("write").unwrap()).iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(callers, vec!["log_message"]);
+    assert_eq!(graph.find("main").unwrap().entry.unwrap().name, "main");
+}
+
+#[test]
+fn test_reachable_and_cumulative_size() {
+    let disassembly = disassembly();
+    let symbol_table = symbol_table();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+
+    let (main, format) = (graph.find("main").unwrap(), graph.find("format").unwrap());
+    let (main_reachable, format_reachable) = (graph.reachable(main), graph.reachable(format));
+    assert_eq!(graph.names(&main_reachable), vec!["format", "log_message", "write"]);
+    assert_eq!(graph.names(&format_reachable), vec!["format"]);
+    assert_eq!(graph.cumulative_size(main, &main_reachable), 0xc + 0x10 + 0x8 + 0x2);
+    assert_eq!(graph.cumulative_size(format, &format_reachable), 0x8);
+}
+
+#[test]
+fn test_report_from_root() {
+    let disassembly = disassembly();
+    let symbol_table = symbol_table();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = CallGraphReport::new(&graph, Some("log_message"));
+    let functions: Vec<&str> = report.functions.iter().map(|x| x.function.as_str()).collect();
+    assert_eq!(functions, vec!["log_message", "format", "write"]);
+    assert_eq!(report.functions[0].cumulative_size, 0x1a);
+    assert_eq!(report.functions[0].reachable, 2);
+}
+
+#[test]
+fn test_add_call_graph_info() {
+    let disassembly = disassembly();
+    let symbol_table = symbol_table();
+    let mut graph = CallGraph::new(&disassembly, &symbol_table);
+    let info = CallGraphInfo::from_lines(&lines(&[
+        r#"node: { title: "write" label: "write\nlog.c:12:6\n8 bytes (static)\n0 dynamic objects" }"#,
+        r#"node: { title: "log.c:flush" label: "flush\nlog.c:3:13\n16 bytes (static)\n0 dynamic objects" }"#,
+        r#"edge: { sourcename: "write" targetname: "log.c:flush" label: "log.c:13:3" }"#,
+        r#"edge: { sourcename: "write" targetname: "__indirect_call" label: "log.c:14:3" }"#,
+    ]));
+    graph.add_call_graph_info(&info, &symbol_table);
+
+    let callees: Vec<&str> = graph.callees(graph.find("write").unwrap()).iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(callees, vec!["flush"]);
+    assert!(graph.find("__indirect_call").is_none());
+    assert_eq!(graph.reachable(graph.find("main").unwrap()).len(), 4);
+}
+
+#[test]
+fn test_local_functions_with_the_same_name() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "Disassembly of section .text:",
+        "",
+        "00008000 <main>:",
+        "    8000:	f000 f806 	bl	8010 <init>",
+        "    8004:	f000 f808 	bl	8018 <init>",
+        "",
+        "00008010 <init>:",
+        "    8010:	4770      	bx	lr",
+        "",
+        "00008018 <init>:",
+        "    8018:	b508      	push	{r3, lr}",
+        "    801a:	bd08      	pop	{r3, pc}",
+    ]));
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 uart.c",
+        "00008010 l     F .text	00000002 init",
+        "00000000 l    df *ABS*	00000000 timer.c",
+        "00008018 l     F .text	00000004 init",
+        "00008000 g     F .text	00000008 main",
+    ]));
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let main = graph.find("main").unwrap();
+    let callees: Vec<(u32, u32)> = graph.callees(main).iter().map(|x| (x.address, x.size)).collect();
+    assert_eq!(callees, vec![(0x8010, 2), (0x8018, 4)]);
+    assert_eq!(graph.cumulative_size(main, &graph.reachable(main)), 0xe);
+    assert_eq!(graph.find_address(0x801a).unwrap().address, 0x8018);
+}
@@ -0,0 +1,383 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::analysis::symbol_keys::SymbolKeys;
+use crate::binary_utils::parser::disassembly::{DisassembledFunction, Disassembly, Instruction};
+use crate::binary_utils::parser::symbol_table::{SymbolTable, SymbolType};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// The content hash of a disassembled function
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FunctionHash {
+    /// Stable key of the function, see `SymbolKeys`
+    pub key: String,
+    pub name: String,
+    pub section: String,
+    pub address: u32,
+    /// Size of the function body, not counting trailing padding
+    pub size: u32,
+    /// Hash of the code with relocations, addresses and build specific symbol names masked
+    pub hash: u64,
+    /// Hash of the raw bytes of the body
+    pub bytes_hash: u64,
+}
+
+/// How the code of a function changed between two builds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeStatus {
+    /// The code is different once addresses are masked
+    Changed,
+    /// The code is the same, but the function or something it refers to moved
+    Moved,
+    /// The bytes are the same and at the same address
+    Identical,
+    Added,
+    Removed,
+}
+
+/// A function matched between two builds by its key
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FunctionChange {
+    pub key: String,
+    pub old_name: Option<String>,
+    pub new_name: Option<String>,
+    pub old_address: Option<u32>,
+    pub new_address: Option<u32>,
+    pub old_size: u32,
+    pub new_size: u32,
+    /// Masked content hashes, see `FunctionHash`
+    pub old_hash: Option<u64>,
+    pub new_hash: Option<u64>,
+    pub status: CodeStatus,
+}
+
+/// The functions whose code changed between two builds, and those that only moved or stayed the same
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CodeDiffReport {
+    /// Every function of either build, by status and then key
+    pub functions: Vec<FunctionChange>,
+}
+
+/// Looks up the symbol an address points into, so addresses can be replaced by symbol keys
+struct AddressMap {
+    /// Start, end and normalized name of every sized symbol, by start address
+    symbols: Vec<(u32, u64, String)>,
+}
+
+impl AddressMap {
+    fn new(symbol_table: &SymbolTable, keys: &SymbolKeys) -> Self {
+        let mut symbols: Vec<(u32, u64, String)> = symbol_table
+            .iter()
+            .filter(|x| x.is_allocated() && !x.is_section_symbol() && x.alignment_or_size > 0)
+            .map(|x| {
+                let end = x.address as u64 + x.alignment_or_size as u64;
+                (x.address, end, keys.normalize(x.plain_name()))
+            })
+            .collect();
+        symbols.sort();
+        AddressMap { symbols }
+    }
+
+    /// Finds the symbol an address points into, as "name" or "name+0x4"
+    fn find(&self, address: u32) -> Option<String> {
+        let index = self.symbols.partition_point(|x| x.0 <= address).checked_sub(1)?;
+        let (start, end, name) = &self.symbols[index];
+        match address - start {
+            _ if address as u64 >= *end => None,
+            0 => Some(name.clone()),
+            offset => Some(format!("{}+{:#x}", name, offset)),
+        }
+    }
+}
+
+/// Hashes every function in the symbol table that has a body in the disassembly. A function with
+/// grouped clones is hashed along with the bodies of its clones, see `SymbolTable::group_clones`
+///
+/// # Arguments
+/// * 'disassembly' - The disassembly of the build
+/// * 'symbol_table' - The symbol table of the same build, used to key the functions and to find
+///   the symbols that plain addresses point to
+/// * 'keys' - The rules that give each function its key
+pub fn hash_functions(disassembly: &Disassembly, symbol_table: &SymbolTable, keys: &SymbolKeys) -> Vec<FunctionHash> {
+    let bodies: BTreeMap<(u32, &str), &DisassembledFunction> =
+        disassembly.iter().map(|x| ((x.address, x.name.as_str()), x)).collect();
+    let addresses = AddressMap::new(symbol_table, keys);
+    keys.keys(symbol_table)
+        .into_iter()
+        .filter(|(_, entry)| entry.flags.symbol_type == SymbolType::Function)
+        .filter_map(|(key, entry)| {
+            let clones = entry.clones.iter().map(|x| (x.address, x.name.as_str()));
+            let functions: Vec<&DisassembledFunction> = std::iter::once((entry.address, entry.plain_name()))
+                .chain(clones)
+                .filter_map(|x| bodies.get(&x).copied())
+                .collect();
+            let function = functions.first()?;
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut masked: Vec<String> = Vec::new();
+            for function in &functions {
+                let body = function.body();
+                bytes.extend(body.iter().flat_map(|x| x.bytes.iter().copied()));
+                masked.extend(body.iter().map(|x| mask(x, function, &addresses, keys)));
+            }
+            Some(FunctionHash {
+                key,
+                name: entry.plain_name().to_string(),
+                section: function.section.clone(),
+                address: entry.address,
+                size: bytes.len() as u32,
+                hash: fnv1a(masked.join("\n").as_bytes()),
+                bytes_hash: fnv1a(&bytes),
+            })
+        })
+        .collect()
+}
+
+/// Creates the text an instruction is hashed by. Relocated instructions are hashed by their
+/// relocations, since their bytes are left blank, and instructions that refer to symbols by the
+/// symbols instead of the encoded offsets. Plain addresses in the operands, like an ARM literal
+/// pool `.word`, are replaced by the symbol they point into. Any other instruction is hashed by
+/// its bytes
+fn mask(
+    instruction: &Instruction,
+    function: &DisassembledFunction,
+    addresses: &AddressMap,
+    keys: &SymbolKeys,
+) -> String {
+    let symbol = |name: &str| match name.strip_prefix(function.name.as_str()) {
+        Some(offset) if offset.is_empty() || offset.starts_with('+') => format!("self{}", offset),
+        _ => {
+            let (name, offset) = name.split_at(name.find('+').unwrap_or(name.len()));
+            format!("{}{}", keys.normalize(name), offset)
+        }
+    };
+    let prefix = format!("{}:{} {}", instruction.bytes.len(), instruction.mnemonic, instruction.masked_operands());
+
+    if !instruction.relocations.is_empty() {
+        let relocations: Vec<String> = instruction
+            .relocations
+            .iter()
+            .map(|x| match &x.symbol {
+                Some(name) => format!("{} {}{:+}", x.relocation_type, symbol(name), x.addend),
+                // Offsets into a section change whenever anything before them in the section does
+                None => format!("{} *", x.relocation_type),
+            })
+            .collect();
+        return format!("{} -> {}", prefix, relocations.join(","));
+    }
+
+    let references = instruction.references();
+    if !references.is_empty() {
+        let symbols: Vec<String> = references.iter().map(|(_, name)| symbol(name)).collect();
+        return format!("{} -> {}", prefix, symbols.join(","));
+    }
+
+    let mut masked = false;
+    let operands: Vec<String> = instruction
+        .operands
+        .split_inclusive([',', ' ', '$', '#'])
+        .map(|token| {
+            let (value, separator) = token.split_at(token.trim_end_matches([',', ' ', '$', '#']).len());
+            let address = value.strip_prefix("0x").and_then(|x| u32::from_str_radix(x, 16).ok());
+            match address.and_then(|x| addresses.find(x)) {
+                Some(name) => {
+                    masked = true;
+                    format!("<{}>{}", name, separator)
+                }
+                None => token.to_string(),
+            }
+        })
+        .collect();
+    if masked {
+        format!("{}:{} {}", instruction.bytes.len(), instruction.mnemonic, operands.concat())
+    } else {
+        instruction.bytes.iter().map(|x| format!("{:02x}", x)).collect()
+    }
+}
+
+/// Hashes bytes with 64 bit FNV-1a, which gives the same hashes on every platform and Rust version
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &x| (hash ^ x as u64).wrapping_mul(0x100000001b3))
+}
+
+impl CodeDiffReport {
+    /// Compares the function hashes of two builds
+    ///
+    /// # Arguments
+    /// * 'old' - The function hashes of the old build
+    /// * 'new' - The function hashes of the new build
+    pub fn new(old: &[FunctionHash], new: &[FunctionHash]) -> Self {
+        let old_keys: BTreeMap<&str, &FunctionHash> = old.iter().map(|x| (x.key.as_str(), x)).collect();
+        let new_keys: BTreeMap<&str, &FunctionHash> = new.iter().map(|x| (x.key.as_str(), x)).collect();
+
+        let mut functions: Vec<FunctionChange> = old_keys
+            .keys()
+            .chain(new_keys.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|key| {
+                let (old_hash, new_hash) = (old_keys.get(key), new_keys.get(key));
+                let status = match (old_hash, new_hash) {
+                    (None, _) => CodeStatus::Added,
+                    (_, None) => CodeStatus::Removed,
+                    (Some(x), Some(y)) if x.hash != y.hash => CodeStatus::Changed,
+                    (Some(x), Some(y)) if x.bytes_hash != y.bytes_hash || x.address != y.address => CodeStatus::Moved,
+                    _ => CodeStatus::Identical,
+                };
+                FunctionChange {
+                    key: key.to_string(),
+                    old_name: old_hash.map(|x| x.name.clone()),
+                    new_name: new_hash.map(|x| x.name.clone()),
+                    old_address: old_hash.map(|x| x.address),
+                    new_address: new_hash.map(|x| x.address),
+                    old_size: old_hash.map_or(0, |x| x.size),
+                    new_size: new_hash.map_or(0, |x| x.size),
+                    old_hash: old_hash.map(|x| x.hash),
+                    new_hash: new_hash.map(|x| x.hash),
+                    status,
+                }
+            })
+            .collect();
+        functions.sort_by(|x, y| x.status.cmp(&y.status).then(x.key.cmp(&y.key)));
+        CodeDiffReport { functions }
+    }
+
+    /// Gets the functions with a status
+    pub fn with_status(&self, status: CodeStatus) -> impl Iterator<Item = &FunctionChange> {
+        self.functions.iter().filter(move |x| x.status == status)
+    }
+
+    /// Finds the change of a function by its key
+    pub fn find(&self, key: &str) -> Option<&FunctionChange> {
+        self.functions.iter().find(|x| x.key == key)
+    }
+}
+
+impl FunctionChange {
+    /// Formats the function's name, showing both names if it was renamed
+    fn display_name(&self) -> String {
+        match (&self.old_name, &self.new_name) {
+            (Some(old), Some(new)) if old != new => format!("{} -> {}", old, new),
+            (_, Some(name)) | (Some(name), None) => name.clone(),
+            (None, None) => self.key.clone(),
+        }
+    }
+}
+
+/// Formats an optional address as hex, or "-" if the function isn't in that build
+fn address_string(address: Option<u32>) -> String {
+    address.map_or_else(|| "-".to_string(), |x| format!("{:08x}", x))
+}
+
+impl fmt::Display for CodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeStatus::Changed => write!(f, "changed"),
+            CodeStatus::Moved => write!(f, "moved"),
+            CodeStatus::Identical => write!(f, "identical"),
+            CodeStatus::Added => write!(f, "added"),
+            CodeStatus::Removed => write!(f, "removed"),
+        }
+    }
+}
+
+impl fmt::Display for CodeDiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:9} {:>8} {:>8} {:>8} {:>8} Function", "Status", "Old", "New", "Old Size", "New Size")?;
+        for function in &self.functions {
+            writeln!(
+                f,
+                "{:9} {:>8} {:>8} {:>8} {:>8} {}",
+                function.status.to_string(),
+                address_string(function.old_address),
+                address_string(function.new_address),
+                function.old_size,
+                function.new_size,
+                function.display_name()
+            )?;
+        }
+        let statuses = [
+            CodeStatus::Changed,
+            CodeStatus::Moved,
+            CodeStatus::Identical,
+            CodeStatus::Added,
+            CodeStatus::Removed,
+        ];
+        let counts: Vec<String> = statuses
+            .iter()
+            .map(|&x| format!("{} {}", self.with_status(x).count(), x))
+            .collect();
+        writeln!(f, "Functions: {}", counts.join(", "))
+    }
+}
+
+impl Report for CodeDiffReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Code Changes";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Code Changes";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Function";
+                                th: "Status";
+                                th: "Old Address";
+                                th: "New Address";
+                                th: "Old Size";
+                                th: "New Size";
+                            }
+                        }
+                        tbody {
+                            @ for function in &self.functions {
+                                tr {
+                                    td: function.display_name();
+                                    td: function.status.to_string();
+                                    td: address_string(function.old_address);
+                                    td: address_string(function.new_address);
+                                    td: function.old_size;
+                                    td: function.new_size;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("key,old_name,new_name,status,old_address,new_address,old_size,new_size,old_hash,new_hash\n");
+        for function in &self.functions {
+            csv += &format!(
+                "{},{},{},{},{},{},{},{},{},{}\n",
+                csv_field(&function.key),
+                csv_field(function.old_name.as_deref().unwrap_or("")),
+                csv_field(function.new_name.as_deref().unwrap_or("")),
+                function.status,
+                function.old_address.map_or_else(String::new, |x| format!("{:#x}", x)),
+                function.new_address.map_or_else(String::new, |x| format!("{:#x}", x)),
+                function.old_size,
+                function.new_size,
+                function.old_hash.map_or_else(String::new, |x| format!("{:016x}", x)),
+                function.new_hash.map_or_else(String::new, |x| format!("{:016x}", x))
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,161 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn old_build() -> Vec<String> {
+    lines(&[
+        "SYMBOL TABLE:",
+        "08000100 g     F .text	00000008 scale",
+        "08000108 g     F .text	0000000c read_sensor",
+        "08000114 g     F .text	00000008 filter.constprop.0",
+        "20000000 g     O .bss	00000004 counter",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "08000100 <scale>:",
+        " 8000100:	eb00 0040 	add.w	r0, r0, r0, lsl #1",
+        " 8000104:	4770      	bx	lr",
+        " 8000106:	bf00      	nop",
+        "",
+        "08000108 <read_sensor>:",
+        " 8000108:	4b01      	ldr	r3, [pc, #4]	@ (8000110 <read_sensor+0x8>)",
+        " 800010a:	6818      	ldr	r0, [r3, #0]",
+        " 800010c:	f000 b802 	b.w	8000114 <filter.constprop.0>",
+        " 8000110:	20000000 	.word	0x20000000",
+        "",
+        "08000114 <filter.constprop.0>:",
+        " 8000114:	280a      	cmp	r0, #10",
+        " 8000116:	bfd8      	it	le",
+        " 8000118:	2000      	movle	r0, #0",
+        " 800011a:	4770      	bx	lr",
+    ])
+}
+
+fn new_build() -> Vec<String> {
+    lines(&[
+        "SYMBOL TABLE:",
+        "08000100 g     F .text	00000008 scale",
+        "08000108 g     F .text	00000010 init",
+        "08000118 g     F .text	0000000c read_sensor",
+        "08000124 g     F .text	00000008 filter.constprop.1",
+        "20000008 g     O .bss	00000004 counter",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "08000100 <scale>:",
+        " 8000100:	eb00 0040 	add.w	r0, r0, r0, lsl #1",
+        " 8000104:	4770      	bx	lr",
+        "",
+        "08000108 <init>:",
+        " 8000108:	2000      	movs	r0, #0",
+        " 800010a:	4770      	bx	lr",
+        "",
+        "08000118 <read_sensor>:",
+        " 8000118:	4b01      	ldr	r3, [pc, #4]	@ (8000120 <read_sensor+0x8>)",
+        " 800011a:	6818      	ldr	r0, [r3, #0]",
+        " 800011c:	f000 b802 	b.w	8000124 <filter.constprop.1>",
+        " 8000120:	20000008 	.word	0x20000008",
+        "",
+        "08000124 <filter.constprop.1>:",
+        " 8000124:	280c      	cmp	r0, #12",
+        " 8000126:	bfd8      	it	le",
+        " 8000128:	2000      	movle	r0, #0",
+        " 800012a:	4770      	bx	lr",
+    ])
+}
+
+fn hashes(input: &[String]) -> Vec<FunctionHash> {
+    hash_functions(&Disassembly::from_lines(input), &SymbolTable::from_lines(input), &SymbolKeys::default())
+}
+
+#[test]
+fn test_hash_ignores_padding_and_addresses() {
+    let (old, new) = (hashes(&old_build()), hashes(&new_build()));
+    assert_eq!(old.len(), 3);
+    // The trailing nop isn't part of scale's body
+    assert_eq!((old[0].key.as_str(), old[0].size), ("scale", 6));
+    assert_eq!(old[0].hash, new[0].hash);
+    assert_eq!(old[0].bytes_hash, new[0].bytes_hash);
+    // read_sensor moved, and so did the variable its literal pool points to and the function it calls
+    let (old_read, new_read) = (&old[1], &new[2]);
+    assert_eq!(old_read.key, "read_sensor");
+    assert_eq!(old_read.hash, new_read.hash);
+    assert_ne!(old_read.bytes_hash, new_read.bytes_hash);
+    // filter compares against a different constant
+    assert_eq!(old[2].key, "filter.constprop");
+    assert_ne!(old[2].hash, new[3].hash);
+}
+
+#[test]
+fn test_hash_grouped_clones() {
+    let grouped = |input: &[String]| {
+        let mut symbol_table = SymbolTable::from_lines(input);
+        symbol_table.group_clones();
+        hash_functions(&Disassembly::from_lines(input), &symbol_table, &SymbolKeys::default())
+    };
+    let (old, new) = (grouped(&old_build()), grouped(&new_build()));
+    let filter = old.iter().find(|x| x.key == "filter").unwrap();
+    // The clone's origin was inlined everywhere, so the clone's body is hashed under its name
+    assert_eq!((filter.name.as_str(), filter.address, filter.size), ("filter", 0x8000114, 8));
+    assert_eq!(filter.hash, hashes(&old_build())[2].hash);
+    assert_ne!(filter.hash, new.iter().find(|x| x.key == "filter").unwrap().hash);
+}
+
+#[test]
+fn test_code_diff_statuses() {
+    let report = CodeDiffReport::new(&hashes(&old_build()), &hashes(&new_build()));
+    let statuses: Vec<(&str, CodeStatus)> = report.functions.iter().map(|x| (x.key.as_str(), x.status)).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("filter.constprop", CodeStatus::Changed),
+            ("read_sensor", CodeStatus::Moved),
+            ("scale", CodeStatus::Identical),
+            ("init", CodeStatus::Added),
+        ]
+    );
+    let filter = report.find("filter.constprop").unwrap();
+    assert_eq!(filter.display_name(), "filter.constprop.0 -> filter.constprop.1");
+    assert_eq!((filter.old_address, filter.new_address), (Some(0x8000114), Some(0x8000124)));
+    assert_eq!(report.with_status(CodeStatus::Removed).count(), 0);
+}
+
+#[test]
+fn test_object_file_relocations_are_masked() {
+    let object = |offset: &str, target: &str| {
+        lines(&[
+            "SYMBOL TABLE:",
+            "0000000000000000 g     F .text	0000000000000010 main",
+            "",
+            "Disassembly of section .text:",
+            "",
+            "0000000000000000 <main>:",
+            "   0:	48 83 ec 08          	sub    $0x8,%rsp",
+            "   4:	bf 00 00 00 00       	mov    $0x0,%edi",
+            &format!("			5: R_X86_64_32	.rodata.str1.1+{}", offset),
+            "   9:	e8 00 00 00 00       	call   e <main+0xe>",
+            &format!("			a: R_X86_64_PLT32	{}-0x4", target),
+            "   e:	31 c0                	xor    %eax,%eax",
+        ])
+    };
+    let hash = |lines: &[String]| hashes(lines)[0].hash;
+    // Section offsets move whenever other strings are added, but a different callee is a change
+    assert_eq!(hash(&object("0x0", "puts")), hash(&object("0x1c", "puts")));
+    assert_ne!(hash(&object("0x0", "puts")), hash(&object("0x0", "printf")));
+}
+
+#[test]
+fn test_code_diff_csv() {
+    let report = CodeDiffReport::new(&hashes(&old_build()), &hashes(&new_build()));
+    let csv = report.to_csv();
+    assert!(csv.starts_with("key,old_name,new_name,status,old_address,new_address,old_size,new_size,old_hash,new_hash\n"));
+    assert!(csv.contains("\ninit,,init,added,,0x8000108,0,4,,"));
+}
+
+#[test]
+fn test_address_map_symbol_ending_at_the_top_of_memory() {
+    let symbol_table = SymbolTable::from_lines(&lines(&["fffffff0 g     O .data	00000010 vectors"]));
+    let addresses = AddressMap::new(&symbol_table, &SymbolKeys::default());
+    assert_eq!(addresses.find(0xfffffff0), Some("vectors".to_string()));
+    assert_eq!(addresses.find(0xffffffff), Some("vectors+0xf".to_string()));
+    assert_eq!(addresses.find(0xffffffef), None);
+}
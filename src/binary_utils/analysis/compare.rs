@@ -0,0 +1,314 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::analysis::symbol_keys::SymbolKeys;
+use crate::binary_utils::parser::symbol_table::{is_thread_local_section, SymbolTable};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// The size of a symbol in each variant
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SymbolRow {
+    /// Stable key of the symbol, see `SymbolKeys`
+    pub key: String,
+    pub section: String,
+    /// Size in each variant, in the order the variants were given, or none if the variant doesn't
+    /// have the symbol
+    pub sizes: Vec<Option<u32>>,
+    /// Index of the variant with the smallest size, or none if every variant has the same size or
+    /// only one has the symbol
+    pub best: Option<usize>,
+}
+
+/// The size of a section in each variant
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionRow {
+    pub section: String,
+    pub sizes: Vec<u32>,
+    pub best: Option<usize>,
+}
+
+/// A matrix of symbol and section sizes across several builds of the same source, like different
+/// optimization levels, compilers or product configurations
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CompareReport {
+    /// Labels of the variants, in the order they were given
+    pub variants: Vec<String>,
+    /// Symbols found in any variant, by their largest size
+    pub symbols: Vec<SymbolRow>,
+    pub sections: Vec<SectionRow>,
+    /// Total size of each variant
+    pub totals: Vec<u32>,
+    pub best: Option<usize>,
+}
+
+impl CompareReport {
+    /// Compares the symbol tables of several variants. Symbols are matched by their keys, so names
+    /// that only differ in build specific numbers or hashes are treated as the same symbol
+    ///
+    /// # Arguments
+    /// * 'variants' - The label and symbol table of each variant
+    /// * 'keys' - The rules that give each symbol its key
+    pub fn new(variants: &[(&str, &SymbolTable)], keys: &SymbolKeys) -> Self {
+        let mut rows: BTreeMap<String, SymbolRow> = BTreeMap::new();
+        for (i, (_, symbol_table)) in variants.iter().enumerate() {
+            for (key, entry) in keys.keys(symbol_table) {
+                let row = rows.entry(key.clone()).or_insert_with(|| SymbolRow {
+                    key,
+                    section: entry.section.clone(),
+                    sizes: vec![None; variants.len()],
+                    best: None,
+                });
+                row.sizes[i] = Some(entry.alignment_or_size);
+            }
+        }
+        // Labels and markers without a size in any variant only clutter the matrix
+        let mut symbols: Vec<SymbolRow> = rows.into_values().filter(|x| largest(x) > 0).collect();
+        for row in &mut symbols {
+            row.best = best(&row.sizes);
+        }
+        symbols.sort_by(|x, y| largest(y).cmp(&largest(x)).then(x.key.cmp(&y.key)));
+
+        let section_sizes: Vec<BTreeMap<String, u32>> = variants.iter().map(|(_, x)| x.section_sizes()).collect();
+        let sections: Vec<SectionRow> = section_sizes
+            .iter()
+            .flat_map(|x| x.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|name| {
+                let sizes: Vec<u32> = section_sizes.iter().map(|x| x.get(name).copied().unwrap_or(0)).collect();
+                SectionRow {
+                    section: name.clone(),
+                    best: best(&sizes.iter().copied().map(Some).collect::<Vec<_>>()),
+                    sizes,
+                }
+            })
+            .filter(|x| x.sizes.iter().any(|&size| size > 0))
+            .collect();
+        // Thread local sections are listed but not totalled, like `SymbolTable::total_size`
+        let totals: Vec<u32> = (0..variants.len())
+            .map(|i| {
+                sections
+                    .iter()
+                    .filter(|x| !is_thread_local_section(&x.section))
+                    .map(|x| x.sizes[i])
+                    .sum()
+            })
+            .collect();
+
+        CompareReport {
+            variants: variants.iter().map(|(label, _)| label.to_string()).collect(),
+            symbols,
+            best: best(&totals.iter().copied().map(Some).collect::<Vec<_>>()),
+            sections,
+            totals,
+        }
+    }
+
+    /// Gets the symbols that only some of the variants have
+    pub fn partial(&self) -> impl Iterator<Item = &SymbolRow> {
+        self.symbols.iter().filter(|x| x.is_partial())
+    }
+
+    /// Gets the labels of the variants that have a symbol
+    pub fn present_in(&self, symbol: &SymbolRow) -> Vec<&str> {
+        self.variants
+            .iter()
+            .zip(&symbol.sizes)
+            .filter(|(_, size)| size.is_some())
+            .map(|(label, _)| label.as_str())
+            .collect()
+    }
+
+    /// Finds the row of a symbol by its key
+    pub fn find(&self, key: &str) -> Option<&SymbolRow> {
+        self.symbols.iter().find(|x| x.key == key)
+    }
+}
+
+impl SymbolRow {
+    /// Checks if any variant doesn't have the symbol
+    pub fn is_partial(&self) -> bool {
+        self.sizes.contains(&None)
+    }
+}
+
+/// Finds the first variant with the smallest size, ignoring variants without one. There is no
+/// best variant if they all have the same size, or if fewer than two have a size to compare
+fn best(sizes: &[Option<u32>]) -> Option<usize> {
+    let present: Vec<(usize, u32)> = sizes.iter().enumerate().filter_map(|(i, x)| x.map(|x| (i, x))).collect();
+    let (index, smallest) = present.iter().copied().min_by_key(|&(i, size)| (size, i))?;
+    if present.len() < 2 || (present.len() == sizes.len() && present.iter().all(|&(_, size)| size == smallest)) {
+        None
+    } else {
+        Some(index)
+    }
+}
+
+/// Gets the largest size of a symbol in any variant
+fn largest(row: &SymbolRow) -> u32 {
+    row.sizes.iter().flatten().copied().max().unwrap_or(0)
+}
+
+/// Formats a size for the text and HTML matrix, marking the best variant and missing symbols
+fn cell(size: Option<u32>, best: bool) -> String {
+    match size {
+        Some(size) if best => format!("{}*", size),
+        Some(size) => size.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+impl fmt::Display for CompareReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.symbols.iter().map(|x| x.key.len()).chain(self.sections.iter().map(|x| x.section.len()));
+        let width = names.max().unwrap_or(0).max(7);
+        let section_width = self.sections.iter().map(|x| x.section.len()).max().unwrap_or(0).max(7);
+        let columns: Vec<usize> = self.variants.iter().map(|x| x.len().max(9)).collect();
+        let row = |f: &mut fmt::Formatter<'_>, name: &str, section: &str, cells: Vec<String>| -> fmt::Result {
+            write!(f, "{:width$} {:section_width$}", name, section, width = width, section_width = section_width)?;
+            for (cell, column) in cells.iter().zip(&columns) {
+                write!(f, " {:>column$}", cell, column = column)?;
+            }
+            writeln!(f)
+        };
+
+        row(f, "Symbol", "Section", self.variants.clone())?;
+        for symbol in &self.symbols {
+            let cells = symbol.sizes.iter().enumerate().map(|(i, x)| cell(*x, symbol.best == Some(i))).collect();
+            row(f, &symbol.key, &symbol.section, cells)?;
+        }
+        row(f, "Section", "", self.variants.clone())?;
+        for section in &self.sections {
+            let cells = section
+                .sizes
+                .iter()
+                .enumerate()
+                .map(|(i, x)| cell(Some(*x), section.best == Some(i)))
+                .collect();
+            row(f, &section.section, "", cells)?;
+        }
+        let totals = self.totals.iter().enumerate().map(|(i, x)| cell(Some(*x), self.best == Some(i))).collect();
+        row(f, "Total", "", totals)?;
+
+        writeln!(f, "Only in some variants:")?;
+        for symbol in self.partial() {
+            writeln!(f, "  {} ({})", symbol.key, self.present_in(symbol).join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for CompareReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Variant Comparison";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Variant Comparison";
+                    p : "The smallest size of each row is marked with *, and - marks symbols a variant doesn't have";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Symbol";
+                                th: "Section";
+                                @ for variant in &self.variants {
+                                    th: variant;
+                                }
+                            }
+                        }
+                        tbody {
+                            @ for symbol in &self.symbols {
+                                tr {
+                                    td: &symbol.key;
+                                    td: &symbol.section;
+                                    @ for (i, size) in symbol.sizes.iter().enumerate() {
+                                        td: cell(*size, symbol.best == Some(i));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Sections";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                @ for variant in &self.variants {
+                                    th: variant;
+                                }
+                            }
+                        }
+                        tbody {
+                            @ for section in &self.sections {
+                                tr {
+                                    td: &section.section;
+                                    @ for (i, size) in section.sizes.iter().enumerate() {
+                                        td: cell(Some(*size), section.best == Some(i));
+                                    }
+                                }
+                            }
+                            tr {
+                                th: "Total";
+                                @ for (i, size) in self.totals.iter().enumerate() {
+                                    th: cell(Some(*size), self.best == Some(i));
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Only in some variants";
+                    ul {
+                        @ for symbol in self.partial() {
+                            li : format!("{} ({})", symbol.key, self.present_in(symbol).join(", "));
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let labels: Vec<String> = self.variants.iter().map(|x| csv_field(x)).collect();
+        let mut csv = format!("kind,key,section,{},best\n", labels.join(","));
+        let best = |index: Option<usize>| index.map_or("", |i| labels[i].as_str());
+        for symbol in &self.symbols {
+            let sizes: Vec<String> = symbol
+                .sizes
+                .iter()
+                .map(|x| x.map_or_else(String::new, |x| x.to_string()))
+                .collect();
+            csv += &format!(
+                "symbol,{},{},{},{}\n",
+                csv_field(&symbol.key),
+                csv_field(&symbol.section),
+                sizes.join(","),
+                best(symbol.best)
+            );
+        }
+        for section in &self.sections {
+            let sizes: Vec<String> = section.sizes.iter().map(|x| x.to_string()).collect();
+            csv += &format!(
+                "section,{},,{},{}\n",
+                csv_field(&section.section),
+                sizes.join(","),
+                best(section.best)
+            );
+        }
+        let totals: Vec<String> = self.totals.iter().map(|x| x.to_string()).collect();
+        csv += &format!("total,,,{},{}\n", totals.join(","), best(self.best));
+        csv
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn variants() -> Vec<SymbolTable> {
+    vec![
+        SymbolTable::from_lines(&lines(&[
+            "08000000 g     F .text	00000080 main",
+            "08000080 l     F .text	00000020 scale.constprop.0",
+            "08000100 g     F .text	00000040 unrolled_copy",
+            "20000000 g     O .bss	00000100 buffer",
+        ])),
+        SymbolTable::from_lines(&lines(&[
+            "08000000 g     F .text	00000060 main",
+            "08000060 l     F .text	00000010 scale.constprop.1",
+            "20000000 g     O .bss	00000100 buffer",
+        ])),
+        SymbolTable::from_lines(&lines(&[
+            "08000000 g     F .text	00000058 main",
+            "08000058 l     F .text	00000018 scale.constprop.0",
+            "20000000 g     O .bss	00000100 buffer",
+        ])),
+    ]
+}
+
+fn report(tables: &[SymbolTable]) -> CompareReport {
+    let variants: Vec<(&str, &SymbolTable)> = ["O2", "Os", "Oz"].iter().copied().zip(tables).collect();
+    CompareReport::new(&variants, &SymbolKeys::default())
+}
+
+#[test]
+fn test_symbol_matrix() {
+    let tables = variants();
+    let report = report(&tables);
+    let keys: Vec<&str> = report.symbols.iter().map(|x| x.key.as_str()).collect();
+    assert_eq!(keys, vec!["buffer", "main", "unrolled_copy", "scale.constprop"]);
+    let row = |key: &str| {
+        let row = report.find(key).unwrap();
+        (row.sizes.clone(), row.best)
+    };
+    assert_eq!(row("buffer"), (vec![Some(0x100), Some(0x100), Some(0x100)], None));
+    assert_eq!(row("main"), (vec![Some(0x80), Some(0x60), Some(0x58)], Some(2)));
+    assert_eq!(row("unrolled_copy"), (vec![Some(0x40), None, None], None));
+    assert_eq!(row("scale.constprop"), (vec![Some(0x20), Some(0x10), Some(0x18)], Some(1)));
+    let partial: Vec<&str> = report.partial().map(|x| x.key.as_str()).collect();
+    assert_eq!(partial, vec!["unrolled_copy"]);
+    assert_eq!(report.present_in(report.find("unrolled_copy").unwrap()), vec!["O2"]);
+}
+
+#[test]
+fn test_section_totals() {
+    let tables = variants();
+    let report = report(&tables);
+    let sections: Vec<(&str, &[u32], Option<usize>)> = report
+        .sections
+        .iter()
+        .map(|x| (x.section.as_str(), x.sizes.as_slice(), x.best))
+        .collect();
+    assert_eq!(
+        sections,
+        vec![
+            (".bss", &[0x100, 0x100, 0x100][..], None),
+            (".text", &[0xe0, 0x70, 0x70][..], Some(1)),
+        ]
+    );
+    assert_eq!(report.totals, vec![0x1e0, 0x170, 0x170]);
+    // Ties go to the first variant given
+    assert_eq!(report.best, Some(1));
+}
+
+#[test]
+fn test_compare_csv() {
+    let tables = variants();
+    let csv = report(&tables).to_csv();
+    assert!(csv.starts_with("kind,key,section,O2,Os,Oz,best\n"));
+    assert!(csv.contains("symbol,unrolled_copy,.text,64,,,\n"));
+    assert!(csv.contains("symbol,buffer,.bss,256,256,256,\n"));
+    assert!(csv.ends_with("total,,,480,368,368,Os\n"));
+}
@@ -0,0 +1,431 @@
+use serde::{Deserialize, Serialize};
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use crate::binary_utils::parser::map_file::MapFile;
+use crate::binary_utils::parser::symbol_table::{
+    covered_size, image_size, is_thread_local_section, SymbolScope, SymbolTable, SymbolTableEntry, SymbolType,
+};
+use crate::binary_utils::report::{csv_field, percent, Report, TABLE_STYLE};
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// Name of the component that symbols matching no rule are attributed to
+pub const UNASSIGNED: &str = "unassigned";
+
+/// Rules that attribute symbols to components, typically loaded from a TOML rules file. Each
+/// symbol belongs to the first component with a matching rule:
+///
+/// ```toml
+/// [[components]]
+/// name = "freertos"
+/// owner = "@rtos-team"
+/// files = ["tasks.c", "queue.c"]
+/// symbols = ["xTask*", "vTask*"]
+///
+/// [[components]]
+/// name = "vendor_hal"
+/// objects = ["*/libhal.a(*)"]
+///
+/// [[components]]
+/// name = "sensor"
+/// namespaces = ["sensor"]
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentRules {
+    #[serde(default)]
+    pub components: Vec<ComponentRule>,
+}
+
+/// The patterns that attribute a symbol to one component. A symbol matching any of them belongs
+/// to the component
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentRule {
+    pub name: String,
+    /// Team or person responsible for the component, like a CODEOWNERS entry
+    pub owner: Option<String>,
+    /// Wildcard patterns for symbol names
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Wildcard patterns for the file symbols that local symbols follow. Global symbols are only
+    /// matched when a linker map places them in the same object as local symbols of the file
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Wildcard patterns for the object paths of a linker map, e.g. `*/libhal.a(*)`
+    #[serde(default)]
+    pub objects: Vec<String>,
+    /// C++ namespaces, matched against both mangled and demangled names
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+}
+
+impl ComponentRules {
+    /// Loads component rules from a TOML file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename of the rules file
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let rules = ComponentRules::from_file("components.toml")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(filename)?.parse()
+    }
+
+    /// Finds the first component whose rules match a symbol
+    ///
+    /// # Arguments
+    /// * 'entry' - The symbol to attribute
+    /// * 'file' - Name of the file the symbol was compiled from, if known
+    /// * 'object' - Object path the linker map placed the symbol from
+    pub fn find(&self, entry: &SymbolTableEntry, file: Option<&str>, object: Option<&str>) -> Option<&ComponentRule> {
+        self.components.iter().find(|x| x.matches(entry, file, object))
+    }
+}
+
+impl std::str::FromStr for ComponentRules {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl ComponentRule {
+    /// Checks if any of the component's patterns match a symbol
+    fn matches(&self, entry: &SymbolTableEntry, file: Option<&str>, object: Option<&str>) -> bool {
+        let any = |patterns: &[String], value: Option<&str>| {
+            value.is_some_and(|value| patterns.iter().any(|x| string_utils::matches_wildcard(x, value)))
+        };
+        any(&self.symbols, Some(&entry.name))
+            || any(&self.files, file)
+            || any(&self.objects, object)
+            || self.namespaces.iter().any(|x| in_namespace(&entry.name, x))
+    }
+}
+
+/// Checks if a symbol name is inside a C++ namespace, either demangled (`ns::name`) or mangled
+/// with the Itanium ABI (`_ZN2ns4nameEv`). Nested namespaces are written as `outer::inner`
+fn in_namespace(name: &str, namespace: &str) -> bool {
+    if name.strip_prefix(namespace).is_some_and(|x| x.starts_with("::")) {
+        return true;
+    }
+    let nested = match name.strip_prefix("_ZN") {
+        // Skip the qualifiers of const and volatile member functions
+        Some(x) => x.trim_start_matches(['r', 'V', 'K']),
+        None => return false,
+    };
+    let prefix: String = namespace.split("::").map(|x| format!("{}{}", x.len(), x)).collect();
+    nested.starts_with(&prefix)
+}
+
+/// The size of one component, per section and in total
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ComponentSize {
+    pub component: String,
+    pub owner: Option<String>,
+    /// Bytes covered per section, counting aliases once
+    pub sections: BTreeMap<String, u32>,
+    pub size: u32,
+    /// Number of symbols attributed to the component
+    pub symbols: usize,
+    /// Size as a percentage of the image
+    pub percent: f64,
+}
+
+/// The combined size of every component with the same owner
+#[derive(Debug, PartialEq, Serialize)]
+pub struct OwnerSize {
+    pub owner: String,
+    pub components: Vec<String>,
+    pub size: u32,
+    pub percent: f64,
+}
+
+/// Symbol table sizes rolled up per component and per owner, largest first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ComponentReport {
+    /// Every section that any component has symbols in
+    pub sections: Vec<String>,
+    pub components: Vec<ComponentSize>,
+    pub owners: Vec<OwnerSize>,
+    pub total_size: u32,
+}
+
+impl ComponentReport {
+    /// Attributes every placed symbol to a component and sums the sizes. Symbols that no rule
+    /// matches are put in the `unassigned` component
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to attribute
+    /// * 'rules' - Rules mapping symbols to components
+    /// * 'map_file' - Optional linker map, needed for the object path rules and to match global
+    ///   symbols against the file rules
+    /// * 'section_sizes' - Optional size of each section to take the percentages of, for when
+    ///   symbols were filtered out of the table. Defaults to the sizes of the table itself
+    pub fn new(
+        symbol_table: &SymbolTable,
+        rules: &ComponentRules,
+        map_file: Option<&MapFile>,
+        section_sizes: Option<&BTreeMap<String, u32>>,
+    ) -> Self {
+        let mut placed: Vec<(&SymbolTableEntry, Option<&str>, Option<&str>)> = Vec::new();
+        let mut file: Option<&str> = None;
+        for entry in symbol_table.iter() {
+            if entry.flags.symbol_type == SymbolType::File {
+                file = Some(entry.name.as_str()).filter(|x| !x.is_empty());
+                continue;
+            }
+            if !entry.is_allocated() || entry.is_section_symbol() {
+                continue;
+            }
+            // Global symbols are listed after every file symbol, so only locals have a known file
+            let local_file = file.filter(|_| entry.flags.scope == SymbolScope::Local);
+            let object = map_file.and_then(|x| x.find(entry.address)).map(|x| x.object.as_str());
+            placed.push((entry, local_file, object));
+        }
+
+        // Globals get the file of the locals the linker map placed from the same object
+        let object_files: BTreeMap<&str, &str> = placed
+            .iter()
+            .filter_map(|&(_, file, object)| Some((object?, file?)))
+            .collect();
+        let mut attributed: BTreeMap<(&str, Option<&str>), Vec<&SymbolTableEntry>> = BTreeMap::new();
+        for (entry, file, object) in placed {
+            let file = file.or_else(|| object.and_then(|x| object_files.get(x).copied()));
+            let key = match rules.find(entry, file, object) {
+                Some(rule) => (rule.name.as_str(), rule.owner.as_deref()),
+                None => (UNASSIGNED, None),
+            };
+            attributed.entry(key).or_default().push(entry);
+        }
+
+        let total_size = match section_sizes {
+            Some(sizes) => image_size(sizes),
+            None => symbol_table.total_size(),
+        };
+        let mut components: Vec<ComponentSize> = attributed
+            .into_iter()
+            .map(|((component, owner), entries)| {
+                let mut by_section: BTreeMap<&str, Vec<&SymbolTableEntry>> = BTreeMap::new();
+                for entry in &entries {
+                    by_section.entry(&entry.section).or_default().push(entry);
+                }
+                let sections: BTreeMap<String, u32> = by_section
+                    .into_iter()
+                    .map(|(section, entries)| (section.to_string(), covered_size(entries)))
+                    .collect();
+                // Thread local sections are listed but not totalled, like `SymbolTable::total_size`
+                let size = sections
+                    .iter()
+                    .filter(|(section, _)| !is_thread_local_section(section))
+                    .map(|(_, size)| size)
+                    .sum();
+                ComponentSize {
+                    component: component.to_string(),
+                    owner: owner.map(String::from),
+                    sections,
+                    size,
+                    symbols: entries.len(),
+                    percent: percent(size, total_size),
+                }
+            })
+            .collect();
+        components.sort_by(|x, y| y.size.cmp(&x.size).then(x.component.cmp(&y.component)));
+
+        let mut by_owner: BTreeMap<&str, (Vec<String>, u32)> = BTreeMap::new();
+        for component in &components {
+            if let Some(owner) = &component.owner {
+                let (names, size) = by_owner.entry(owner).or_default();
+                names.push(component.component.clone());
+                *size += component.size;
+            }
+        }
+        let mut owners: Vec<OwnerSize> = by_owner
+            .into_iter()
+            .map(|(owner, (components, size))| OwnerSize {
+                owner: owner.to_string(),
+                components,
+                size,
+                percent: percent(size, total_size),
+            })
+            .collect();
+        owners.sort_by(|x, y| y.size.cmp(&x.size).then(x.owner.cmp(&y.owner)));
+
+        let mut sections: Vec<String> = components.iter().flat_map(|x| x.sections.keys().cloned()).collect();
+        sections.sort();
+        sections.dedup();
+
+        ComponentReport {
+            sections,
+            components,
+            owners,
+            total_size,
+        }
+    }
+
+    /// Finds a component by name
+    pub fn find(&self, component: &str) -> Option<&ComponentSize> {
+        self.components.iter().find(|x| x.component == component)
+    }
+}
+
+impl ComponentSize {
+    /// Gets the bytes the component covers in a section
+    pub fn section_size(&self, section: &str) -> u32 {
+        self.sections.get(section).copied().unwrap_or(0)
+    }
+
+    /// Gets the owner, or `-` if the component has none
+    fn owner_string(&self) -> &str {
+        self.owner.as_deref().unwrap_or("-")
+    }
+}
+
+impl fmt::Display for ComponentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .components
+            .iter()
+            .map(|x| x.component.len())
+            .max()
+            .unwrap_or(0)
+            .max("Component".len());
+        let owner_width = self
+            .components
+            .iter()
+            .map(|x| x.owner_string().len())
+            .max()
+            .unwrap_or(0)
+            .max("Owner".len());
+        write!(f, "{:w$} {:ow$}", "Component", "Owner", w = width, ow = owner_width)?;
+        for section in &self.sections {
+            write!(f, " {:>10}", section)?;
+        }
+        writeln!(f, " {:>10} {:>7} {:>7}", "Size", "Symbols", "Percent")?;
+        for component in &self.components {
+            write!(
+                f,
+                "{:w$} {:ow$}",
+                component.component,
+                component.owner_string(),
+                w = width,
+                ow = owner_width
+            )?;
+            for section in &self.sections {
+                write!(f, " {:>10}", component.section_size(section))?;
+            }
+            writeln!(f, " {:>10} {:>7} {:>6.1}%", component.size, component.symbols, component.percent)?;
+        }
+        if !self.owners.is_empty() {
+            writeln!(f, "Owners:")?;
+            for owner in &self.owners {
+                writeln!(
+                    f,
+                    "  {:ow$} {:>10} {:>6.1}% {}",
+                    owner.owner,
+                    owner.size,
+                    owner.percent,
+                    owner.components.join(", "),
+                    ow = owner_width
+                )?;
+            }
+        }
+        writeln!(f, "Total: {} bytes", self.total_size)
+    }
+}
+
+impl Report for ComponentReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Component Sizes";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Component Sizes";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Component";
+                                th: "Owner";
+                                @ for section in &self.sections {
+                                    th: section;
+                                }
+                                th: "Size";
+                                th: "Symbols";
+                                th: "Percent";
+                            }
+                        }
+                        tbody {
+                            @ for component in &self.components {
+                                tr {
+                                    td: &component.component;
+                                    td: component.owner_string();
+                                    @ for section in &self.sections {
+                                        td: component.section_size(section);
+                                    }
+                                    td: component.size;
+                                    td: component.symbols;
+                                    td: format!("{:.1}%", component.percent);
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Owners";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Owner";
+                                th: "Size";
+                                th: "Percent";
+                                th: "Components";
+                            }
+                        }
+                        tbody {
+                            @ for owner in &self.owners {
+                                tr {
+                                    td: &owner.owner;
+                                    td: owner.size;
+                                    td: format!("{:.1}%", owner.percent);
+                                    td: owner.components.join(", ");
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Total: {} bytes", self.total_size);
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("component,owner");
+        for section in &self.sections {
+            csv += &format!(",{}", csv_field(section));
+        }
+        csv += ",size,symbols,percent\n";
+        for component in &self.components {
+            csv += &format!(
+                "{},{}",
+                csv_field(&component.component),
+                csv_field(component.owner.as_deref().unwrap_or(""))
+            );
+            for section in &self.sections {
+                csv += &format!(",{}", component.section_size(section));
+            }
+            csv += &format!(",{},{},{:.2}\n", component.size, component.symbols, component.percent);
+        }
+        csv
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn sample_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 tasks.c",
+        "08000000 l     F .text	00000040 prvIdleTask",
+        "08000040 g     F .text	00000080 xTaskCreate",
+        "080000c0 g     F .text	00000020 tcp_write",
+        "080000e0 g     F .text	00000010 _ZNK6sensor4readEv",
+        "20000000 g     O .bss	00000100 ucHeap",
+        "20000100 g     O .bss	00000010 counter",
+        "00000000       F *UND*	00000000 printf",
+    ]))
+}
+
+fn sample_rules() -> ComponentRules {
+    "
+    [[components]]
+    name = \"freertos\"
+    owner = \"@rtos\"
+    files = [\"tasks.c\"]
+    symbols = [\"xTask*\", \"ucHeap\"]
+
+    [[components]]
+    name = \"lwip\"
+    owner = \"@networking\"
+    symbols = [\"tcp_*\"]
+
+    [[components]]
+    name = \"sensor\"
+    owner = \"@networking\"
+    namespaces = [\"sensor\"]
+    "
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn test_parse_rules() {
+    let rules = sample_rules();
+    assert_eq!(rules.components.len(), 3);
+    assert_eq!(rules.components[0].owner.as_deref(), Some("@rtos"));
+    assert!(rules.components[1].files.is_empty());
+    assert!("[[components]]\nname = \"x\"\npattern = \"*\"".parse::<ComponentRules>().is_err());
+}
+
+#[test]
+fn test_in_namespace() {
+    assert!(in_namespace("sensor::read()", "sensor"));
+    assert!(in_namespace("_ZN6sensor4readEv", "sensor"));
+    assert!(in_namespace("_ZNK6sensor4readEv", "sensor"));
+    assert!(in_namespace("_ZN6vendor3hal4initEv", "vendor::hal"));
+    assert!(!in_namespace("_ZN6vendor3usb4initEv", "vendor::hal"));
+    assert!(!in_namespace("sensors::read()", "sensor"));
+    assert!(!in_namespace("sensor_read", "sensor"));
+}
+
+#[test]
+fn test_component_report() {
+    let report = ComponentReport::new(&sample_table(), &sample_rules(), None, None);
+    let sizes: Vec<(&str, u32, usize)> = report
+        .components
+        .iter()
+        .map(|x| (x.component.as_str(), x.size, x.symbols))
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![("freertos", 0x1c0, 3), ("lwip", 0x20, 1), ("sensor", 0x10, 1), (UNASSIGNED, 0x10, 1)]
+    );
+    let freertos = report.find("freertos").unwrap();
+    assert_eq!(freertos.section_size(".text"), 0xc0);
+    assert_eq!(freertos.section_size(".bss"), 0x100);
+    assert_eq!(report.sections, vec![".bss".to_string(), ".text".to_string()]);
+    assert_eq!(report.total_size, 0x200);
+}
+
+#[test]
+fn test_owner_totals() {
+    let report = ComponentReport::new(&sample_table(), &sample_rules(), None, None);
+    let owners: Vec<(&str, u32, Vec<String>)> = report
+        .owners
+        .iter()
+        .map(|x| (x.owner.as_str(), x.size, x.components.clone()))
+        .collect();
+    assert_eq!(
+        owners,
+        vec![
+            ("@rtos", 0x1c0, vec!["freertos".to_string()]),
+            ("@networking", 0x30, vec!["lwip".to_string(), "sensor".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_first_matching_component_wins() {
+    let rules: ComponentRules = "
+        [[components]]
+        name = \"net\"
+        symbols = [\"tcp_*\"]
+
+        [[components]]
+        name = \"everything\"
+        symbols = [\"*\"]
+    "
+    .parse()
+    .unwrap();
+    let report = ComponentReport::new(&sample_table(), &rules, None, None);
+    assert_eq!(report.find("net").unwrap().symbols, 1);
+    assert_eq!(report.find("everything").unwrap().symbols, 5);
+    assert!(report.find(UNASSIGNED).is_none());
+}
+
+#[test]
+fn test_file_rules_only_match_local_symbols() {
+    let rules: ComponentRules = "
+        [[components]]
+        name = \"rtos\"
+        files = [\"tasks.c\"]
+    "
+    .parse()
+    .unwrap();
+    let report = ComponentReport::new(&sample_table(), &rules, None, None);
+    assert_eq!(report.find("rtos").unwrap().size, 0x40);
+}
+
+#[test]
+fn test_file_rules_match_global_symbols_through_map_file() {
+    let rules: ComponentRules = "
+        [[components]]
+        name = \"rtos\"
+        files = [\"tasks.c\"]
+    "
+    .parse()
+    .unwrap();
+    let map_file = MapFile::from_lines(&lines(&[
+        "Linker script and memory map",
+        " .text          0x08000000       0xc0 rtos/tasks.o",
+        " .text          0x080000c0       0x20 net/tcp.o",
+    ]));
+    let report = ComponentReport::new(&sample_table(), &rules, Some(&map_file), None);
+    assert_eq!(report.find("rtos").unwrap().size, 0xc0);
+    assert_eq!(report.find("rtos").unwrap().symbols, 2);
+}
+
+#[test]
+fn test_component_csv() {
+    let report = ComponentReport::new(&sample_table(), &sample_rules(), None, None);
+    let csv = report.to_csv();
+    let mut rows = csv.lines();
+    assert_eq!(rows.next(), Some("component,owner,.bss,.text,size,symbols,percent"));
+    assert_eq!(rows.next(), Some("freertos,@rtos,256,192,448,3,87.50"));
+    assert!(csv.contains("unassigned,,16,0,16,1,3.12\n"));
+}
+
+#[test]
+fn test_component_percentages_of_unfiltered_sizes() {
+    let section_sizes = sample_table().section_sizes();
+    let symbol_table: SymbolTable = sample_table().into_iter().filter(|x| x.name != "ucHeap").collect();
+    let report = ComponentReport::new(&symbol_table, &sample_rules(), None, Some(&section_sizes));
+    assert_eq!(report.total_size, 0x200);
+    assert!((report.find("freertos").unwrap().percent - 37.5).abs() < 1e-9);
+}
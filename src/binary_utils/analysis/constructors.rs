@@ -0,0 +1,267 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeSet, fmt};
+
+use crate::binary_utils::analysis::call_graph::CallGraph;
+use crate::binary_utils::parser::section_contents::SectionContents;
+use crate::binary_utils::parser::symbol_table::{SymbolConstructor, SymbolTable};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Sections holding the functions the C runtime calls before `main`
+pub const INIT_SECTIONS: [&str; 3] = [".preinit_array", ".init_array", ".ctors"];
+
+/// Sections holding the functions the C runtime calls after `main` returns
+pub const FINI_SECTIONS: [&str; 2] = [".fini_array", ".dtors"];
+
+/// Whether a constructor runs at startup or at exit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Init,
+    Fini,
+}
+
+/// A function called through an init or fini array, along with the code it reaches
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Constructor {
+    pub section: String,
+    pub phase: Phase,
+    /// Address of the array slot, or none for symbols that are marked as constructors
+    pub slot: Option<u32>,
+    /// Address the slot points at
+    pub target: u32,
+    /// The function at the target address, if one was found
+    pub function: Option<String>,
+    pub size: u32,
+    /// Every function the constructor can reach through calls
+    pub reachable: Vec<String>,
+    /// Size of the function plus every function it can reach
+    pub cumulative_size: u32,
+}
+
+/// The static constructors and destructors of an image and the code they run
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ConstructorReport {
+    pub constructors: Vec<Constructor>,
+    /// Bytes taken by the init and fini arrays themselves
+    pub table_size: u32,
+    /// Code run before `main`, counting functions reached from several constructors once
+    pub init_size: u32,
+    /// Code run at exit, counting functions reached from several destructors once
+    pub fini_size: u32,
+}
+
+impl ConstructorReport {
+    /// Resolves every slot of the init and fini arrays to the function it points at and follows
+    /// the call graph from there. Symbols marked as constructors in the symbol table are included
+    /// as well. The `-1` and `0` markers at the ends of `.ctors` and `.dtors` are skipped
+    ///
+    /// # Arguments
+    /// * 'graph' - Call graph of the image
+    /// * 'contents' - Section contents (`objdump -s`) holding the arrays
+    /// * 'symbol_table' - Symbol table of the image
+    pub fn new(graph: &CallGraph, contents: &SectionContents, symbol_table: &SymbolTable) -> Self {
+        let sentinel = match contents.pointer_size() {
+            8 => u64::MAX,
+            _ => u32::MAX as u64,
+        };
+
+        let mut constructors = Vec::new();
+        // Functions each constructor runs, by node index so same-named local functions stay apart
+        let mut reached: Vec<BTreeSet<usize>> = Vec::new();
+        let mut table_size = 0;
+        let sections = INIT_SECTIONS
+            .iter()
+            .map(|x| (x, Phase::Init))
+            .chain(FINI_SECTIONS.iter().map(|x| (x, Phase::Fini)));
+        for (section, phase) in sections {
+            table_size += contents.find(section).map_or(0, |x| x.bytes.len() as u32);
+            for (slot, target) in contents.words_in_section(section) {
+                if target == 0 || target == sentinel || target > u32::MAX as u64 {
+                    continue;
+                }
+                let (constructor, functions) = Constructor::new(graph, section, phase, Some(slot), target as u32);
+                constructors.push(constructor);
+                reached.push(functions);
+            }
+        }
+        for entry in symbol_table
+            .iter()
+            .filter(|x| x.is_allocated() && x.flags.constructor == SymbolConstructor::Constructor)
+        {
+            let (constructor, functions) = Constructor::new(graph, &entry.section, Phase::Init, None, entry.address);
+            constructors.push(constructor);
+            reached.push(functions);
+        }
+
+        let phase_size = |phase: Phase| -> u32 {
+            let functions: BTreeSet<usize> = constructors
+                .iter()
+                .zip(&reached)
+                .filter(|(x, _)| x.phase == phase)
+                .flat_map(|(_, functions)| functions.iter().copied())
+                .collect();
+            functions.iter().map(|&x| graph.node(x).size).sum()
+        };
+        ConstructorReport {
+            init_size: phase_size(Phase::Init),
+            fini_size: phase_size(Phase::Fini),
+            table_size,
+            constructors,
+        }
+    }
+}
+
+impl Constructor {
+    /// Creates the constructor for an array slot or marked symbol, along with the index of every
+    /// function it runs, itself included
+    fn new(graph: &CallGraph, section: &str, phase: Phase, slot: Option<u32>, target: u32) -> (Self, BTreeSet<usize>) {
+        // Thumb function pointers have the lowest bit set
+        let function = graph.find_address(target).or_else(|| graph.find_address(target & !1));
+        let reachable = function.map(|x| graph.reachable(x)).unwrap_or_default();
+        let constructor = Constructor {
+            section: section.to_string(),
+            phase,
+            slot,
+            target,
+            function: function.map(|x| x.name.clone()),
+            size: function.map_or(0, |x| x.size),
+            reachable: graph.names(&reachable.iter().copied().filter(|&x| Some(x) != function.map(|x| x.index)).collect()),
+            cumulative_size: function.map_or(0, |x| graph.cumulative_size(x, &reachable)),
+        };
+        let mut functions = reachable;
+        functions.extend(function.map(|x| x.index));
+        (constructor, functions)
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Init => write!(f, "init"),
+            Phase::Fini => write!(f, "fini"),
+        }
+    }
+}
+
+/// Formats the slot address of a constructor, using a dash for symbols marked as constructors
+fn slot_string(constructor: &Constructor) -> String {
+    constructor
+        .slot
+        .map(|x| format!("{:08x}", x))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Formats the function a constructor calls, falling back to its address if no function was found
+fn function_string(constructor: &Constructor) -> String {
+    constructor
+        .function
+        .clone()
+        .unwrap_or_else(|| format!("{:08x}", constructor.target))
+}
+
+impl fmt::Display for ConstructorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .constructors
+            .iter()
+            .map(|x| function_string(x).len())
+            .max()
+            .unwrap_or(0)
+            .max("Function".len());
+        writeln!(
+            f,
+            "{:14} {:8} {:w$} {:>8} {:>10} Reaches",
+            "Section",
+            "Slot",
+            "Function",
+            "Size",
+            "Cumulative",
+            w = width
+        )?;
+        for constructor in &self.constructors {
+            writeln!(
+                f,
+                "{:14} {:8} {:w$} {:>8} {:>10} {}",
+                constructor.section,
+                slot_string(constructor),
+                function_string(constructor),
+                constructor.size,
+                constructor.cumulative_size,
+                constructor.reachable.join(", "),
+                w = width
+            )?;
+        }
+        writeln!(f, "Constructor tables: {} bytes", self.table_size)?;
+        writeln!(f, "Code run before main: {} bytes", self.init_size)?;
+        writeln!(f, "Code run at exit: {} bytes", self.fini_size)
+    }
+}
+
+impl Report for ConstructorReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Static Constructors";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Static Constructors";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                th: "Slot";
+                                th: "Function";
+                                th: "Size";
+                                th: "Cumulative Size";
+                                th: "Reaches";
+                            }
+                        }
+                        tbody {
+                            @ for constructor in &self.constructors {
+                                tr {
+                                    td: &constructor.section;
+                                    td: slot_string(constructor);
+                                    td: function_string(constructor);
+                                    td: constructor.size;
+                                    td: constructor.cumulative_size;
+                                    td: constructor.reachable.join(", ");
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Constructor tables: {} bytes", self.table_size);
+                    h2 : format!("Code run before main: {} bytes", self.init_size);
+                    h2 : format!("Code run at exit: {} bytes", self.fini_size);
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("section,phase,slot,target,function,size,cumulative_size,reachable\n");
+        for constructor in &self.constructors {
+            csv += &format!(
+                "{},{},{},{:08x},{},{},{},{}\n",
+                csv_field(&constructor.section),
+                constructor.phase,
+                constructor.slot.map(|x| format!("{:08x}", x)).unwrap_or_default(),
+                constructor.target,
+                csv_field(constructor.function.as_deref().unwrap_or("")),
+                constructor.size,
+                constructor.cumulative_size,
+                csv_field(&constructor.reachable.join(" "))
+            );
+        }
+        csv
+    }
+}
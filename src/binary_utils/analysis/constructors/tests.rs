@@ -0,0 +1,97 @@
+use super::*;
+
+use crate::binary_utils::parser::disassembly::Disassembly;
+use crate::binary_utils::test_utils::lines;
+
+fn firmware() -> Vec<String> {
+    lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "08000100 l     F .text	00000008 _GLOBAL__sub_I_logger",
+        "08000108 l     F .text	00000008 _GLOBAL__sub_I_registry",
+        "08000110 g     F .text	00000010 Logger::Logger()",
+        "08000120 g     F .text	00000020 malloc",
+        "08000140 l     F .text	00000004 cleanup",
+        "08000144 g     F .text	00000004 legacy_init",
+        "08001000 l     O .init_array	00000008 __init_array",
+        "",
+        "Contents of section .init_array:",
+        " 8001000 01010008 09010008                    ........        ",
+        "Contents of section .fini_array:",
+        " 8001008 41010008                             A...            ",
+        "Contents of section .ctors:",
+        " 800100c ffffffff 45010008 00000000           ....E.......    ",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "08000100 <_GLOBAL__sub_I_logger>:",
+        " 8000100:	f000 f806 	bl	8000110 <Logger::Logger()>",
+        " 8000104:	4770      	bx	lr",
+        " 8000106:	bf00      	nop",
+        "",
+        "08000108 <_GLOBAL__sub_I_registry>:",
+        " 8000108:	f000 f80a 	bl	8000120 <malloc>",
+        " 800010c:	4770      	bx	lr",
+        " 800010e:	bf00      	nop",
+        "",
+        "08000110 <Logger::Logger()>:",
+        " 8000110:	f000 f806 	bl	8000120 <malloc>",
+        " 8000114:	4770      	bx	lr",
+        "",
+        "08000120 <malloc>:",
+        " 8000120:	4770      	bx	lr",
+        "",
+        "08000140 <cleanup>:",
+        " 8000140:	4770      	bx	lr",
+        "",
+        "08000144 <legacy_init>:",
+        " 8000144:	4770      	bx	lr",
+    ])
+}
+
+#[test]
+fn test_constructor_report() {
+    let lines = firmware();
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let disassembly = Disassembly::from_lines(&lines);
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = ConstructorReport::new(&graph, &SectionContents::from_lines(&lines), &symbol_table);
+
+    let functions: Vec<(&str, Phase, Option<u32>, Option<&str>)> = report
+        .constructors
+        .iter()
+        .map(|x| (x.section.as_str(), x.phase, x.slot, x.function.as_deref()))
+        .collect();
+    assert_eq!(
+        functions,
+        vec![
+            (".init_array", Phase::Init, Some(0x08001000), Some("_GLOBAL__sub_I_logger")),
+            (".init_array", Phase::Init, Some(0x08001004), Some("_GLOBAL__sub_I_registry")),
+            (".ctors", Phase::Init, Some(0x08001010), Some("legacy_init")),
+            (".fini_array", Phase::Fini, Some(0x08001008), Some("cleanup")),
+        ]
+    );
+    assert_eq!(report.constructors[0].reachable, vec!["Logger::Logger()".to_string(), "malloc".to_string()]);
+    assert_eq!(report.constructors[0].cumulative_size, 0x8 + 0x10 + 0x20);
+    assert_eq!(report.table_size, 0x18);
+    // malloc is reached from both constructors but only counted once
+    assert_eq!(report.init_size, 0x8 + 0x8 + 0x10 + 0x20 + 0x4);
+    assert_eq!(report.fini_size, 0x4);
+}
+
+#[test]
+fn test_constructor_report_formats() {
+    let lines = firmware();
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let disassembly = Disassembly::from_lines(&lines);
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = ConstructorReport::new(&graph, &SectionContents::from_lines(&lines), &symbol_table);
+
+    assert!(report.to_string().contains("Code run before main: 68 bytes"));
+    assert!(report
+        .to_csv()
+        .contains(".fini_array,fini,08001008,08000141,cleanup,4,4,\n"));
+    assert!(report.to_html().contains("<td>_GLOBAL__sub_I_registry</td>"));
+    assert!(report.to_json().contains("\"phase\": \"fini\""));
+}
@@ -0,0 +1,278 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::analysis::symbol_keys::SymbolKeys;
+use crate::binary_utils::parser::symbol_table::{is_thread_local_section, SymbolTable};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// How a symbol changed between two builds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+/// A symbol matched between two builds by its key
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SymbolDiff {
+    pub key: String,
+    /// Name in the old build, if it was there
+    pub old_name: Option<String>,
+    /// Name in the new build, if it is there
+    pub new_name: Option<String>,
+    pub section: String,
+    pub old_size: u32,
+    pub new_size: u32,
+    pub delta: i64,
+    pub status: DiffStatus,
+}
+
+/// The size of one section in both builds
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionDiff {
+    pub section: String,
+    pub old_size: u32,
+    pub new_size: u32,
+    pub delta: i64,
+}
+
+/// The differences between the symbol tables of two builds
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DiffReport {
+    /// Symbols that were added, removed or changed size, largest change first
+    pub symbols: Vec<SymbolDiff>,
+    /// Number of symbols with the same size in both builds
+    pub unchanged: usize,
+    pub sections: Vec<SectionDiff>,
+    pub old_total: u32,
+    pub new_total: u32,
+    pub delta: i64,
+}
+
+impl DiffReport {
+    /// Compares two symbol tables. Symbols are matched by their keys, so names that only differ in
+    /// build specific numbers or hashes are treated as the same symbol
+    ///
+    /// # Arguments
+    /// * 'old' - The symbol table of the old build
+    /// * 'new' - The symbol table of the new build
+    /// * 'keys' - The rules that give each symbol its key
+    pub fn new(old: &SymbolTable, new: &SymbolTable, keys: &SymbolKeys) -> Self {
+        let old_keys: BTreeMap<String, _> = keys.keys(old).into_iter().collect();
+        let new_keys: BTreeMap<String, _> = keys.keys(new).into_iter().collect();
+
+        let mut symbols = Vec::new();
+        let mut unchanged = 0;
+        for key in old_keys.keys().chain(new_keys.keys()).collect::<BTreeSet<_>>() {
+            let (old_entry, new_entry) = (old_keys.get(key), new_keys.get(key));
+            let old_size = old_entry.map_or(0, |x| x.alignment_or_size);
+            let new_size = new_entry.map_or(0, |x| x.alignment_or_size);
+            let status = match (old_entry, new_entry) {
+                (None, _) => DiffStatus::Added,
+                (_, None) => DiffStatus::Removed,
+                _ if old_size != new_size => DiffStatus::Changed,
+                _ => DiffStatus::Unchanged,
+            };
+            if status == DiffStatus::Unchanged {
+                unchanged += 1;
+                continue;
+            }
+            symbols.push(SymbolDiff {
+                key: key.clone(),
+                old_name: old_entry.map(|x| x.name.clone()),
+                new_name: new_entry.map(|x| x.name.clone()),
+                section: new_entry.or(old_entry).map(|x| x.section.clone()).unwrap_or_default(),
+                old_size,
+                new_size,
+                delta: new_size as i64 - old_size as i64,
+                status,
+            });
+        }
+        symbols.sort_by(|x, y| y.delta.abs().cmp(&x.delta.abs()).then(x.key.cmp(&y.key)));
+
+        let (old_sections, new_sections) = (old.section_sizes(), new.section_sizes());
+        let sections: Vec<SectionDiff> = old_sections
+            .keys()
+            .chain(new_sections.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|name| {
+                let old_size = old_sections.get(name).copied().unwrap_or(0);
+                let new_size = new_sections.get(name).copied().unwrap_or(0);
+                SectionDiff {
+                    section: name.clone(),
+                    old_size,
+                    new_size,
+                    delta: new_size as i64 - old_size as i64,
+                }
+            })
+            .collect();
+        // Thread local sections are listed but not totalled, like `SymbolTable::total_size`
+        let totalled = || sections.iter().filter(|x| !is_thread_local_section(&x.section));
+        let old_total = totalled().map(|x| x.old_size).sum();
+        let new_total = totalled().map(|x| x.new_size).sum();
+
+        DiffReport {
+            symbols,
+            unchanged,
+            sections,
+            old_total,
+            new_total,
+            delta: new_total as i64 - old_total as i64,
+        }
+    }
+
+    /// Finds the change of a symbol by its key
+    pub fn find(&self, key: &str) -> Option<&SymbolDiff> {
+        self.symbols.iter().find(|x| x.key == key)
+    }
+}
+
+impl SymbolDiff {
+    /// Formats the symbol's name, showing both names if it was renamed
+    fn display_name(&self) -> String {
+        match (&self.old_name, &self.new_name) {
+            (Some(old), Some(new)) if old != new => format!("{} -> {}", old, new),
+            (_, Some(name)) | (Some(name), None) => name.clone(),
+            (None, None) => self.key.clone(),
+        }
+    }
+}
+
+impl fmt::Display for DiffStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffStatus::Added => write!(f, "added"),
+            DiffStatus::Removed => write!(f, "removed"),
+            DiffStatus::Changed => write!(f, "changed"),
+            DiffStatus::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>8} {:>8} {:>8} {:9} {:10} Symbol", "Old", "New", "Delta", "Status", "Section")?;
+        for symbol in &self.symbols {
+            writeln!(
+                f,
+                "{:>8} {:>8} {:>+8} {:9} {:10} {}",
+                symbol.old_size,
+                symbol.new_size,
+                symbol.delta,
+                symbol.status.to_string(),
+                symbol.section,
+                symbol.display_name()
+            )?;
+        }
+        writeln!(f, "{} symbols unchanged", self.unchanged)?;
+        writeln!(f, "{:>8} {:>8} {:>8} Section", "Old", "New", "Delta")?;
+        for section in self.sections.iter().filter(|x| x.delta != 0) {
+            writeln!(f, "{:>8} {:>8} {:>+8} {}", section.old_size, section.new_size, section.delta, section.section)?;
+        }
+        writeln!(f, "{:>8} {:>8} {:>+8} Total", self.old_total, self.new_total, self.delta)
+    }
+}
+
+impl Report for DiffReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Symbol Diff";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Symbol Diff";
+                    h2 : format!("{} -> {} bytes ({:+})", self.old_total, self.new_total, self.delta);
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Symbol";
+                                th: "Section";
+                                th: "Status";
+                                th: "Old";
+                                th: "New";
+                                th: "Delta";
+                            }
+                        }
+                        tbody {
+                            @ for symbol in &self.symbols {
+                                tr {
+                                    td: symbol.display_name();
+                                    td: &symbol.section;
+                                    td: symbol.status.to_string();
+                                    td: symbol.old_size;
+                                    td: symbol.new_size;
+                                    td: format!("{:+}", symbol.delta);
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Sections";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                th: "Old";
+                                th: "New";
+                                th: "Delta";
+                            }
+                        }
+                        tbody {
+                            @ for section in &self.sections {
+                                tr {
+                                    td: &section.section;
+                                    td: section.old_size;
+                                    td: section.new_size;
+                                    td: format!("{:+}", section.delta);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,key,old_name,new_name,section,status,old_size,new_size,delta\n");
+        for symbol in &self.symbols {
+            csv += &format!(
+                "symbol,{},{},{},{},{},{},{},{}\n",
+                csv_field(&symbol.key),
+                csv_field(symbol.old_name.as_deref().unwrap_or("")),
+                csv_field(symbol.new_name.as_deref().unwrap_or("")),
+                csv_field(&symbol.section),
+                symbol.status,
+                symbol.old_size,
+                symbol.new_size,
+                symbol.delta
+            );
+        }
+        for section in &self.sections {
+            csv += &format!(
+                "section,{},,,,,{},{},{}\n",
+                csv_field(&section.section),
+                section.old_size,
+                section.new_size,
+                section.delta
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn old_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 crtstuff.c",
+        "20000000 l     O .bss	00000001 completed.8060",
+        "00000000 l    df *ABS*	00000000 main.c",
+        "08000000 l     F .text	00000010 scale.constprop.0",
+        "08000010 g     F .text	00000040 main",
+        "08000050 g     F .text	00000020 legacy",
+        "20000004 g     O .bss	00000010 buffer",
+    ]))
+}
+
+fn new_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 crtstuff.c",
+        "20000000 l     O .bss	00000001 completed.8061",
+        "00000000 l    df *ABS*	00000000 main.c",
+        "08000000 l     F .text	00000010 scale.constprop.1",
+        "08000010 g     F .text	00000048 main",
+        "08000058 g     F .text	00000030 feature",
+        "20000004 g     O .bss	00000010 buffer",
+    ]))
+}
+
+#[test]
+fn test_diff_matches_renamed_symbols() {
+    let report = DiffReport::new(&old_table(), &new_table(), &SymbolKeys::default());
+    let changes: Vec<(&str, DiffStatus, i64)> = report
+        .symbols
+        .iter()
+        .map(|x| (x.key.as_str(), x.status, x.delta))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![("feature", DiffStatus::Added, 0x30), ("legacy", DiffStatus::Removed, -0x20), ("main", DiffStatus::Changed, 8)]
+    );
+    // completed, scale.constprop and buffer only changed their numbers, or nothing at all
+    assert_eq!(report.unchanged, 3);
+    assert_eq!(report.delta, 0x18);
+}
+
+#[test]
+fn test_diff_without_keys_sees_noise() {
+    let keys: SymbolKeys = "compilers = []".parse().unwrap();
+    let report = DiffReport::new(&old_table(), &new_table(), &keys);
+    assert_eq!(report.find("crtstuff.c:completed.8060").unwrap().status, DiffStatus::Removed);
+    assert_eq!(report.find("main.c:scale.constprop.1").unwrap().status, DiffStatus::Added);
+}
+
+#[test]
+fn test_diff_sections_and_csv() {
+    let report = DiffReport::new(&old_table(), &new_table(), &SymbolKeys::default());
+    let sections: Vec<(&str, i64)> = report.sections.iter().map(|x| (x.section.as_str(), x.delta)).collect();
+    assert_eq!(sections, vec![(".bss", 0), (".text", 0x18)]);
+    let csv = report.to_csv();
+    assert!(csv.contains("symbol,main,main,main,.text,changed,64,72,8\n"));
+    assert!(csv.contains("section,.text,,,,,112,136,24\n"));
+}
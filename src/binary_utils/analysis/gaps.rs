@@ -0,0 +1,285 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::disassembly::Disassembly;
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{SymbolTable, SymbolTableEntry};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// The most likely reason for bytes that no symbol covers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GapCause {
+    /// Padding inserted so the following symbol starts on an aligned address
+    Alignment,
+    /// Padding instructions or zeros that are larger than any alignment would need
+    Fill,
+    /// Real content without a symbol, like literal pools, jump tables or local labels
+    Unlabelled,
+}
+
+/// A range of bytes inside a section that is not covered by any symbol
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Gap {
+    pub address: u32,
+    pub size: u32,
+    /// The symbol that ends where the gap starts, if any
+    pub after: Option<String>,
+    /// The symbol that starts where the gap ends, if any
+    pub before: Option<String>,
+    pub cause: GapCause,
+}
+
+/// All gaps found in one section
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionGaps {
+    pub section: String,
+    pub gaps: Vec<Gap>,
+    pub total: u32,
+    pub alignment_waste: u32,
+}
+
+/// Gaps between the symbols of every section, ordered by address
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GapReport {
+    pub sections: Vec<SectionGaps>,
+}
+
+/// Alignment assumed for symbols when the section's own alignment is not known
+const DEFAULT_MAX_ALIGNMENT: u32 = 16;
+
+impl GapReport {
+    /// Finds the gaps between consecutive symbols in each section. Gaps after a zero sized symbol
+    /// are skipped since where that symbol ends is unknown
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to analyze
+    /// * 'section_table' - Optional section headers, used to find gaps at the start and end of sections
+    /// * 'disassembly' - Optional disassembly, used to tell padding instructions apart from unlabelled code
+    pub fn new(
+        symbol_table: &SymbolTable,
+        section_table: Option<&SectionTable>,
+        disassembly: Option<&Disassembly>,
+    ) -> Self {
+        let mut by_section: BTreeMap<&str, Vec<&SymbolTableEntry>> = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated() && !x.is_section_symbol()) {
+            by_section.entry(&entry.section).or_default().push(entry);
+        }
+
+        let mut sections = Vec::new();
+        for (name, mut symbols) in by_section {
+            // Sort by address and keep the largest of any symbols that share an address
+            symbols.sort_by(|x, y| {
+                x.address
+                    .cmp(&y.address)
+                    .then(y.alignment_or_size.cmp(&x.alignment_or_size))
+            });
+            symbols.dedup_by_key(|x| x.address);
+
+            let header = section_table.and_then(|x| x.find(name));
+            let max_alignment = header.map(|x| x.alignment).unwrap_or(DEFAULT_MAX_ALIGNMENT);
+            let has_contents = header.map(|x| x.has_contents()).unwrap_or(true);
+            let instructions = disassembly.map(|x| x.index_section(name));
+            let classify = |start: u32, end: u64, aligned: bool| {
+                if aligned && is_alignment_padding(start, end, max_alignment) {
+                    return GapCause::Alignment;
+                }
+                if !has_contents {
+                    return GapCause::Fill;
+                }
+                let instructions = instructions.as_ref().map_or(&[][..], |x| x.in_range(start, end));
+                if !instructions.is_empty() && instructions.iter().all(|x| x.is_padding()) {
+                    GapCause::Fill
+                } else {
+                    GapCause::Unlabelled
+                }
+            };
+
+            let mut gaps = Vec::new();
+            if let (Some(header), Some(first)) = (header, symbols.first()) {
+                if first.address > header.vma {
+                    gaps.push(Gap {
+                        address: header.vma,
+                        size: first.address - header.vma,
+                        after: None,
+                        before: Some(first.name.clone()),
+                        cause: classify(header.vma, first.address.into(), false),
+                    });
+                }
+            }
+            // The furthest end of any symbol so far and the symbol that reaches it, since symbols
+            // can be nested inside others. None once a zero sized symbol starts past it
+            let mut covered: Option<(u64, &SymbolTableEntry)> = None;
+            for symbol in &symbols {
+                if let Some((end, after)) = covered {
+                    if symbol.address as u64 > end {
+                        gaps.push(Gap {
+                            address: end as u32,
+                            size: symbol.address - end as u32,
+                            after: Some(after.name.clone()),
+                            before: Some(symbol.name.clone()),
+                            cause: classify(end as u32, symbol.address.into(), true),
+                        });
+                    }
+                }
+                let end = symbol.address as u64 + symbol.alignment_or_size as u64;
+                match covered {
+                    Some((covered_end, _)) if (symbol.address as u64) < covered_end => {
+                        if end > covered_end {
+                            covered = Some((end, symbol));
+                        }
+                    }
+                    _ if symbol.alignment_or_size == 0 => covered = None,
+                    _ => covered = Some((end, symbol)),
+                }
+            }
+            if let (Some(header), Some((end, last))) = (header, covered) {
+                let section_end = header.vma as u64 + header.size as u64;
+                if section_end > end {
+                    gaps.push(Gap {
+                        address: end as u32,
+                        size: (section_end - end) as u32,
+                        after: Some(last.name.clone()),
+                        before: None,
+                        cause: classify(end as u32, section_end, false),
+                    });
+                }
+            }
+
+            if !gaps.is_empty() {
+                sections.push(SectionGaps {
+                    section: name.to_string(),
+                    total: gaps.iter().map(|x| x.size).sum(),
+                    alignment_waste: gaps
+                        .iter()
+                        .filter(|x| x.cause == GapCause::Alignment)
+                        .map(|x| x.size)
+                        .sum(),
+                    gaps,
+                });
+            }
+        }
+        GapReport { sections }
+    }
+
+    /// Gets the total alignment waste across all sections
+    pub fn alignment_waste(&self) -> u32 {
+        self.sections.iter().map(|x| x.alignment_waste).sum()
+    }
+}
+
+/// Checks if a gap ends on the next address aligned to the largest power of two that the end
+/// address is a multiple of, capped at the maximum alignment of the section
+fn is_alignment_padding(start: u32, end: u64, max_alignment: u32) -> bool {
+    if end == 0 {
+        return false;
+    }
+    let alignment = (1u64 << end.trailing_zeros()).min(max_alignment.max(1) as u64);
+    let aligned = (start as u64).div_ceil(alignment) * alignment;
+    aligned == end
+}
+
+impl fmt::Display for GapCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GapCause::Alignment => write!(f, "alignment"),
+            GapCause::Fill => write!(f, "fill"),
+            GapCause::Unlabelled => write!(f, "unlabelled"),
+        }
+    }
+}
+
+impl fmt::Display for GapReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in &self.sections {
+            writeln!(
+                f,
+                "{}: {} gaps, {} bytes ({} alignment waste)",
+                section.section,
+                section.gaps.len(),
+                section.total,
+                section.alignment_waste
+            )?;
+            writeln!(f, "  {:8} {:>8} {:10} Between", "Address", "Size", "Cause")?;
+            for gap in &section.gaps {
+                writeln!(
+                    f,
+                    "  {:08x} {:>8} {:10} {} -> {}",
+                    gap.address,
+                    gap.size,
+                    gap.cause.to_string(),
+                    gap.after.as_deref().unwrap_or("(section start)"),
+                    gap.before.as_deref().unwrap_or("(section end)")
+                )?;
+            }
+        }
+        writeln!(f, "Total alignment waste: {} bytes", self.alignment_waste())
+    }
+}
+
+impl Report for GapReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Section Gaps";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Section Gaps";
+                    @ for section in &self.sections {
+                        h2 : format!("{}: {} bytes ({} alignment waste)", section.section, section.total, section.alignment_waste);
+                        table(class="tg") {
+                            thead {
+                                tr {
+                                    th: "Address";
+                                    th: "Size";
+                                    th: "Cause";
+                                    th: "After";
+                                    th: "Before";
+                                }
+                            }
+                            tbody {
+                                @ for gap in &section.gaps {
+                                    tr {
+                                        td: format!("{:08x}", gap.address);
+                                        td: gap.size;
+                                        td: gap.cause.to_string();
+                                        td: gap.after.as_deref().unwrap_or("-");
+                                        td: gap.before.as_deref().unwrap_or("-");
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("section,address,size,cause,after,before\n");
+        for section in &self.sections {
+            for gap in &section.gaps {
+                csv += &format!(
+                    "{},{:08x},{},{},{},{}\n",
+                    csv_field(&section.section),
+                    gap.address,
+                    gap.size,
+                    gap.cause,
+                    csv_field(gap.after.as_deref().unwrap_or("")),
+                    csv_field(gap.before.as_deref().unwrap_or(""))
+                );
+            }
+        }
+        csv
+    }
+}
@@ -0,0 +1,117 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_is_alignment_padding() {
+    assert!(is_alignment_padding(0x10a8, 0x10b0, 16));
+    assert!(is_alignment_padding(0x4021, 0x4040, 32));
+    assert!(!is_alignment_padding(0x4021, 0x4040, 16));
+    assert!(!is_alignment_padding(0x1000, 0x1020, 16));
+}
+
+#[test]
+fn test_gaps_between_symbols() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00001060 g     F .text	00000048 main",
+        "000010b0 g     F .text	00000022 _start",
+        "000010e0 l     F .text	00000000 deregister_tm_clones",
+        "00001110 l     F .text	00000010 register_tm_clones",
+        "00001200 l     F .text	00000010 frame_dummy",
+    ]));
+    let report = GapReport::new(&symbol_table, None, None);
+    assert_eq!(report.sections.len(), 1);
+    let text = &report.sections[0];
+    assert_eq!(
+        text.gaps,
+        vec![
+            Gap {
+                address: 0x10a8,
+                size: 8,
+                after: Some("main".to_string()),
+                before: Some("_start".to_string()),
+                cause: GapCause::Alignment
+            },
+            Gap {
+                address: 0x10d2,
+                size: 0xe,
+                after: Some("_start".to_string()),
+                before: Some("deregister_tm_clones".to_string()),
+                cause: GapCause::Alignment
+            },
+            Gap {
+                address: 0x1120,
+                size: 0xe0,
+                after: Some("register_tm_clones".to_string()),
+                before: Some("frame_dummy".to_string()),
+                cause: GapCause::Unlabelled
+            },
+        ]
+    );
+    assert_eq!(text.total, 0xf6);
+    assert_eq!(text.alignment_waste, 0x16);
+}
+
+#[test]
+fn test_nested_symbols_are_not_gaps() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "08000000 g     F .text	00000040 handler",
+        "08000010 l     F .text	00000008 handler_fast_path",
+        "08000020 l       .text	00000000 handler_exit",
+        "08000048 g     F .text	00000010 next",
+    ]));
+    let report = GapReport::new(&symbol_table, None, None);
+    let gaps: Vec<(u32, u32, Option<&str>)> = report.sections[0]
+        .gaps
+        .iter()
+        .map(|x| (x.address, x.size, x.after.as_deref()))
+        .collect();
+    assert_eq!(gaps, vec![(0x8000040, 8, Some("handler"))]);
+}
+
+#[test]
+fn test_gaps_use_disassembly_and_section_headers() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00001000 g     F .text	00000010 first",
+        "00001020 g     F .text	00000008 second",
+        "00004000 l     O .bss	00000004 counter",
+    ]));
+    let section_table = SectionTable::from_lines(&lines(&[
+        "  0 .text         00000040  00001000  00001000  00001000  2**2",
+        "                  CONTENTS, ALLOC, LOAD, READONLY, CODE",
+        "  1 .bss          00000020  00004000  00004000  00002000  2**2",
+        "                  ALLOC",
+    ]));
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "Disassembly of section .text:",
+        "00001000 <first>:",
+        "    1000:	00 00 00 00 00 00 00 00 	nop",
+        "    1008:	00 00 00 00 00 00 00 00 	nop",
+        "    1010:	90                   	nop",
+        "    1011:	0f 1f 00             	nopl   (%rax)",
+        "00001020 <second>:",
+        "    1020:	c3                   	ret",
+        "    1028:	ff ff ff ff ff ff ff ff 	(bad)",
+    ]));
+    let report = GapReport::new(&symbol_table, Some(&section_table), Some(&disassembly));
+    let bss = &report.sections[0];
+    assert_eq!(bss.section, ".bss");
+    assert_eq!(bss.gaps[0].size, 0x1c);
+    assert_eq!(bss.gaps[0].cause, GapCause::Fill);
+
+    let text = &report.sections[1];
+    let causes: Vec<GapCause> = text.gaps.iter().map(|x| x.cause).collect();
+    assert_eq!(causes, vec![GapCause::Fill, GapCause::Unlabelled]);
+    assert_eq!(text.gaps[1].before, None);
+}
+
+#[test]
+fn test_symbol_ending_at_the_top_of_memory() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "ffffff00 g     F .text	00000020 low",
+        "ffffff40 g     F .text	000000c0 top",
+    ]));
+    let report = GapReport::new(&symbol_table, None, None);
+    assert_eq!(report.sections[0].gaps.len(), 1);
+    assert_eq!(report.sections[0].gaps[0].address, 0xffffff20);
+    assert_eq!(report.sections[0].gaps[0].size, 0x20);
+}
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::binary_utils::parser::symbol_table::SymbolTable;
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// A labelled record of the section, file and symbol sizes of one build
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Snapshot {
+    pub build: String,
+    pub commit: Option<String>,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub sections: BTreeMap<String, u32>,
+    pub files: BTreeMap<String, u32>,
+    pub symbols: BTreeMap<String, u32>,
+}
+
+/// A JSON lines file holding one snapshot per line, oldest first
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+/// What a history query tracks over time
+#[derive(Debug, PartialEq)]
+pub enum HistoryQuery {
+    Section(String),
+    File(String),
+    Symbol(String),
+}
+
+/// The size of the queried item in a single snapshot
+#[derive(Debug, PartialEq)]
+pub struct HistoryPoint {
+    pub build: String,
+    pub commit: Option<String>,
+    pub timestamp: u64,
+    /// None if the item did not exist in the snapshot
+    pub size: Option<u32>,
+    /// Change in size from the previous snapshot
+    pub delta: i64,
+}
+
+/// The sizes of one queried item across every recorded snapshot
+#[derive(Debug, PartialEq)]
+pub struct History {
+    pub query: HistoryQuery,
+    pub points: Vec<HistoryPoint>,
+}
+
+impl Snapshot {
+    /// Creates a snapshot of a symbol table, timestamped with the current time
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to record the sizes of
+    /// * 'build' - Name of the build (e.g., the target or configuration)
+    /// * 'commit' - Optional commit id the build was made from
+    pub fn new(symbol_table: &SymbolTable, build: &str, commit: Option<&str>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or(0);
+        let mut symbols = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated() && x.alignment_or_size > 0) {
+            *symbols.entry(entry.name.clone()).or_insert(0) += entry.alignment_or_size;
+        }
+        Snapshot {
+            build: build.to_string(),
+            commit: commit.map(String::from),
+            timestamp,
+            sections: symbol_table.section_sizes(),
+            files: symbol_table.file_sizes(),
+            symbols,
+        }
+    }
+}
+
+impl HistoryStore {
+    /// Opens a history store. The file is created on the first recorded snapshot
+    ///
+    /// # Arguments
+    /// * 'path' - Path/filename of the JSON lines store
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        HistoryStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Appends a snapshot to the end of the store
+    pub fn record(&self, snapshot: &Snapshot) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let line = serde_json::to_string(snapshot)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// Reads every snapshot in the store, oldest first
+    pub fn snapshots(&self) -> io::Result<Vec<Snapshot>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        string_utils::read_lines_from_file(&self.path)?
+            .iter()
+            .filter(|x| !x.trim().is_empty())
+            .map(|x| serde_json::from_str(x).map_err(io::Error::from))
+            .collect()
+    }
+}
+
+impl History {
+    /// Builds the history of a section, file or symbol from a list of snapshots. Each delta is
+    /// the change from the previous snapshot of the same build, so the first snapshot of a build
+    /// never counts as a change
+    ///
+    /// # Arguments
+    /// * 'snapshots' - Recorded snapshots, oldest first
+    /// * 'query' - The item to track
+    /// * 'build' - Only use the snapshots of this build, or all of them if none
+    pub fn new(snapshots: &[Snapshot], query: HistoryQuery, build: Option<&str>) -> Self {
+        let mut previous: BTreeMap<&str, i64> = BTreeMap::new();
+        let points = snapshots
+            .iter()
+            .filter(|x| build.is_none_or(|build| x.build == build))
+            .map(|x| {
+                let size = match &query {
+                    HistoryQuery::Section(name) => x.sections.get(name),
+                    HistoryQuery::File(name) => x.files.get(name),
+                    HistoryQuery::Symbol(name) => x.symbols.get(name),
+                }
+                .copied();
+                let current = size.unwrap_or(0) as i64;
+                let delta = previous.insert(&x.build, current).map_or(0, |previous| current - previous);
+                HistoryPoint {
+                    build: x.build.clone(),
+                    commit: x.commit.clone(),
+                    timestamp: x.timestamp,
+                    size,
+                    delta,
+                }
+            })
+            .collect();
+        History { query, points }
+    }
+
+    /// Keeps only the snapshots where the size changed by at least a number of bytes
+    ///
+    /// # Arguments
+    /// * 'min_delta' - Minimum absolute change in bytes
+    pub fn changes(self, min_delta: u32) -> Self {
+        History {
+            query: self.query,
+            points: self
+                .points
+                .into_iter()
+                .filter(|x| x.delta != 0 && x.delta.unsigned_abs() >= min_delta as u64)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for HistoryQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryQuery::Section(name) => write!(f, "section {}", name),
+            HistoryQuery::File(name) => write!(f, "file {}", name),
+            HistoryQuery::Symbol(name) => write!(f, "symbol {}", name),
+        }
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let build_width = self
+            .points
+            .iter()
+            .map(|x| x.build.len())
+            .chain(std::iter::once("Build".len()))
+            .max()
+            .unwrap_or(0);
+        writeln!(f, "History of {}", self.query)?;
+        writeln!(
+            f,
+            "{:19} {:bw$} {:12} {:>10} {:>10}",
+            "Date",
+            "Build",
+            "Commit",
+            "Size",
+            "Delta",
+            bw = build_width
+        )?;
+        for point in &self.points {
+            let commit: String = point.commit.as_deref().unwrap_or("-").chars().take(12).collect();
+            let size = point.size.map(|x| x.to_string()).unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "{:19} {:bw$} {:12} {:>10} {:>+10}",
+                format_timestamp(point.timestamp),
+                point.build,
+                commit,
+                size,
+                point.delta,
+                bw = build_width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats seconds since the unix epoch as a UTC date and time
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Convert days since the epoch to a civil date
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+fn snapshot(build: &str, timestamp: u64, bss: u32) -> Snapshot {
+    let mut sections = BTreeMap::new();
+    sections.insert(".bss".to_string(), bss);
+    Snapshot {
+        build: build.to_string(),
+        commit: Some(format!("{}abc", build)),
+        timestamp,
+        sections,
+        files: BTreeMap::new(),
+        symbols: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn test_snapshot_from_symbol_table() {
+    let lines: Vec<String> = vec![
+        "00000000 l    df *ABS*	00000000 main.c",
+        "20000000 l     O .bss	00000400 print_buffer",
+        "08000000 g     F .text	00000100 main",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let snapshot = Snapshot::new(&SymbolTable::from_lines(&lines), "release", Some("1234"));
+    assert_eq!(snapshot.build, "release");
+    assert_eq!(snapshot.commit, Some("1234".to_string()));
+    assert_eq!(snapshot.sections.get(".bss"), Some(&0x400));
+    assert_eq!(snapshot.sections.get(".text"), Some(&0x100));
+    assert_eq!(snapshot.files.get("main.c"), Some(&0x400));
+    assert_eq!(snapshot.symbols.get("main"), Some(&0x100));
+}
+
+#[test]
+fn test_history_of_section() {
+    let snapshots = vec![
+        snapshot("a", 0, 0x100),
+        snapshot("a", 86400, 0x100),
+        snapshot("a", 2 * 86400, 0x1100),
+    ];
+    let history = History::new(&snapshots, HistoryQuery::Section(".bss".to_string()), None);
+    let deltas: Vec<i64> = history.points.iter().map(|x| x.delta).collect();
+    assert_eq!(deltas, vec![0, 0, 0x1000]);
+
+    let changes = history.changes(0x1000);
+    assert_eq!(changes.points.len(), 1);
+    assert_eq!(changes.points[0].timestamp, 2 * 86400);
+    assert_eq!(changes.points[0].size, Some(0x1100));
+}
+
+#[test]
+fn test_history_deltas_per_build() {
+    let snapshots = vec![
+        snapshot("debug", 0, 0x800),
+        snapshot("release", 1, 0x100),
+        snapshot("debug", 2, 0x900),
+        snapshot("release", 3, 0x140),
+    ];
+    let history = History::new(&snapshots, HistoryQuery::Section(".bss".to_string()), None);
+    let deltas: Vec<i64> = history.points.iter().map(|x| x.delta).collect();
+    assert_eq!(deltas, vec![0, 0, 0x100, 0x40]);
+
+    let release = History::new(&snapshots, HistoryQuery::Section(".bss".to_string()), Some("release"));
+    let points: Vec<(u64, i64)> = release.points.iter().map(|x| (x.timestamp, x.delta)).collect();
+    assert_eq!(points, vec![(1, 0), (3, 0x40)]);
+}
+
+#[test]
+fn test_history_of_missing_item() {
+    let snapshots = vec![snapshot("a", 0, 0x100)];
+    let history = History::new(&snapshots, HistoryQuery::Symbol("main".to_string()), None);
+    assert_eq!(history.points[0].size, None);
+    assert_eq!(history.points[0].delta, 0);
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+    assert_eq!(format_timestamp(1635000000), "2021-10-23 14:40:00");
+}
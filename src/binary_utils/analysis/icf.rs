@@ -0,0 +1,199 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::disassembly::{DisassembledFunction, Disassembly, Instruction};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// How closely the functions of a duplicate group match
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Match {
+    /// Every byte of the function bodies is the same
+    Identical,
+    /// The bodies only differ in PC relative offsets to the same symbols
+    Normalised,
+}
+
+/// Functions with the same body that identical code folding could merge into one
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DuplicateGroup {
+    #[serde(rename = "match")]
+    pub kind: Match,
+    /// Size of each function body, not counting trailing padding
+    pub size: u32,
+    pub functions: Vec<String>,
+    /// Bytes saved by keeping a single copy of the function
+    pub savings: u32,
+}
+
+/// Groups of duplicate functions found in a disassembly, largest savings first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IcfReport {
+    pub groups: Vec<DuplicateGroup>,
+}
+
+impl IcfReport {
+    /// Finds functions whose bodies are byte identical, or identical once the PC relative offsets
+    /// that linking resolved are replaced with the symbols they refer to
+    ///
+    /// # Arguments
+    /// * 'disassembly' - The disassembly to search
+    /// * 'min_size' - Smallest function body, in bytes, to consider
+    pub fn new(disassembly: &Disassembly, min_size: u32) -> Self {
+        let mut candidates: BTreeMap<Vec<String>, Vec<(&DisassembledFunction, &[Instruction])>> = BTreeMap::new();
+        for function in disassembly.iter() {
+            let body = function.body();
+            let size: u32 = body.iter().map(|x| x.bytes.len() as u32).sum();
+            if size == 0 || size < min_size {
+                continue;
+            }
+            let key = body.iter().map(|x| normalise(x, &function.name)).collect();
+            candidates.entry(key).or_default().push((function, body));
+        }
+
+        let mut groups: Vec<DuplicateGroup> = candidates
+            .into_values()
+            .filter(|x| x.len() > 1)
+            .map(|functions| {
+                let bytes = |body: &[Instruction]| body.iter().flat_map(|x| x.bytes.iter().copied()).collect::<Vec<u8>>();
+                let first = bytes(functions[0].1);
+                let identical = functions.iter().all(|(_, body)| bytes(body) == first);
+                let size = first.len() as u32;
+                DuplicateGroup {
+                    kind: if identical { Match::Identical } else { Match::Normalised },
+                    size,
+                    functions: functions.iter().map(|(x, _)| x.name.clone()).collect(),
+                    savings: size * (functions.len() as u32 - 1),
+                }
+            })
+            .collect();
+        groups.sort_by(|x, y| y.savings.cmp(&x.savings).then(x.functions.cmp(&y.functions)));
+        IcfReport { groups }
+    }
+
+    /// Gets the bytes saved by folding every duplicate group
+    pub fn savings(&self) -> u32 {
+        self.groups.iter().map(|x| x.savings).sum()
+    }
+}
+
+/// Creates the comparison key of an instruction. Instructions that refer to a symbol are compared
+/// by their mnemonic, operands and symbols instead of their bytes, since the encoded offsets
+/// depend on where the function was placed. References into the function itself are renamed so
+/// that local branches compare equal. In object files the targets are only known from the
+/// relocations, so relocated instructions are compared by their relocations instead
+fn normalise(instruction: &Instruction, function: &str) -> String {
+    let symbol = |name: &str| match name.strip_prefix(function) {
+        Some(offset) if offset.is_empty() || offset.starts_with('+') => format!("self{}", offset),
+        _ => name.to_string(),
+    };
+    let prefix = format!("{}:{} {}", instruction.bytes.len(), instruction.mnemonic, instruction.masked_operands());
+
+    if !instruction.relocations.is_empty() {
+        let relocations: Vec<String> = instruction
+            .relocations
+            .iter()
+            .map(|x| match &x.symbol {
+                Some(name) => format!("{} {}{:+}", x.relocation_type, symbol(name), x.addend),
+                // Section relative relocations still point at different bytes if their addends differ
+                None => format!("{} *{:+}", x.relocation_type, x.addend),
+            })
+            .collect();
+        return format!("{} -> {}", prefix, relocations.join(","));
+    }
+
+    let references = instruction.references();
+    if references.is_empty() {
+        return instruction.bytes.iter().map(|x| format!("{:02x}", x)).collect();
+    }
+    let symbols: Vec<String> = references.iter().map(|(_, name)| symbol(name)).collect();
+    format!("{} -> {}", prefix, symbols.join(","))
+}
+
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Match::Identical => write!(f, "identical"),
+            Match::Normalised => write!(f, "normalised"),
+        }
+    }
+}
+
+impl fmt::Display for IcfReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>8} {:>8} {:10} Functions", "Size", "Savings", "Match")?;
+        for group in &self.groups {
+            writeln!(
+                f,
+                "{:>8} {:>8} {:10} {}",
+                group.size,
+                group.savings,
+                group.kind.to_string(),
+                group.functions.join(", ")
+            )?;
+        }
+        writeln!(f, "Total savings: {} bytes", self.savings())
+    }
+}
+
+impl Report for IcfReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Identical Code Folding Candidates";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Identical Code Folding Candidates";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Size";
+                                th: "Savings";
+                                th: "Match";
+                                th: "Functions";
+                            }
+                        }
+                        tbody {
+                            @ for group in &self.groups {
+                                tr {
+                                    td: group.size;
+                                    td: group.savings;
+                                    td: group.kind.to_string();
+                                    td: group.functions.join(", ");
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Total savings: {} bytes", self.savings());
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("group,match,size,savings,function\n");
+        for (i, group) in self.groups.iter().enumerate() {
+            for function in &group.functions {
+                csv += &format!(
+                    "{},{},{},{},{}\n",
+                    i + 1,
+                    group.kind,
+                    group.size,
+                    group.savings,
+                    csv_field(function)
+                );
+            }
+        }
+        csv
+    }
+}
@@ -0,0 +1,113 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn disassembly() -> Disassembly {
+    Disassembly::from_lines(&lines(&[
+        "Disassembly of section .text:",
+        "",
+        "0000000000001000 <vector_int_size>:",
+        "    1000:	48 8b 47 08          	mov    0x8(%rdi),%rax",
+        "    1004:	48 2b 07             	sub    (%rdi),%rax",
+        "    1007:	c3                   	ret",
+        "    1008:	0f 1f 84 00 00 00 00 	nopl   0x0(%rax,%rax,1)",
+        "    100f:	00 ",
+        "",
+        "0000000000001010 <vector_long_size>:",
+        "    1010:	48 8b 47 08          	mov    0x8(%rdi),%rax",
+        "    1014:	48 2b 07             	sub    (%rdi),%rax",
+        "    1017:	c3                   	ret",
+        "",
+        "0000000000001020 <log_info>:",
+        "    1020:	48 8d 3d d9 2f 00 00 	lea    0x2fd9(%rip),%rdi        # 4000 <log_buffer>",
+        "    1027:	74 02                	je     102b <log_info+0xb>",
+        "    1029:	e8 d2 ff ff ff       	call   1000 <vector_int_size>",
+        "    102e:	c3                   	ret",
+        "",
+        "0000000000001030 <log_warning>:",
+        "    1030:	48 8d 3d c9 2f 00 00 	lea    0x2fc9(%rip),%rdi        # 4000 <log_buffer>",
+        "    1037:	74 02                	je     103b <log_warning+0xb>",
+        "    1039:	e8 c2 ff ff ff       	call   1000 <vector_int_size>",
+        "    103e:	c3                   	ret",
+        "",
+        "0000000000001040 <log_error>:",
+        "    1040:	48 8d 3d b9 2f 00 00 	lea    0x2fb9(%rip),%rsi        # 4000 <log_buffer>",
+        "    1047:	74 02                	je     104b <log_error+0xb>",
+        "    1049:	e8 b2 ff ff ff       	call   1000 <vector_int_size>",
+        "    104e:	c3                   	ret",
+    ]))
+}
+
+#[test]
+fn test_identical_and_normalised_groups() {
+    let report = IcfReport::new(&disassembly(), 0);
+    assert_eq!(
+        report.groups,
+        vec![
+            DuplicateGroup {
+                kind: Match::Normalised,
+                size: 15,
+                functions: vec!["log_info".to_string(), "log_warning".to_string()],
+                savings: 15,
+            },
+            DuplicateGroup {
+                kind: Match::Identical,
+                size: 8,
+                functions: vec!["vector_int_size".to_string(), "vector_long_size".to_string()],
+                savings: 8,
+            },
+        ]
+    );
+    assert_eq!(report.savings(), 23);
+}
+
+#[test]
+fn test_min_size() {
+    let report = IcfReport::new(&disassembly(), 10);
+    assert_eq!(report.groups.len(), 1);
+    assert_eq!(report.groups[0].functions[0], "log_info");
+}
+
+#[test]
+fn test_normalise_keeps_registers_and_symbols() {
+    let (_, first) =
+        parse("    1020:	48 8d 3d d9 2f 00 00 	lea    0x2fd9(%rip),%rdi        # 4000 <log_buffer>");
+    let (_, second) =
+        parse("    1040:	48 8d 3d b9 2f 00 00 	lea    0x2fb9(%rip),%rdi        # 4000 <log_buffer>");
+    assert_eq!(normalise(&first, "a"), normalise(&second, "b"));
+    assert_eq!(normalise(&first, "a"), "7:lea (%rip),%rdi -> log_buffer");
+
+    let (_, branch) = parse("    1027:	74 02                	je     102b <log_info+0xb>");
+    assert_eq!(normalise(&branch, "log_info"), "2:je  -> self+0xb");
+
+    let (_, literal) = parse(" 8000128:	4b03      	ldr	r3, [pc, #12]	@ (8000138 <main+0x18>)");
+    assert_eq!(normalise(&literal, "main"), "2:ldr r3, [pc] -> self+0x18");
+}
+
+fn parse(line: &str) -> (&str, Instruction) {
+    crate::binary_utils::parser::disassembly::parse_instruction(line).unwrap()
+}
+
+#[test]
+fn test_object_file_calls_to_different_functions_differ() {
+    let function = |name: &str, target: &str| {
+        vec![
+            format!("0000000000000000 <{}>:", name),
+            "   0:	48 83 ec 08          	sub    $0x8,%rsp".to_string(),
+            "   4:	e8 00 00 00 00       	call   9 <main+0x9>".to_string(),
+            format!("			5: R_X86_64_PLT32	{}-0x4", target),
+            "   9:	48 83 c4 08          	add    $0x8,%rsp".to_string(),
+            "   d:	c3                   	ret".to_string(),
+            String::new(),
+        ]
+    };
+    let input = |targets: [&str; 2]| {
+        let mut input = vec!["Disassembly of section .text:".to_string(), String::new()];
+        input.extend(function("first", targets[0]));
+        input.extend(function("second", targets[1]));
+        input
+    };
+    assert!(IcfReport::new(&Disassembly::from_lines(&input(["puts", "printf"])), 0).groups.is_empty());
+    let report = IcfReport::new(&Disassembly::from_lines(&input(["puts", "puts"])), 0);
+    assert_eq!(report.groups.len(), 1);
+    assert_eq!(report.groups[0].kind, Match::Identical);
+}
@@ -0,0 +1,358 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::fmt;
+
+use crate::binary_utils::parser::disassembly::{Disassembly, Instruction};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// ARM condition code suffixes of conditional branches, e.g. `bne` or `blt.n`
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "cs", "hs", "cc", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le",
+];
+
+/// Branch mnemonics of ARM, AArch64 and RISC-V, without any condition code or width suffix
+const BRANCHES: [&str; 28] = [
+    "b", "bl", "blx", "bx", "br", "blr", "ret", "cbz", "cbnz", "tbz", "tbnz", "tbb", "tbh", "j", "jal", "jalr",
+    "jr", "call", "tail", "beqz", "bnez", "blez", "bgez", "bltz", "bgtz", "bltu", "bgeu", "bgtu",
+];
+
+/// RISC-V load and store mnemonics, without the `c.` prefix of compressed encodings
+const RISCV_LOADS: [&str; 13] = [
+    "lb", "lh", "lw", "ld", "lbu", "lhu", "lwu", "flw", "fld", "lwsp", "ldsp", "flwsp", "fldsp",
+];
+const RISCV_STORES: [&str; 10] = ["sb", "sh", "sw", "sd", "fsw", "fsd", "swsp", "sdsp", "fswsp", "fsdsp"];
+
+/// Instruction counts and encoding sizes of a block of code
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct InstructionMix {
+    /// Number of instructions, not counting literal pool data or trailing padding
+    pub instructions: u32,
+    /// Bytes of instructions, not counting literal pool data
+    pub code_bytes: u32,
+    pub average_length: f64,
+    /// Instructions with a 16 bit encoding, e.g. Thumb or RISC-V compressed instructions
+    pub narrow: u32,
+    /// Instructions with a 32 bit encoding, e.g. Thumb-2 or full RISC-V instructions
+    pub wide: u32,
+    /// Calls, jumps, conditional branches and returns
+    pub branches: u32,
+    pub loads: u32,
+    pub stores: u32,
+    /// Bytes of constants placed between the instructions, e.g. ARM `.word` directives
+    pub literal_pool_bytes: u32,
+}
+
+/// The instruction mix of a single function
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FunctionMix {
+    pub function: String,
+    #[serde(flatten)]
+    pub mix: InstructionMix,
+}
+
+/// Instruction mix of every function in a disassembly, largest code size first, along with totals
+#[derive(Debug, PartialEq, Serialize)]
+pub struct InstructionMixReport {
+    pub functions: Vec<FunctionMix>,
+    pub total: InstructionMix,
+}
+
+impl InstructionMix {
+    /// Counts the instructions of a block of code
+    ///
+    /// # Arguments
+    /// * 'instructions' - The instructions to count
+    /// * 'x86' - Whether the instructions are x86, which has its own mnemonics and memory operands
+    pub fn new(instructions: &[Instruction], x86: bool) -> Self {
+        let mut mix = InstructionMix::default();
+        for instruction in instructions {
+            let size = instruction.bytes.len() as u32;
+            if instruction.is_data() {
+                mix.literal_pool_bytes += size;
+                continue;
+            }
+            mix.instructions += 1;
+            mix.code_bytes += size;
+            match size {
+                2 => mix.narrow += 1,
+                4 => mix.wide += 1,
+                _ => (),
+            }
+            let (branch, load, store) = if x86 {
+                classify_x86(instruction)
+            } else {
+                classify(instruction)
+            };
+            mix.branches += branch as u32;
+            mix.loads += load as u32;
+            mix.stores += store as u32;
+        }
+        mix.update_average();
+        mix
+    }
+
+    /// Adds the counts of another block of code to this one
+    pub fn add(&mut self, other: &InstructionMix) {
+        self.instructions += other.instructions;
+        self.code_bytes += other.code_bytes;
+        self.narrow += other.narrow;
+        self.wide += other.wide;
+        self.branches += other.branches;
+        self.loads += other.loads;
+        self.stores += other.stores;
+        self.literal_pool_bytes += other.literal_pool_bytes;
+        self.update_average();
+    }
+
+    fn update_average(&mut self) {
+        self.average_length = match self.instructions {
+            0 => 0.0,
+            count => self.code_bytes as f64 / count as f64,
+        };
+    }
+}
+
+impl InstructionMixReport {
+    /// Counts the instructions of every function in a disassembly. Padding after the end of each
+    /// function is left out, since it depends on alignment rather than on the code generated
+    ///
+    /// # Arguments
+    /// * 'disassembly' - The disassembly to analyze
+    pub fn new(disassembly: &Disassembly) -> Self {
+        let format = disassembly.format.as_deref().unwrap_or("");
+        let x86 = format.contains("x86") || format.contains("i386");
+
+        let mut total = InstructionMix::default();
+        let mut functions: Vec<FunctionMix> = disassembly
+            .iter()
+            .map(|function| {
+                let mix = InstructionMix::new(function.body(), x86);
+                total.add(&mix);
+                FunctionMix {
+                    function: function.name.clone(),
+                    mix,
+                }
+            })
+            .collect();
+        functions.sort_by(|x, y| {
+            y.mix
+                .code_bytes
+                .cmp(&x.mix.code_bytes)
+                .then(x.function.cmp(&y.function))
+        });
+        InstructionMixReport { functions, total }
+    }
+}
+
+/// Classifies an ARM, AArch64 or RISC-V instruction as a branch, a load and/or a store
+fn classify(instruction: &Instruction) -> (bool, bool, bool) {
+    let mnemonic = instruction.mnemonic.as_str();
+    let mnemonic = mnemonic.strip_prefix("c.").unwrap_or(mnemonic);
+    let mnemonic = mnemonic
+        .strip_suffix(".n")
+        .or_else(|| mnemonic.strip_suffix(".w"))
+        .unwrap_or(mnemonic);
+
+    let conditional = ["blx", "bl", "bx", "b"]
+        .iter()
+        .any(|x| mnemonic.strip_prefix(x).is_some_and(|x| CONDITIONS.contains(&x)));
+    let branch = BRANCHES.contains(&mnemonic) || mnemonic.starts_with("b.") || conditional;
+
+    let load = RISCV_LOADS.contains(&mnemonic)
+        || mnemonic.starts_with("lr.")
+        || ["ldr", "ldm", "ldp", "ldu", "ldx", "lda", "vldr", "vldm", "vpop", "pop"]
+            .iter()
+            .any(|x| mnemonic.starts_with(x));
+    let store = RISCV_STORES.contains(&mnemonic)
+        || mnemonic.starts_with("sc.")
+        || ["str", "stm", "stp", "stu", "stx", "stl", "vstr", "vstm", "vpush", "push"]
+            .iter()
+            .any(|x| mnemonic.starts_with(x));
+    (branch, load, store)
+}
+
+/// Classifies an x86 instruction as a branch, a load and/or a store. Memory is accessed through
+/// operands like `0x8(%rsp)` or `%fs:0x28`, and the last operand is the destination
+fn classify_x86(instruction: &Instruction) -> (bool, bool, bool) {
+    let mnemonic = instruction.mnemonic.as_str();
+    let branch = mnemonic.starts_with('j')
+        || mnemonic.starts_with("call")
+        || mnemonic.starts_with("ret")
+        || mnemonic.starts_with("loop");
+    match mnemonic {
+        "push" | "pushq" => return (branch, false, true),
+        "pop" | "popq" => return (branch, true, false),
+        _ if branch || mnemonic.starts_with("lea") || mnemonic.starts_with("nop") => {
+            return (branch, false, false)
+        }
+        _ => (),
+    }
+
+    let is_memory = |x: &str| x.contains('(') || x.contains(':');
+    let operands = split_operands(&instruction.operands);
+    // Comparisons only read their last operand
+    if mnemonic.starts_with("cmp") || mnemonic.starts_with("test") {
+        return (false, operands.iter().any(|x| is_memory(x)), false);
+    }
+    match operands.split_last() {
+        Some((destination, sources)) => (false, sources.iter().any(|x| is_memory(x)), is_memory(destination)),
+        None => (false, false, false),
+    }
+}
+
+/// Splits AT&T operands at the commas that aren't inside a memory operand, e.g.
+/// "$0x1,0x8(%rax,%rbx,4)" into "$0x1" and "0x8(%rax,%rbx,4)"
+fn split_operands(operands: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in operands.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(&operands[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if !operands.is_empty() {
+        result.push(&operands[start..]);
+    }
+    result
+}
+
+impl fmt::Display for InstructionMixReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .functions
+            .iter()
+            .map(|x| x.function.len())
+            .max()
+            .unwrap_or(0)
+            .max("Function".len());
+        writeln!(
+            f,
+            "{:w$} {:>8} {:>8} {:>7} {:>7} {:>7} {:>8} {:>7} {:>7} {:>8}",
+            "Function",
+            "Instrs",
+            "Bytes",
+            "Average",
+            "16-bit",
+            "32-bit",
+            "Branches",
+            "Loads",
+            "Stores",
+            "Literals",
+            w = width
+        )?;
+        let rows = self
+            .functions
+            .iter()
+            .map(|x| (x.function.as_str(), &x.mix))
+            .chain(std::iter::once(("Total", &self.total)));
+        for (name, mix) in rows {
+            writeln!(
+                f,
+                "{:w$} {:>8} {:>8} {:>7.2} {:>7} {:>7} {:>8} {:>7} {:>7} {:>8}",
+                name,
+                mix.instructions,
+                mix.code_bytes,
+                mix.average_length,
+                mix.narrow,
+                mix.wide,
+                mix.branches,
+                mix.loads,
+                mix.stores,
+                mix.literal_pool_bytes,
+                w = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for InstructionMixReport {
+    fn to_html(&self) -> String {
+        let rows: Vec<(&str, &InstructionMix)> = self
+            .functions
+            .iter()
+            .map(|x| (x.function.as_str(), &x.mix))
+            .chain(std::iter::once(("Total", &self.total)))
+            .collect();
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Instruction Mix";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Instruction Mix";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Function";
+                                th: "Instructions";
+                                th: "Code Bytes";
+                                th: "Average Length";
+                                th: "16-bit";
+                                th: "32-bit";
+                                th: "Branches";
+                                th: "Loads";
+                                th: "Stores";
+                                th: "Literal Pool Bytes";
+                            }
+                        }
+                        tbody {
+                            @ for (name, mix) in &rows {
+                                tr {
+                                    td: *name;
+                                    td: mix.instructions;
+                                    td: mix.code_bytes;
+                                    td: format!("{:.2}", mix.average_length);
+                                    td: mix.narrow;
+                                    td: mix.wide;
+                                    td: mix.branches;
+                                    td: mix.loads;
+                                    td: mix.stores;
+                                    td: mix.literal_pool_bytes;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "function,instructions,code_bytes,average_length,narrow,wide,branches,loads,stores,literal_pool_bytes\n",
+        );
+        for function in &self.functions {
+            let mix = &function.mix;
+            csv += &format!(
+                "{},{},{},{:.2},{},{},{},{},{},{}\n",
+                csv_field(&function.function),
+                mix.instructions,
+                mix.code_bytes,
+                mix.average_length,
+                mix.narrow,
+                mix.wide,
+                mix.branches,
+                mix.loads,
+                mix.stores,
+                mix.literal_pool_bytes
+            );
+        }
+        csv
+    }
+}
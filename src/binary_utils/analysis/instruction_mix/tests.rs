@@ -0,0 +1,86 @@
+use super::*;
+
+use crate::binary_utils::parser::disassembly::parse_instruction;
+use crate::binary_utils::test_utils::lines;
+
+fn instruction(line: &str) -> Instruction {
+    parse_instruction(line).unwrap().1
+}
+
+#[test]
+fn test_classify_thumb() {
+    assert_eq!(classify(&instruction(" 8000100:	b580      	push	{r7, lr}")), (false, false, true));
+    assert_eq!(classify(&instruction(" 8000102:	4b03      	ldr	r3, [pc, #12]	@ (8000110 <main+0x10>)")), (false, true, false));
+    assert_eq!(classify(&instruction(" 8000104:	f8c3 2004 	str.w	r2, [r3, #4]")), (false, false, true));
+    assert_eq!(classify(&instruction(" 8000108:	d1fc      	bne.n	8000104 <main+0x4>")), (true, false, false));
+    assert_eq!(classify(&instruction(" 800010a:	f000 f801 	bl	8000110 <helper>")), (true, false, false));
+    assert_eq!(classify(&instruction(" 800010e:	bd80      	pop	{r7, pc}")), (false, true, false));
+    assert_eq!(classify(&instruction(" 8000110:	f023 0301 	bic.w	r3, r3, #1")), (false, false, false));
+}
+
+#[test]
+fn test_classify_riscv() {
+    assert_eq!(classify(&instruction("   10078:	00812423          	sw	s0,8(sp)")), (false, false, true));
+    assert_eq!(classify(&instruction("   1007c:	4501                	li	a0,0")), (false, false, false));
+    assert_eq!(classify(&instruction("   1007e:	4398                	lw	a4,0(a5)")), (false, true, false));
+    assert_eq!(classify(&instruction("   10080:	c7b5                	beqz	a5,100ec <main+0x74>")), (true, false, false));
+    assert_eq!(classify(&instruction("   10082:	00f75463          	bge	a4,a5,1008a <main+0x12>")), (true, false, false));
+    assert_eq!(classify(&instruction("   10086:	8082                	ret")), (true, false, false));
+}
+
+#[test]
+fn test_classify_x86() {
+    assert_eq!(classify_x86(&instruction("    1000:	55                   	push   %rbp")), (false, false, true));
+    assert_eq!(classify_x86(&instruction("    1001:	48 8b 47 08          	mov    0x8(%rdi),%rax")), (false, true, false));
+    assert_eq!(
+        classify_x86(&instruction("    1005:	c7 44 98 08 01 00 00 00 	movl   $0x1,0x8(%rax,%rbx,4)")),
+        (false, false, true)
+    );
+    assert_eq!(classify_x86(&instruction("    100d:	83 3f 00             	cmpl   $0x0,(%rdi)")), (false, true, false));
+    assert_eq!(classify_x86(&instruction("    1010:	48 8d 47 08          	lea    0x8(%rdi),%rax")), (false, false, false));
+    assert_eq!(classify_x86(&instruction("    1014:	74 02                	je     1018 <f+0x18>")), (true, false, false));
+    assert_eq!(classify_x86(&instruction("    1016:	c3                   	ret")), (true, false, false));
+    assert_eq!(split_operands("$0x1,0x8(%rax,%rbx,4)"), vec!["$0x1", "0x8(%rax,%rbx,4)"]);
+}
+
+#[test]
+fn test_instruction_mix_report() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "08000100 <main>:",
+        " 8000100:	b580      	push	{r7, lr}",
+        " 8000102:	4b03      	ldr	r3, [pc, #12]	@ (8000110 <main+0x10>)",
+        " 8000104:	f8c3 2004 	str.w	r2, [r3, #4]",
+        " 8000108:	f000 f804 	bl	8000114 <helper>",
+        " 800010c:	bd80      	pop	{r7, pc}",
+        " 800010e:	bf00      	nop",
+        " 8000110:	20000000 	.word	0x20000000",
+        "",
+        "08000114 <helper>:",
+        " 8000114:	4770      	bx	lr",
+        " 8000116:	bf00      	nop",
+    ]));
+    let report = InstructionMixReport::new(&disassembly);
+    assert_eq!(report.functions[0].function, "main");
+    let main = &report.functions[0].mix;
+    assert_eq!(main.instructions, 6);
+    assert_eq!(main.code_bytes, 16);
+    assert_eq!(main.narrow, 4);
+    assert_eq!(main.wide, 2);
+    assert_eq!((main.branches, main.loads, main.stores), (1, 2, 2));
+    assert_eq!(main.literal_pool_bytes, 4);
+
+    // The nop after helper is padding, so it isn't counted
+    assert_eq!(report.functions[1].mix.instructions, 1);
+    assert_eq!(report.total.instructions, 7);
+    assert_eq!(report.total.code_bytes, 18);
+    assert!((report.total.average_length - 18.0 / 7.0).abs() < 1e-9);
+
+    assert!(report.to_string().contains("Total"));
+    assert!(report.to_csv().contains("helper,1,2,2.00,1,0,1,0,0,0\n"));
+    assert!(report.to_html().contains("<td>main</td>"));
+    assert!(report.to_json().contains("\"literal_pool_bytes\": 4"));
+}
@@ -0,0 +1,363 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{
+    covered_size, is_thread_local_section, SymbolTable, SymbolTableEntry, SymbolType, CLONE_SUFFIXES,
+};
+use crate::binary_utils::report::{csv_field, percent, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// What produced a symbol, for the artifacts that compilers emit without a matching line of source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    /// C++ virtual tables, VTTs and construction vtables
+    Vtable,
+    /// C++ RTTI objects and type name strings
+    Typeinfo,
+    /// Guard variables that make sure a function local static is only initialized once
+    Guard,
+    /// String literal pools, e.g. GCC's `.LC0` or Rust's `str.0`
+    String,
+    /// Switch lookup and jump tables
+    JumpTable,
+    /// Rust's unnamed constants, e.g. panic locations and formatting pieces
+    RustAnon,
+    /// Rust closures and C++ lambdas
+    Closure,
+    /// Rust `drop_in_place` functions
+    DropGlue,
+    /// Functions the compiler copied and specialized, e.g. `.constprop.0`, `.isra.0` or `.cold`
+    Clone,
+    Function,
+    Data,
+    Other,
+}
+
+/// Every kind, in the order they are reported
+pub const SYMBOL_KINDS: [SymbolKind; 12] = [
+    SymbolKind::Vtable,
+    SymbolKind::Typeinfo,
+    SymbolKind::Guard,
+    SymbolKind::String,
+    SymbolKind::JumpTable,
+    SymbolKind::RustAnon,
+    SymbolKind::Closure,
+    SymbolKind::DropGlue,
+    SymbolKind::Clone,
+    SymbolKind::Function,
+    SymbolKind::Data,
+    SymbolKind::Other,
+];
+
+/// Size of the symbols of one kind
+#[derive(Debug, PartialEq, Serialize)]
+pub struct KindSize {
+    pub kind: SymbolKind,
+    /// Bytes covered by the symbols, counting aliases and overlaps once
+    pub size: u32,
+    pub symbols: usize,
+    /// Size as a percentage of the section, or of the image for the image totals
+    pub percent: f64,
+}
+
+/// Symbol kinds of a single section
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionKinds {
+    pub section: String,
+    /// Size from the section header if available, otherwise the bytes covered by its symbols
+    pub size: u32,
+    pub kinds: Vec<KindSize>,
+}
+
+/// How much of each section, and of the image, each kind of symbol takes up
+#[derive(Debug, PartialEq, Serialize)]
+pub struct KindReport {
+    pub sections: Vec<SectionKinds>,
+    /// Kinds summed over every section
+    pub kinds: Vec<KindSize>,
+    pub total_size: u32,
+}
+
+/// Classifies a symbol by the naming conventions of GCC, Clang and rustc. Both mangled names and
+/// names demangled by `objdump -C` are recognized
+///
+/// # Arguments
+/// * 'entry' - The symbol to classify
+pub fn classify_symbol(entry: &SymbolTableEntry) -> SymbolKind {
+    let name = entry.plain_name();
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|x| name.starts_with(x));
+    let contains = |parts: &[&str]| parts.iter().any(|x| name.contains(x));
+    if starts(&["_ZTV", "_ZTT", "_ZTC", "vtable for ", "VTT for ", "construction vtable for "]) {
+        SymbolKind::Vtable
+    } else if starts(&["_ZTI", "_ZTS", "typeinfo for ", "typeinfo name for "]) {
+        SymbolKind::Typeinfo
+    } else if starts(&["_ZGV", "guard variable for "]) {
+        SymbolKind::Guard
+    } else if starts(&[".LC", ".L.str", ".str", "str."]) {
+        SymbolKind::String
+    } else if starts(&["CSWTCH.", "switch.table.", ".Lswitch.table", ".LJTI"]) {
+        SymbolKind::JumpTable
+    } else if starts(&["anon.", ".Lanon."]) {
+        SymbolKind::RustAnon
+    } else if contains(&["drop_in_place"]) {
+        SymbolKind::DropGlue
+    } else if contains(&["{{closure}}", "$u7b$$u7b$closure$u7d$$u7d$", "{lambda("]) {
+        SymbolKind::Closure
+    } else if contains(&CLONE_SUFFIXES) {
+        SymbolKind::Clone
+    } else {
+        match entry.flags.symbol_type {
+            SymbolType::Function => SymbolKind::Function,
+            SymbolType::Object => SymbolKind::Data,
+            _ => SymbolKind::Other,
+        }
+    }
+}
+
+impl KindReport {
+    /// Groups the sized symbols of every section, or of a single section, by kind. Section headers
+    /// give the full size of each section, so bytes without a symbol count against the percentages
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to classify
+    /// * 'section_table' - Optional section headers of the same binary
+    /// * 'section_sizes' - Optional size of each section to use when there are no section headers,
+    ///   for when symbols were filtered out of the table. Defaults to the sizes of the table itself
+    /// * 'section' - Optional section to limit the report to
+    pub fn new(
+        symbol_table: &SymbolTable,
+        section_table: Option<&SectionTable>,
+        section_sizes: Option<&BTreeMap<String, u32>>,
+        section: Option<&str>,
+    ) -> Self {
+        let mut grouped: BTreeMap<&str, BTreeMap<SymbolKind, Vec<&SymbolTableEntry>>> = BTreeMap::new();
+        let symbols = symbol_table
+            .iter()
+            .filter(|x| x.is_allocated() && !x.is_section_symbol() && x.alignment_or_size > 0)
+            .filter(|x| section.is_none_or(|name| x.section == name));
+        for entry in symbols {
+            grouped
+                .entry(&entry.section)
+                .or_default()
+                .entry(classify_symbol(entry))
+                .or_default()
+                .push(entry);
+        }
+
+        let section_sizes = section_sizes.cloned().unwrap_or_else(|| symbol_table.section_sizes());
+        let mut totals: BTreeMap<SymbolKind, (u32, usize)> = BTreeMap::new();
+        let mut sections = Vec::new();
+        for (name, kinds) in grouped {
+            let size = match section_table.and_then(|x| x.find(name)) {
+                Some(header) => header.size,
+                None => section_sizes.get(name).copied().unwrap_or(0),
+            };
+            let mut kinds: Vec<KindSize> = kinds
+                .into_iter()
+                .map(|(kind, entries)| KindSize {
+                    kind,
+                    size: covered_size(entries.iter().copied()),
+                    symbols: entries.len(),
+                    percent: 0.0,
+                })
+                .collect();
+            for kind in &mut kinds {
+                kind.percent = percent(kind.size, size);
+                // Thread local sections are listed but not totalled, like `SymbolTable::total_size`
+                if is_thread_local_section(name) {
+                    continue;
+                }
+                let total = totals.entry(kind.kind).or_default();
+                total.0 += kind.size;
+                total.1 += kind.symbols;
+            }
+            kinds.sort_by(|x, y| y.size.cmp(&x.size).then(x.kind.cmp(&y.kind)));
+            sections.push(SectionKinds {
+                section: name.to_string(),
+                size,
+                kinds,
+            });
+        }
+
+        let total_size = sections
+            .iter()
+            .filter(|x| !is_thread_local_section(&x.section))
+            .map(|x| x.size)
+            .sum();
+        let mut kinds: Vec<KindSize> = totals
+            .into_iter()
+            .map(|(kind, (size, symbols))| KindSize {
+                kind,
+                size,
+                symbols,
+                percent: percent(size, total_size),
+            })
+            .collect();
+        kinds.sort_by(|x, y| y.size.cmp(&x.size).then(x.kind.cmp(&y.kind)));
+        KindReport {
+            sections,
+            kinds,
+            total_size,
+        }
+    }
+
+    /// Finds the size of a kind in a section
+    pub fn find(&self, section: &str, kind: SymbolKind) -> Option<&KindSize> {
+        self.sections
+            .iter()
+            .find(|x| x.section == section)
+            .and_then(|x| x.kinds.iter().find(|x| x.kind == kind))
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolKind::Vtable => write!(f, "vtable"),
+            SymbolKind::Typeinfo => write!(f, "typeinfo"),
+            SymbolKind::Guard => write!(f, "guard"),
+            SymbolKind::String => write!(f, "string"),
+            SymbolKind::JumpTable => write!(f, "jump_table"),
+            SymbolKind::RustAnon => write!(f, "rust_anon"),
+            SymbolKind::Closure => write!(f, "closure"),
+            SymbolKind::DropGlue => write!(f, "drop_glue"),
+            SymbolKind::Clone => write!(f, "clone"),
+            SymbolKind::Function => write!(f, "function"),
+            SymbolKind::Data => write!(f, "data"),
+            SymbolKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl FromStr for SymbolKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SYMBOL_KINDS
+            .iter()
+            .find(|x| x.to_string() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown symbol kind: {}", s))
+    }
+}
+
+impl fmt::Display for KindReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:16} {:12} {:>8} {:>8} {:>9}", "Section", "Kind", "Size", "Symbols", "% Section")?;
+        for section in &self.sections {
+            for kind in &section.kinds {
+                writeln!(
+                    f,
+                    "{:16} {:12} {:>8} {:>8} {:>8.1}%",
+                    section.section,
+                    kind.kind.to_string(),
+                    kind.size,
+                    kind.symbols,
+                    kind.percent
+                )?;
+            }
+        }
+        writeln!(f, "{:12} {:>8} {:>8} {:>7}", "Kind", "Size", "Symbols", "% Image")?;
+        for kind in &self.kinds {
+            writeln!(
+                f,
+                "{:12} {:>8} {:>8} {:>6.1}%",
+                kind.kind.to_string(),
+                kind.size,
+                kind.symbols,
+                kind.percent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for KindReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Symbol Kinds";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Symbol Kinds";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                th: "Kind";
+                                th: "Size";
+                                th: "Symbols";
+                                th: "% Section";
+                            }
+                        }
+                        tbody {
+                            @ for section in &self.sections {
+                                @ for kind in &section.kinds {
+                                    tr {
+                                        td: &section.section;
+                                        td: kind.kind.to_string();
+                                        td: kind.size;
+                                        td: kind.symbols;
+                                        td: format!("{:.1}", kind.percent);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Image";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Kind";
+                                th: "Size";
+                                th: "Symbols";
+                                th: "% Image";
+                            }
+                        }
+                        tbody {
+                            @ for kind in &self.kinds {
+                                tr {
+                                    td: kind.kind.to_string();
+                                    td: kind.size;
+                                    td: kind.symbols;
+                                    td: format!("{:.1}", kind.percent);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("section,kind,size,symbols,percent\n");
+        for section in &self.sections {
+            for kind in &section.kinds {
+                csv += &format!(
+                    "{},{},{},{},{:.2}\n",
+                    csv_field(&section.section),
+                    kind.kind,
+                    kind.size,
+                    kind.symbols,
+                    kind.percent
+                );
+            }
+        }
+        for kind in &self.kinds {
+            csv += &format!("total,{},{},{},{:.2}\n", kind.kind, kind.size, kind.symbols, kind.percent);
+        }
+        csv
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+use crate::binary_utils::parser::symbol_table::parse_symbol_table_entry;
+use crate::binary_utils::test_utils::lines;
+
+fn kind(line: &str) -> SymbolKind {
+    classify_symbol(&parse_symbol_table_entry(line).unwrap().1)
+}
+
+#[test]
+fn test_classify_cxx_symbols() {
+    assert_eq!(kind("00003d68  w    O .data.rel.ro	00000028 _ZTV6Circle"), SymbolKind::Vtable);
+    assert_eq!(kind("00003d68  w    O .data.rel.ro	00000028 vtable for Circle"), SymbolKind::Vtable);
+    assert_eq!(kind("00003db8  w    O .data.rel.ro	00000018 _ZTI6Circle"), SymbolKind::Typeinfo);
+    assert_eq!(kind("00002038  w    O .rodata	00000008 _ZTS6Circle"), SymbolKind::Typeinfo);
+    assert_eq!(kind("00004050 l     O .bss	00000008 _ZGVZ4makeiE4unit"), SymbolKind::Guard);
+    assert_eq!(kind("00002010 l     O .rodata	00000018 CSWTCH.16"), SymbolKind::JumpTable);
+    assert_eq!(kind("000011d0 l     F .text	00000012 _ZL5scaleii.constprop.0"), SymbolKind::Clone);
+    assert_eq!(kind("000011e0 l     F .text	00000040 main.cold"), SymbolKind::Clone);
+    assert_eq!(kind("00001280 g     F .text	00000014 _Z6lookupi"), SymbolKind::Function);
+    assert_eq!(kind("00002000 g     O .rodata	00000004 _IO_stdin_used"), SymbolKind::Data);
+}
+
+#[test]
+fn test_classify_rust_symbols() {
+    assert_eq!(kind("00000000 l     O .rodata	00000010 .L__unnamed_1"), SymbolKind::Data);
+    assert_eq!(kind("00000000 l     O .rodata	00000010 anon.5f3c0d1e.0.llvm.123"), SymbolKind::RustAnon);
+    assert_eq!(kind("00000000 l     O .rodata	00000005 str.0"), SymbolKind::String);
+    assert_eq!(
+        kind("00000000 l     F .text	00000020 _ZN4core3ptr42drop_in_place$LT$alloc..string..String$GT$17h0123456789abcdefE"),
+        SymbolKind::DropGlue
+    );
+    assert_eq!(
+        kind("00000000 l     F .text	00000020 _ZN3app4main28_$u7b$$u7b$closure$u7d$$u7d$17h0123456789abcdefE"),
+        SymbolKind::Closure
+    );
+    assert_eq!(kind("00000000 l     F .text	00000020 app::main::{{closure}}"), SymbolKind::Closure);
+}
+
+#[test]
+fn test_kind_names() {
+    for kind in SYMBOL_KINDS {
+        assert_eq!(kind.to_string().parse::<SymbolKind>(), Ok(kind));
+    }
+    assert_eq!("jump_table".parse::<SymbolKind>(), Ok(SymbolKind::JumpTable));
+    assert!("rtti".parse::<SymbolKind>().is_err());
+}
+
+#[test]
+fn test_kind_report() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00002000 g     O .rodata	00000020 _IO_stdin_used",
+        "00002020 l     O .rodata	00000010 CSWTCH.16",
+        "00002030  w    O .rodata	00000008 _ZTS6Circle",
+        "00002038  w    O .rodata	00000008 _ZTS6Square",
+        "00003d40  w    O .data.rel.ro	00000028 _ZTV6Square",
+        "00003d68  w    O .data.rel.ro	00000018 _ZTI6Square",
+        "00001000 g     F .text	00000040 main",
+        "00001000 g     F .text	00000040 main_alias",
+    ]));
+    let report = KindReport::new(&symbol_table, None, None, None);
+
+    let rodata: Vec<(SymbolKind, u32, usize)> = report.sections[1]
+        .kinds
+        .iter()
+        .map(|x| (x.kind, x.size, x.symbols))
+        .collect();
+    assert_eq!(
+        rodata,
+        vec![(SymbolKind::Data, 0x20, 1), (SymbolKind::Typeinfo, 0x10, 2), (SymbolKind::JumpTable, 0x10, 1)]
+    );
+    assert_eq!(report.find(".rodata", SymbolKind::Typeinfo).unwrap().percent, 25.0);
+    // Aliases share their bytes
+    assert_eq!(report.find(".text", SymbolKind::Function).unwrap().size, 0x40);
+    assert_eq!(report.total_size, 0x40 + 0x40 + 0x40);
+
+    let totals: Vec<(SymbolKind, u32)> = report.kinds.iter().map(|x| (x.kind, x.size)).collect();
+    assert_eq!(
+        totals,
+        vec![
+            (SymbolKind::Function, 0x40),
+            (SymbolKind::Vtable, 0x28),
+            (SymbolKind::Typeinfo, 0x28),
+            (SymbolKind::Data, 0x20),
+            (SymbolKind::JumpTable, 0x10),
+        ]
+    );
+}
+
+#[test]
+fn test_kind_report_for_one_section() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00002000 g     O .rodata	00000020 _IO_stdin_used",
+        "00001000 g     F .text	00000040 main",
+    ]));
+    let report = KindReport::new(&symbol_table, None, None, Some(".text"));
+    assert_eq!(report.sections.len(), 1);
+    assert_eq!(report.total_size, 0x40);
+    assert_eq!(report.to_csv(), "section,kind,size,symbols,percent\n.text,function,64,1,100.00\ntotal,function,64,1,100.00\n");
+}
+
+#[test]
+fn test_kind_report_of_filtered_table() {
+    let unfiltered = SymbolTable::from_lines(&lines(&[
+        "00002000 g     O .rodata	00000020 _IO_stdin_used",
+        "00001000 g     F .text	00000040 main",
+        "00001040 g     O .text	000000c0 table",
+    ]));
+    let section_sizes = unfiltered.section_sizes();
+    let symbol_table: SymbolTable = unfiltered.into_iter().filter(|x| x.name == "main").collect();
+    let report = KindReport::new(&symbol_table, None, Some(&section_sizes), None);
+    assert_eq!(report.sections[0].size, 0x100);
+    assert_eq!(report.total_size, 0x100);
+    assert_eq!(report.find(".text", SymbolKind::Function).unwrap().percent, 25.0);
+}
@@ -0,0 +1,338 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+use horrorshow::prelude::*;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::parser::symbol_table::{image_size, SymbolClone, SymbolTable};
+use crate::binary_utils::report::{csv_field, percent, size_string, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Size of the cumulative curve drawn in the HTML output
+const CURVE_WIDTH: f64 = 600.0;
+const CURVE_HEIGHT: f64 = 300.0;
+
+/// A symbol along with the running total of every symbol at least as large
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ParetoEntry {
+    pub rank: usize,
+    pub name: String,
+    pub section: String,
+    pub size: u32,
+    pub size_inferred: bool,
+    pub cumulative_size: u32,
+    /// Whether any inferred size was counted in the cumulative size, see `SymbolTable::infer_sizes`
+    pub cumulative_inferred: bool,
+    pub percent_of_section: f64,
+    pub percent_of_image: f64,
+    /// Cumulative size as a percentage of the section, or of the image if no section was chosen
+    pub cumulative_percent: f64,
+    /// Clones included in the size, see `SymbolTable::group_clones`
+    pub clones: Vec<SymbolClone>,
+}
+
+/// Symbols ranked by size with their cumulative share of a section or of the whole image
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ParetoReport {
+    /// The section the symbols were taken from, or none for the whole image
+    pub section: Option<String>,
+    pub entries: Vec<ParetoEntry>,
+    /// Number of sized symbols ranked, including any left out by the top N limit
+    pub symbol_count: usize,
+    /// Size of the section, or of the image
+    pub total_size: u32,
+    pub threshold: f64,
+    /// Number of the largest symbols that make up the threshold percentage of the total
+    pub threshold_count: usize,
+}
+
+impl ParetoReport {
+    /// Ranks the symbols of a section, or of the whole image, from largest to smallest. Aliases
+    /// of a symbol that is already ranked are skipped so the cumulative size isn't inflated
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to rank
+    /// * 'section_sizes' - Optional size of each section to take the percentages of, for when
+    ///   symbols were filtered out of the table. Defaults to the sizes of the table itself
+    /// * 'section' - Optional section to rank the symbols of
+    /// * 'top' - Optional number of symbols to list
+    /// * 'threshold' - Percentage of the total to find the number of symbols for, e.g. 80
+    pub fn new(
+        mut symbol_table: SymbolTable,
+        section_sizes: Option<&BTreeMap<String, u32>>,
+        section: Option<&str>,
+        top: Option<usize>,
+        threshold: f64,
+    ) -> Self {
+        let section_sizes = section_sizes.cloned().unwrap_or_else(|| symbol_table.section_sizes());
+        let image_size = image_size(&section_sizes);
+        let total_size = match section {
+            Some(name) => section_sizes.get(name).copied().unwrap_or(0),
+            None => image_size,
+        };
+
+        symbol_table.sort_by_size_descending();
+        let mut placed = BTreeSet::new();
+        let mut covered = BTreeMap::new();
+        let mut cumulative_size = 0;
+        let mut cumulative_inferred = false;
+        let mut threshold_count = None;
+        let mut entries = Vec::new();
+        let symbols = symbol_table
+            .iter()
+            .filter(|x| x.is_allocated() && !x.is_section_symbol() && x.alignment_or_size > 0)
+            .filter(|x| match section {
+                Some(name) => x.section == name,
+                // Thread local symbols aren't part of the image size, see `SymbolTable::total_size`
+                None => !x.is_thread_local(),
+            });
+        for entry in symbols {
+            if !placed.insert((entry.section.as_str(), entry.address)) {
+                continue;
+            }
+            // Symbols nested in or overlapping ones already ranked only add the bytes not yet counted
+            let clones = entry.clones.iter().map(|x| (x.section.as_str(), x.address, x.size));
+            let mut added = 0;
+            for (section, address, size) in std::iter::once((entry.section.as_str(), entry.address, entry.own_size())).chain(clones) {
+                added += cover(&mut covered, section, address as u64, address as u64 + size as u64);
+            }
+            cumulative_size += added;
+            cumulative_inferred |= entry.size_inferred && added > 0;
+            let cumulative_percent = percent(cumulative_size, total_size);
+            if threshold_count.is_none() && cumulative_percent >= threshold {
+                threshold_count = Some(entries.len() + 1);
+            }
+            entries.push(ParetoEntry {
+                rank: entries.len() + 1,
+                name: entry.name.clone(),
+                section: entry.section.clone(),
+                size: entry.alignment_or_size,
+                size_inferred: entry.size_inferred,
+                cumulative_size,
+                cumulative_inferred,
+                percent_of_section: percent(
+                    entry.alignment_or_size,
+                    section_sizes.get(&entry.section).copied().unwrap_or(0),
+                ),
+                percent_of_image: percent(entry.alignment_or_size, image_size),
+                cumulative_percent,
+                clones: entry.clones.clone(),
+            });
+        }
+
+        let symbol_count = entries.len();
+        if let Some(top) = top {
+            entries.truncate(top);
+        }
+        ParetoReport {
+            section: section.map(|x| x.to_string()),
+            entries,
+            symbol_count,
+            total_size,
+            threshold,
+            threshold_count: threshold_count.unwrap_or(symbol_count),
+        }
+    }
+
+    /// Describes how many symbols make up the threshold percentage of the total
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} symbols make up {}% of {} ({} bytes)",
+            self.threshold_count,
+            self.symbol_count,
+            self.threshold,
+            self.section.as_deref().unwrap_or("the image"),
+            self.total_size
+        )
+    }
+
+    /// Draws the cumulative percentage of the listed symbols against their rank as an SVG image
+    fn curve(&self) -> String {
+        let count = self.entries.len().max(1) as f64;
+        let point = |rank: usize, percent: f64| {
+            format!(
+                "{:.1},{:.1}",
+                rank as f64 * CURVE_WIDTH / count,
+                CURVE_HEIGHT - percent.min(100.0) * CURVE_HEIGHT / 100.0
+            )
+        };
+        let points: Vec<String> = std::iter::once(point(0, 0.0))
+            .chain(self.entries.iter().map(|x| point(x.rank, x.cumulative_percent)))
+            .collect();
+        let threshold = CURVE_HEIGHT - self.threshold.min(100.0) * CURVE_HEIGHT / 100.0;
+        format!(
+            "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" xmlns=\"http://www.w3.org/2000/svg\">\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"none\" stroke=\"#dddddd\"/>\
+             <line x1=\"0\" y1=\"{t:.1}\" x2=\"{w}\" y2=\"{t:.1}\" stroke=\"#cc0000\" stroke-dasharray=\"4\"/>\
+             <polyline points=\"{p}\" fill=\"none\" stroke=\"#0055aa\" stroke-width=\"2\"/>\
+             </svg>",
+            w = CURVE_WIDTH,
+            h = CURVE_HEIGHT,
+            t = threshold,
+            p = points.join(" ")
+        )
+    }
+}
+
+impl ParetoEntry {
+    /// Formats the symbol name along with the number of clones grouped into it
+    fn display_name(&self) -> String {
+        match self.clones.len() {
+            0 => self.name.clone(),
+            1 => format!("{} (+1 clone)", self.name),
+            count => format!("{} (+{} clones)", self.name, count),
+        }
+    }
+}
+
+/// Adds a range to a set of disjoint ranges, merging it with the ones it overlaps, and gets the
+/// number of bytes it covered that weren't covered before
+///
+/// # Arguments
+/// * 'covered' - End of each covered range, by section and start address
+/// * 'section' - Section of the range
+/// * 'start' - Start of the range
+/// * 'end' - End of the range, exclusive
+fn cover<'a>(covered: &mut BTreeMap<(&'a str, u64), u64>, section: &'a str, start: u64, end: u64) -> u32 {
+    if end <= start {
+        return 0;
+    }
+    let overlapping: Vec<(u64, u64)> = covered
+        .range((section, 0)..(section, end))
+        .rev()
+        .take_while(|(_, &x)| x > start)
+        .map(|(&(_, x), &y)| (x, y))
+        .collect();
+    let mut merged = (start, end);
+    let mut already_covered = 0;
+    for (x, y) in overlapping {
+        covered.remove(&(section, x));
+        already_covered += y.min(end) - x.max(start);
+        merged = (merged.0.min(x), merged.1.max(y));
+    }
+    covered.insert((section, merged.0), merged.1);
+    (end - start - already_covered) as u32
+}
+
+impl fmt::Display for ParetoReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .entries
+            .iter()
+            .map(|x| x.display_name().len())
+            .max()
+            .unwrap_or(0)
+            .max("Symbol".len());
+        writeln!(
+            f,
+            "{:>5} {:w$} {:10} {:>8} {:>10} {:>9} {:>7} {:>7}",
+            "Rank",
+            "Symbol",
+            "Section",
+            "Size",
+            "Cumulative",
+            "% Section",
+            "% Image",
+            "% Total",
+            w = width
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:>5} {:w$} {:10} {:>8} {:>10} {:>8.1}% {:>6.1}% {:>6.1}%",
+                entry.rank,
+                entry.display_name(),
+                entry.section,
+                size_string(entry.size, entry.size_inferred),
+                size_string(entry.cumulative_size, entry.cumulative_inferred),
+                entry.percent_of_section,
+                entry.percent_of_image,
+                entry.cumulative_percent,
+                w = width
+            )?;
+        }
+        writeln!(f, "{}", self.summary())
+    }
+}
+
+impl Report for ParetoReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Pareto Analysis";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Pareto Analysis";
+                    h2 : self.summary();
+                    : Raw(self.curve());
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Rank";
+                                th: "Symbol";
+                                th: "Section";
+                                th: "Size";
+                                th: "Cumulative Size";
+                                th: "% Section";
+                                th: "% Image";
+                                th: "% Total";
+                            }
+                        }
+                        tbody {
+                            @ for entry in &self.entries {
+                                tr {
+                                    td: entry.rank;
+                                    td {
+                                        : &entry.name;
+                                        @ for clone in &entry.clones {
+                                            br;
+                                            : format!("+ {} ({})", clone.name, clone.size);
+                                        }
+                                    }
+                                    td: &entry.section;
+                                    td: size_string(entry.size, entry.size_inferred);
+                                    td: size_string(entry.cumulative_size, entry.cumulative_inferred);
+                                    td: format!("{:.1}", entry.percent_of_section);
+                                    td: format!("{:.1}", entry.percent_of_image);
+                                    td: format!("{:.1}", entry.cumulative_percent);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "rank,symbol,section,size,cumulative_size,percent_of_section,percent_of_image,cumulative_percent,size_inferred,cumulative_inferred\n",
+        );
+        for entry in &self.entries {
+            csv += &format!(
+                "{},{},{},{},{},{:.2},{:.2},{:.2},{},{}\n",
+                entry.rank,
+                csv_field(&entry.name),
+                csv_field(&entry.section),
+                entry.size,
+                entry.cumulative_size,
+                entry.percent_of_section,
+                entry.percent_of_image,
+                entry.cumulative_percent,
+                entry.size_inferred,
+                entry.cumulative_inferred
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn sample_table() -> SymbolTable {
+    SymbolTable::from_lines(
+        &[
+            "08000000 l    d  .text	00000000 .text",
+            "08000000 g     F .text	00000050 big",
+            "08000000 g     F .text	00000050 big_alias",
+            "08000050 g     F .text	00000020 medium",
+            "08000070 g     F .text	00000008 small",
+            "08000078 g     F .text	00000004 tiny",
+            "08000080 g     F .text	00000004 smallest",
+            "20000000 g     O .bss	00000080 buffer",
+        ]
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>(),
+    )
+}
+
+#[test]
+fn test_pareto_of_section() {
+    let report = ParetoReport::new(sample_table(), None, Some(".text"), None, 80.0);
+    let names: Vec<&str> = report.entries.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names[1..], ["medium", "small", "tiny", "smallest"]);
+    assert_eq!(report.symbol_count, 5);
+    assert_eq!(report.total_size, 0x80);
+    // 0x50 + 0x20 = 0x70 of 0x80 is 87.5%
+    assert_eq!(report.threshold_count, 2);
+    assert_eq!(report.entries[1].cumulative_size, 0x70);
+    assert_eq!(report.entries[4].cumulative_size, 0x80);
+    assert!((report.entries[4].cumulative_percent - 100.0).abs() < 1e-9);
+    assert!((report.entries[0].percent_of_image - 31.25).abs() < 1e-9);
+}
+
+#[test]
+fn test_pareto_top_n_of_image() {
+    let report = ParetoReport::new(sample_table(), None, None, Some(2), 50.0);
+    assert_eq!(report.entries.len(), 2);
+    assert_eq!(report.entries[0].name, "buffer");
+    assert_eq!(report.symbol_count, 6);
+    assert_eq!(report.threshold_count, 1);
+    assert_eq!(report.summary(), "1 of 6 symbols make up 50% of the image (256 bytes)");
+}
+
+#[test]
+fn test_pareto_report_formats() {
+    let report = ParetoReport::new(sample_table(), None, Some(".text"), None, 80.0);
+    assert!(report.to_string().contains("2 of 5 symbols make up 80% of .text"));
+    assert!(report.to_csv().contains("2,medium,.text,32,112,25.00,12.50,87.50,false,false\n"));
+    let html = report.to_html();
+    assert!(html.contains("<svg"));
+    assert!(html.contains("<polyline points=\"0.0,300.0 120.0,112.5 240.0,37.5 "));
+    assert!(report.to_json().contains("\"threshold_count\": 2"));
+}
+
+#[test]
+fn test_pareto_with_grouped_clones() {
+    let mut symbol_table = SymbolTable::from_lines(
+        &[
+            "08000000 g     F .text	00000030 parse",
+            "08000030 l     F .text	00000028 parse.part.0",
+            "08000058 l     F .text	00000010 parse.cold",
+            "08000068 g     F .text	00000050 render",
+        ]
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>(),
+    );
+    symbol_table.group_clones();
+    let report = ParetoReport::new(symbol_table, None, Some(".text"), None, 50.0);
+
+    let sizes: Vec<(&str, u32)> = report.entries.iter().map(|x| (x.name.as_str(), x.size)).collect();
+    assert_eq!(sizes, vec![("parse", 0x68), ("render", 0x50)]);
+    assert_eq!(report.total_size, 0xb8);
+    assert_eq!(report.entries[0].clones.len(), 2);
+    assert!(report.to_string().contains("parse (+2 clones)"));
+    assert!(report.to_html().contains("+ parse.cold (16)"));
+}
+
+#[test]
+fn test_pareto_with_nested_symbols() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "08000000 g     F .text	00000100 outer",
+        "08000080 l     F .text	00000040 inner",
+        "080000f0 g     F .text	00000020 straddling",
+    ]));
+    let report = ParetoReport::new(symbol_table, None, Some(".text"), None, 80.0);
+    assert_eq!(report.total_size, 0x110);
+    let cumulative: Vec<u32> = report.entries.iter().map(|x| x.cumulative_size).collect();
+    assert_eq!(cumulative, vec![0x100, 0x100, 0x110]);
+    assert_eq!(report.entries.last().unwrap().cumulative_percent, 100.0);
+}
+
+#[test]
+fn test_pareto_percentages_of_unfiltered_sizes() {
+    let section_sizes = sample_table().section_sizes();
+    let symbol_table: SymbolTable = sample_table().into_iter().filter(|x| x.name.starts_with("small")).collect();
+    let report = ParetoReport::new(symbol_table, Some(&section_sizes), Some(".text"), None, 80.0);
+    assert_eq!(report.total_size, 0x80);
+    assert_eq!(report.threshold_count, 2);
+    // 0x8 + 0x4 of 0x80
+    assert!((report.entries[1].cumulative_percent - 9.375).abs() < 1e-9);
+    assert!((report.entries[0].percent_of_image - 3.125).abs() < 1e-9);
+}
+
+#[test]
+fn test_pareto_marks_inferred_sizes() {
+    let mut symbol_table = SymbolTable::from_lines(&lines(&[
+        "00001000 g     F .text	00000010 foo",
+        "00001010 g     F .text	00000000 bar",
+        "00001018 g     F .text	00000004 baz",
+    ]));
+    symbol_table.infer_sizes(None);
+    let report = ParetoReport::new(symbol_table, None, Some(".text"), None, 80.0);
+    let flags: Vec<(bool, bool)> = report.entries.iter().map(|x| (x.size_inferred, x.cumulative_inferred)).collect();
+    assert_eq!(flags, vec![(false, false), (true, true), (false, true)]);
+    assert!(report.to_string().contains("8 (inferred)"));
+    assert!(report.to_html().contains("<td>8 (inferred)</td>"));
+    assert!(report.to_csv().contains("2,bar,.text,8,24,28.57,28.57,85.71,true,true\n"));
+}
@@ -0,0 +1,320 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::parser::relocations::{RelocationKind, Relocations};
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{SymbolScope, SymbolTable, SymbolTableEntry};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Sections that hold the PLT stubs, the GOT and the dynamic relocations that fill them in
+pub const PLT_GOT_SECTIONS: [&str; 9] = [
+    ".plt", ".plt.got", ".plt.sec", ".got", ".got.plt", ".rela.dyn", ".rela.plt", ".rel.dyn", ".rel.plt",
+];
+
+/// Size of the pages that the dynamic linker writes to, and so makes private to the process
+const PAGE_SIZE: u32 = 4096;
+
+/// The relocations that refer to one symbol
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SymbolRelocations {
+    pub symbol: String,
+    /// Whether the binary defines the symbol itself
+    pub defined: bool,
+    /// Relocations for calls through the PLT
+    pub plt: u32,
+    /// Relocations for addresses loaded from the GOT
+    pub got: u32,
+    pub other: u32,
+    pub total: u32,
+    /// Whether the symbol is defined and exported, but only reached through the PLT or GOT because
+    /// it could be interposed. Making it hidden or local lets the linker bind it directly
+    pub candidate: bool,
+}
+
+/// Size of a PLT, GOT or relocation section
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RelocationSection {
+    pub section: String,
+    pub size: u32,
+}
+
+/// The dynamic relocations of a shared library or PIE executable and what they cost at startup.
+/// Object files have no dynamic relocations, so for them the PLT and GOT relocations that the
+/// compiler emits are counted instead
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RelocationReport {
+    /// Whether the report counts dynamic relocations rather than object file relocations
+    pub dynamic: bool,
+    pub symbols: Vec<SymbolRelocations>,
+    pub sections: Vec<RelocationSection>,
+    /// Number of relocations counted
+    pub total: u32,
+    /// Whether the binary is assumed to be loaded with immediate binding (`-z now` or
+    /// `LD_BIND_NOW`), which resolves the PLT at startup instead of on the first call
+    pub bind_now: bool,
+    /// Relocations that only add the load address
+    pub relative: u32,
+    /// Relocations that call a resolver function to pick an implementation
+    pub irelative: u32,
+    /// Relocations that look up a symbol when the binary is loaded
+    pub symbol_lookups: u32,
+    /// PLT relocations that look up a symbol on the first call instead
+    pub lazy_lookups: u32,
+    /// Pages written by the dynamic linker, which can't be shared with other processes
+    pub dirty_pages: u32,
+}
+
+impl RelocationReport {
+    /// Counts the relocations of a binary per symbol and estimates their startup cost
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table of the binary, used to find the symbols it defines
+    /// * 'section_table' - The section headers of the binary
+    /// * 'relocations' - The relocations of the binary
+    /// * 'bind_now' - Whether the binary is loaded with immediate binding
+    pub fn new(
+        symbol_table: &SymbolTable,
+        section_table: &SectionTable,
+        relocations: &Relocations,
+        bind_now: bool,
+    ) -> Self {
+        let dynamic = relocations.dynamic().next().is_some();
+        let counted: Vec<_> = relocations.iter().filter(|x| x.is_dynamic() == dynamic).collect();
+
+        let defined = symbol_table.defined_by_name();
+        let mut symbols: BTreeMap<&str, SymbolRelocations> = BTreeMap::new();
+        for relocation in &counted {
+            let name = match &relocation.symbol {
+                Some(name) => name,
+                None => continue,
+            };
+            let symbol = symbols.entry(name).or_insert_with(|| new_symbol(name, defined.get(name.as_str()).copied()));
+            match relocation.kind() {
+                RelocationKind::JumpSlot => symbol.plt += 1,
+                RelocationKind::GlobalData => symbol.got += 1,
+                _ => symbol.other += 1,
+            }
+            symbol.total += 1;
+        }
+        let mut symbols: Vec<SymbolRelocations> = symbols.into_values().collect();
+        for symbol in &mut symbols {
+            symbol.candidate = symbol.candidate && symbol.plt + symbol.got > 0;
+        }
+        symbols.sort_by(|x, y| y.total.cmp(&x.total).then(x.symbol.cmp(&y.symbol)));
+
+        let sections = PLT_GOT_SECTIONS
+            .iter()
+            .filter_map(|x| section_table.find(x))
+            .map(|x| RelocationSection {
+                section: x.name.clone(),
+                size: x.size,
+            })
+            .collect();
+
+        let (mut relative, mut irelative, mut symbol_lookups, mut lazy_lookups) = (0, 0, 0, 0);
+        let mut pages = BTreeSet::new();
+        for relocation in counted.iter().filter(|x| x.is_dynamic()) {
+            match relocation.kind() {
+                RelocationKind::Relative => relative += 1,
+                RelocationKind::IRelative => irelative += 1,
+                RelocationKind::JumpSlot if !bind_now => lazy_lookups += 1,
+                _ => symbol_lookups += 1,
+            }
+            pages.insert(relocation.offset / PAGE_SIZE);
+        }
+
+        RelocationReport {
+            dynamic,
+            symbols,
+            sections,
+            total: counted.len() as u32,
+            bind_now,
+            relative,
+            irelative,
+            symbol_lookups,
+            lazy_lookups,
+            dirty_pages: pages.len() as u32,
+        }
+    }
+
+    /// Gets the symbols that could be made hidden or local
+    pub fn candidates(&self) -> impl Iterator<Item = &SymbolRelocations> {
+        self.symbols.iter().filter(|x| x.candidate)
+    }
+
+    /// Describes the candidates to be hidden. For object files they are only a hint, since the
+    /// compiler goes through the PLT or GOT for any symbol it can't prove is local
+    fn candidate_heading(&self) -> &str {
+        if self.dynamic {
+            "Could be hidden"
+        } else {
+            "Could be hidden, going by compiler relocations"
+        }
+    }
+
+    /// Finds the relocation counts of a symbol
+    pub fn find(&self, symbol: &str) -> Option<&SymbolRelocations> {
+        self.symbols.iter().find(|x| x.symbol == symbol)
+    }
+}
+
+/// Creates an empty row for a symbol. A symbol is a candidate to be hidden if the binary defines
+/// and exports it, which is narrowed down to the PLT and GOT once its relocations are counted
+fn new_symbol(name: &str, definition: Option<&SymbolTableEntry>) -> SymbolRelocations {
+    SymbolRelocations {
+        symbol: name.to_string(),
+        defined: definition.is_some(),
+        plt: 0,
+        got: 0,
+        other: 0,
+        total: 0,
+        candidate: definition.is_some_and(|x| x.flags.scope == SymbolScope::Global && !x.is_hidden()),
+    }
+}
+
+impl SymbolRelocations {
+    /// Formats the symbol name, marking candidates to be hidden
+    fn marked_name(&self) -> String {
+        if self.candidate {
+            format!("{} *", self.symbol)
+        } else {
+            self.symbol.clone()
+        }
+    }
+}
+
+impl fmt::Display for RelocationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.symbols.iter().map(|x| x.marked_name().len()).max().unwrap_or(0).max(6);
+        writeln!(f, "{:width$} {:>5} {:>5} {:>5} {:>5}", "Symbol", "PLT", "GOT", "Other", "Total", width = width)?;
+        for symbol in &self.symbols {
+            writeln!(
+                f,
+                "{:width$} {:>5} {:>5} {:>5} {:>5}",
+                symbol.marked_name(),
+                symbol.plt,
+                symbol.got,
+                symbol.other,
+                symbol.total,
+                width = width
+            )?;
+        }
+        for section in &self.sections {
+            writeln!(f, "{}: {} bytes", section.section, section.size)?;
+        }
+        if self.dynamic {
+            writeln!(f, "Dynamic relocations: {}", self.total)?;
+            writeln!(f, "Relative fixups: {}", self.relative)?;
+            writeln!(f, "IFUNC resolver calls: {}", self.irelative)?;
+            writeln!(f, "Symbol lookups at startup: {}", self.symbol_lookups)?;
+            writeln!(f, "Symbol lookups on first call: {}", self.lazy_lookups)?;
+            writeln!(f, "Pages written: {}", self.dirty_pages)?;
+        } else {
+            writeln!(f, "Object file relocations: {}", self.total)?;
+        }
+        let candidates: Vec<&str> = self.candidates().map(|x| x.symbol.as_str()).collect();
+        writeln!(f, "{} (*): {}", self.candidate_heading(), candidates.join(", "))
+    }
+}
+
+impl Report for RelocationReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Relocation Overhead";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Relocation Overhead";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Symbol";
+                                th: "PLT";
+                                th: "GOT";
+                                th: "Other";
+                                th: "Total";
+                                th: self.candidate_heading();
+                            }
+                        }
+                        tbody {
+                            @ for symbol in &self.symbols {
+                                tr {
+                                    td: &symbol.symbol;
+                                    td: symbol.plt;
+                                    td: symbol.got;
+                                    td: symbol.other;
+                                    td: symbol.total;
+                                    td: if symbol.candidate { "yes" } else { "" };
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Sections";
+                    table(class="tg") {
+                        tbody {
+                            @ for section in &self.sections {
+                                tr { th: &section.section; td: section.size; }
+                            }
+                        }
+                    }
+                    @ if self.dynamic {
+                        h2 : "Startup Cost";
+                        table(class="tg") {
+                            tbody {
+                                tr { th: "Dynamic relocations"; td: self.total; }
+                                tr { th: "Relative fixups"; td: self.relative; }
+                                tr { th: "IFUNC resolver calls"; td: self.irelative; }
+                                tr { th: "Symbol lookups at startup"; td: self.symbol_lookups; }
+                                tr { th: "Symbol lookups on first call"; td: self.lazy_lookups; }
+                                tr { th: "Pages written"; td: self.dirty_pages; }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,name,plt,got,other,total,candidate\n");
+        for symbol in &self.symbols {
+            csv += &format!(
+                "symbol,{},{},{},{},{},{}\n",
+                csv_field(&symbol.symbol),
+                symbol.plt,
+                symbol.got,
+                symbol.other,
+                symbol.total,
+                symbol.candidate
+            );
+        }
+        for section in &self.sections {
+            csv += &format!("section,{},,,,{},false\n", csv_field(&section.section), section.size);
+        }
+        let totals = [
+            ("relocations", self.total),
+            ("relative", self.relative),
+            ("irelative", self.irelative),
+            ("symbol_lookups", self.symbol_lookups),
+            ("lazy_lookups", self.lazy_lookups),
+            ("dirty_pages", self.dirty_pages),
+        ];
+        for (name, value) in totals {
+            csv += &format!("total,{},,,,{},false\n", name, value);
+        }
+        csv
+    }
+}
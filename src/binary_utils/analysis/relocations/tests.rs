@@ -0,0 +1,109 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn sample_lines() -> Vec<String> {
+    lines(&[
+        "Sections:",
+        "Idx Name          Size      VMA               LMA               File off  Algn",
+        "  7 .rela.plt     00000030  00000000000005b8  00000000000005b8  000005b8  2**3",
+        "                  CONTENTS, ALLOC, LOAD, READONLY, DATA",
+        "  9 .plt          00000030  0000000000001020  0000000000001020  00001020  2**4",
+        "                  CONTENTS, ALLOC, LOAD, READONLY, CODE",
+        "SYMBOL TABLE:",
+        "0000000000001146 g     F .text	0000000000000051              api_entry",
+        "0000000000001139 l     F .text	000000000000000d              local_helper",
+        "000000000000404c g     O .bss	0000000000000004              counter",
+        "0000000000000000       F *UND*	0000000000000000              strlen@GLIBC_2.2.5",
+        "",
+        "DYNAMIC RELOCATION RECORDS",
+        "OFFSET           TYPE              VALUE",
+        "0000000000003de8 R_X86_64_RELATIVE  *ABS*+0x0000000000001130",
+        "0000000000003fc8 R_X86_64_GLOB_DAT  counter@@Base",
+        "0000000000003fd0 R_X86_64_64       counter@@Base",
+        "0000000000004000 R_X86_64_JUMP_SLOT  strlen@GLIBC_2.2.5",
+        "0000000000004008 R_X86_64_JUMP_SLOT  api_entry@@Base",
+    ])
+}
+
+fn sample_report(bind_now: bool) -> RelocationReport {
+    let lines = sample_lines();
+    RelocationReport::new(
+        &SymbolTable::from_lines(&lines),
+        &SectionTable::from_lines(&lines),
+        &Relocations::from_lines(&lines),
+        bind_now,
+    )
+}
+
+#[test]
+fn test_relocations_per_symbol() {
+    let report = sample_report(false);
+    let rows: Vec<(&str, u32, u32, u32, bool)> = report
+        .symbols
+        .iter()
+        .map(|x| (x.symbol.as_str(), x.plt, x.got, x.other, x.candidate))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("counter", 0, 1, 1, true),
+            ("api_entry", 1, 0, 0, true),
+            ("strlen", 1, 0, 0, false),
+        ]
+    );
+    assert!(!report.find("strlen").unwrap().defined);
+    assert_eq!(report.total, 5);
+}
+
+#[test]
+fn test_startup_cost() {
+    let lazy = sample_report(false);
+    assert_eq!((lazy.relative, lazy.symbol_lookups, lazy.lazy_lookups), (1, 2, 2));
+    // 0x3de8 to 0x3fd0 share a page, the PLT slots are on the next one
+    assert_eq!(lazy.dirty_pages, 2);
+
+    let now = sample_report(true);
+    assert_eq!((now.symbol_lookups, now.lazy_lookups), (4, 0));
+}
+
+#[test]
+fn test_object_file_relocations() {
+    let lines = lines(&[
+        "SYMBOL TABLE:",
+        "0000000000000000 g     F .text	0000000000000010 .hidden helper",
+        "0000000000000010 g     F .text	0000000000000020 api_entry",
+        "0000000000000000 g     O .bss	0000000000000004 counter",
+        "",
+        "RELOCATION RECORDS FOR [.text]:",
+        "OFFSET           TYPE              VALUE",
+        "0000000000000010 R_X86_64_REX_GOTPCRELX  counter-0x0000000000000004",
+        "0000000000000041 R_X86_64_PLT32    helper-0x0000000000000004",
+        "0000000000000051 R_X86_64_PC32     api_entry-0x0000000000000004",
+        "0000000000000087 R_X86_64_PC32     .data-0x0000000000000004",
+    ]);
+    let report = RelocationReport::new(
+        &SymbolTable::from_lines(&lines),
+        &SectionTable::from_lines(&lines),
+        &Relocations::from_lines(&lines),
+        false,
+    );
+    assert!(!report.dynamic);
+    assert_eq!(report.total, 4);
+    let candidates: Vec<&str> = report.candidates().map(|x| x.symbol.as_str()).collect();
+    // helper is already hidden, and api_entry is called directly
+    assert_eq!(candidates, vec!["counter"]);
+    assert_eq!((report.symbol_lookups, report.dirty_pages), (0, 0));
+    assert!(report
+        .to_string()
+        .contains("Could be hidden, going by compiler relocations (*): counter\n"));
+}
+
+#[test]
+fn test_relocation_csv() {
+    let csv = sample_report(false).to_csv();
+    let mut rows = csv.lines();
+    assert_eq!(rows.next(), Some("kind,name,plt,got,other,total,candidate"));
+    assert_eq!(rows.next(), Some("symbol,counter,0,1,1,2,true"));
+    assert!(csv.contains("section,.plt,,,,48,false\n"));
+    assert!(csv.contains("total,lazy_lookups,,,,2,false\n"));
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use crate::binary_utils::parser::section_table::{SectionTable, SectionTableEntry};
+use crate::binary_utils::parser::symbol_table::SymbolTable;
+
+#[cfg(test)]
+mod tests;
+
+/// Which `size` total a section counts towards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeClass {
+    Text,
+    Data,
+    Bss,
+    /// Sections that are not loaded into memory, like .comment or debug info
+    NotAllocated,
+}
+
+/// The size and address of a single section
+#[derive(Debug, PartialEq)]
+pub struct SectionSize {
+    pub name: String,
+    pub size: u32,
+    pub address: u32,
+    pub class: SizeClass,
+}
+
+/// Section sizes of a binary that can be written in the same formats as GNU `size`
+#[derive(Debug, PartialEq)]
+pub struct SizeReport {
+    pub filename: String,
+    pub sections: Vec<SectionSize>,
+}
+
+/// Classifies a section header the same way as binutils: allocated code or read only sections
+/// are text, other allocated sections with contents are data and the rest are bss
+pub fn classify_section(section: &SectionTableEntry) -> SizeClass {
+    if !section.is_allocated() {
+        SizeClass::NotAllocated
+    } else if section.is_code() || section.is_readonly() {
+        SizeClass::Text
+    } else if section.has_contents() {
+        SizeClass::Data
+    } else {
+        SizeClass::Bss
+    }
+}
+
+/// Classifies a section by its name, for when no section headers are available. This follows the
+/// section flags that GNU linkers give the standard output sections
+pub fn classify_section_name(name: &str) -> SizeClass {
+    const BSS: [&str; 5] = [".bss", ".sbss", ".tbss", ".noinit", "COMMON"];
+    const DATA: [&str; 11] = [
+        ".data", ".sdata", ".tdata", ".init_array", ".fini_array", ".preinit_array", ".ctors",
+        ".dtors", ".dynamic", ".got", ".jcr",
+    ];
+    const NOT_ALLOCATED: [&str; 8] = [
+        ".comment", ".debug", ".zdebug", ".stab", ".symtab", ".strtab", ".shstrtab", ".ARM.attributes",
+    ];
+    let matches = |prefix: &&str| name == *prefix || name.starts_with(&format!("{}.", prefix)) || name.starts_with(&format!("{}_", prefix));
+    if NOT_ALLOCATED.iter().any(matches) || name.starts_with(".note.GNU-stack") {
+        SizeClass::NotAllocated
+    } else if BSS.iter().any(matches) {
+        SizeClass::Bss
+    } else if DATA.iter().any(matches) {
+        SizeClass::Data
+    } else {
+        SizeClass::Text
+    }
+}
+
+impl SizeReport {
+    /// Creates a size report from the section headers when they are available, otherwise from the
+    /// summed symbol sizes of each section
+    ///
+    /// # Arguments
+    /// * 'filename' - Name of the binary, used in the report output
+    /// * 'symbol_table' - The symbol table of the binary
+    /// * 'section_table' - Optional section headers of the same binary
+    pub fn new(filename: &str, symbol_table: &SymbolTable, section_table: Option<&SectionTable>) -> Self {
+        let sections = match section_table.filter(|x| !x.is_empty()) {
+            Some(table) => table
+                .iter()
+                .map(|x| SectionSize {
+                    name: x.name.clone(),
+                    size: x.size,
+                    address: x.vma,
+                    class: classify_section(x),
+                })
+                .collect(),
+            None => {
+                let mut addresses: BTreeMap<&str, u32> = BTreeMap::new();
+                for entry in symbol_table.iter().filter(|x| x.is_allocated()) {
+                    let address = addresses.entry(&entry.section).or_insert(entry.address);
+                    *address = (*address).min(entry.address);
+                }
+                let mut sections: Vec<SectionSize> = symbol_table
+                    .section_sizes()
+                    .into_iter()
+                    .map(|(name, size)| SectionSize {
+                        address: addresses[name.as_str()],
+                        class: classify_section_name(&name),
+                        name,
+                        size,
+                    })
+                    .collect();
+                sections.sort_by_key(|x| (x.class == SizeClass::NotAllocated, x.address));
+                sections
+            }
+        };
+        SizeReport {
+            filename: filename.to_string(),
+            sections,
+        }
+    }
+
+    /// Gets the total size of every section in a size class
+    pub fn class_total(&self, class: SizeClass) -> u32 {
+        self.sections.iter().filter(|x| x.class == class).map(|x| x.size).sum()
+    }
+
+    /// Formats the report like `size --format=berkeley`
+    pub fn to_berkeley(&self) -> String {
+        let text = self.class_total(SizeClass::Text);
+        let data = self.class_total(SizeClass::Data);
+        let bss = self.class_total(SizeClass::Bss);
+        let total = text as u64 + data as u64 + bss as u64;
+        format!(
+            "   text\t   data\t    bss\t    dec\t    hex\tfilename\n{:7}\t{:7}\t{:7}\t{:7}\t{:7x}\t{}\n",
+            text, data, bss, total, total, self.filename
+        )
+    }
+
+    /// Formats the report like `size --format=sysv`
+    pub fn to_sysv(&self) -> String {
+        let total: u64 = self.sections.iter().map(|x| x.size as u64).sum();
+        let name_width = self.sections.iter().map(|x| x.name.len()).max().unwrap_or(0).max("section".len());
+        let size_width = total.to_string().len().max("size".len());
+        let address_width = self
+            .sections
+            .iter()
+            .map(|x| x.address.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max("addr".len());
+
+        let mut output = format!(
+            "{}  :\n{:nw$}   {:>sw$}   {:>aw$}\n",
+            self.filename,
+            "section",
+            "size",
+            "addr",
+            nw = name_width,
+            sw = size_width,
+            aw = address_width
+        );
+        for section in &self.sections {
+            output += &format!(
+                "{:nw$}   {:>sw$}   {:>aw$}\n",
+                section.name,
+                section.size,
+                section.address,
+                nw = name_width,
+                sw = size_width,
+                aw = address_width
+            );
+        }
+        output += &format!("{:nw$}   {:>sw$}\n\n\n", "Total", total, nw = name_width, sw = size_width);
+        output
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn test_classify_section_name() {
+    assert_eq!(classify_section_name(".text"), SizeClass::Text);
+    assert_eq!(classify_section_name(".text.startup"), SizeClass::Text);
+    assert_eq!(classify_section_name(".rodata"), SizeClass::Text);
+    assert_eq!(classify_section_name(".isr_vector"), SizeClass::Text);
+    assert_eq!(classify_section_name(".data"), SizeClass::Data);
+    assert_eq!(classify_section_name(".data.rel.ro"), SizeClass::Data);
+    assert_eq!(classify_section_name(".init_array"), SizeClass::Data);
+    assert_eq!(classify_section_name(".got.plt"), SizeClass::Data);
+    assert_eq!(classify_section_name(".bss"), SizeClass::Bss);
+    assert_eq!(classify_section_name(".tbss"), SizeClass::Bss);
+    assert_eq!(classify_section_name(".comment"), SizeClass::NotAllocated);
+    assert_eq!(classify_section_name(".debug_info"), SizeClass::NotAllocated);
+    assert_eq!(classify_section_name(".dataset"), SizeClass::Text);
+}
+
+#[test]
+fn test_size_report_from_symbols() {
+    let lines: Vec<String> = vec![
+        "08000000 g     F .text	00000100 main",
+        "08000100 l     F .text	00000040 print_hello",
+        "20000000 g     O .data	00000010 counter",
+        "20000010 l     O .bss	00000400 print_buffer",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let report = SizeReport::new("firmware.elf", &SymbolTable::from_lines(&lines), None);
+    assert_eq!(
+        report.to_berkeley(),
+        "   text\t   data\t    bss\t    dec\t    hex\tfilename\n    320\t     16\t   1024\t   1360\t    550\tfirmware.elf\n"
+    );
+    assert_eq!(
+        report.to_sysv(),
+        "firmware.elf  :\n\
+         section   size        addr\n\
+         .text      320   134217728\n\
+         .data       16   536870912\n\
+         .bss      1024   536870928\n\
+         Total     1360\n\n\n"
+    );
+}
@@ -0,0 +1,502 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::binary_utils::analysis::call_graph::{CallGraph, FunctionNode};
+use crate::binary_utils::parser::call_graph_info::CallGraphInfo;
+use crate::binary_utils::parser::disassembly::{DisassembledFunction, Disassembly, Instruction};
+use crate::binary_utils::parser::stack_usage::{FrameKind, FrameSize};
+use crate::binary_utils::parser::symbol_table::SymbolTable;
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// How much stack a single function uses for its own frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Frame {
+    pub size: u32,
+    /// Set when the stack pointer is moved by a register, like `alloca` or variable length arrays
+    pub dynamic: bool,
+    /// Set when the function calls through a register or function pointer
+    pub indirect_calls: bool,
+    /// Set when the size comes from the compiler rather than being estimated from the disassembly
+    pub from_compiler: bool,
+}
+
+/// Worst case stack usage of an entry point and everything it calls
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StackUsage {
+    pub entry: String,
+    pub frame_size: u32,
+    /// Deepest stack usage through any chain of direct calls
+    pub worst_case: u32,
+    /// The chain of calls that uses the most stack, starting at the entry point
+    pub path: Vec<String>,
+    /// Reachable functions that are part of a recursive cycle
+    pub recursion: Vec<String>,
+    /// Reachable functions that make calls which can't be followed
+    pub indirect_calls: Vec<String>,
+    /// Reachable functions with a frame size only known at run time
+    pub dynamic_frames: Vec<String>,
+    /// Reachable functions without a compiler reported frame, when compiler output was given
+    pub estimated_frames: Vec<String>,
+}
+
+/// Worst case stack usage of each entry point, deepest first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StackReport {
+    pub entries: Vec<StackUsage>,
+}
+
+impl StackUsage {
+    /// Checks if the worst case is exact, rather than a lower bound because of recursion, indirect
+    /// calls or dynamic frames
+    pub fn is_bounded(&self) -> bool {
+        self.recursion.is_empty() && self.indirect_calls.is_empty() && self.dynamic_frames.is_empty()
+    }
+}
+
+impl StackReport {
+    /// Calculates the worst case stack usage of entry points from the frames estimated from a
+    /// disassembly
+    ///
+    /// # Arguments
+    /// * 'disassembly' - The disassembly to read stack frames from
+    /// * 'graph' - Call graph of the same disassembly
+    /// * 'entries' - Names of the entry points to report, or empty to find them automatically
+    pub fn new(disassembly: &Disassembly, graph: &CallGraph, entries: &[&str]) -> Self {
+        StackReport::from_frames(graph, &disassembly_frames(disassembly, graph), entries)
+    }
+
+    /// Calculates the worst case stack usage of entry points. Without explicit entry points every
+    /// function that no other function calls is used, which finds `main`, the reset handler and
+    /// interrupt handlers since they are only referenced from the vector table
+    ///
+    /// # Arguments
+    /// * 'graph' - Call graph of the binary
+    /// * 'frames' - Stack frame of each function by its index in the call graph
+    /// * 'entries' - Names of the entry points to report, or empty to find them automatically
+    pub fn from_frames(graph: &CallGraph, frames: &BTreeMap<usize, Frame>, entries: &[&str]) -> Self {
+        let entries: Vec<&FunctionNode> = if entries.is_empty() {
+            graph
+                .iter()
+                .filter(|x| x.callers.is_empty() && (x.size > 0 || frames.contains_key(&x.index)))
+                .collect()
+        } else {
+            entries.iter().filter_map(|x| graph.find(x)).collect()
+        };
+        let any_from_compiler = frames.values().any(|x| x.from_compiler);
+
+        let mut analysis = Analysis {
+            graph,
+            frames,
+            memo: BTreeMap::new(),
+            active: Vec::new(),
+            recursive: BTreeSet::new(),
+        };
+        let mut usages: Vec<StackUsage> = entries
+            .into_iter()
+            .map(|entry| {
+                let (worst_case, path) = analysis.worst_case(entry.index);
+                let mut reachable = graph.reachable(entry);
+                reachable.insert(entry.index);
+                let matching = |filter: &dyn Fn(Option<&Frame>) -> bool| -> Vec<String> {
+                    let nodes = reachable.iter().copied().filter(|&x| filter(frames.get(&x)));
+                    graph.names(&nodes.collect())
+                };
+                StackUsage {
+                    entry: entry.name.clone(),
+                    frame_size: frames.get(&entry.index).map_or(0, |x| x.size),
+                    worst_case,
+                    path,
+                    recursion: graph.names(&reachable.intersection(&analysis.recursive).copied().collect()),
+                    indirect_calls: matching(&|x| x.is_some_and(|x| x.indirect_calls)),
+                    dynamic_frames: matching(&|x| x.is_some_and(|x| x.dynamic)),
+                    estimated_frames: if any_from_compiler {
+                        matching(&|x| !x.is_some_and(|x| x.from_compiler))
+                    } else {
+                        Vec::new()
+                    },
+                }
+            })
+            .collect();
+        usages.sort_by(|x, y| y.worst_case.cmp(&x.worst_case).then(x.entry.cmp(&y.entry)));
+        StackReport { entries: usages }
+    }
+}
+
+/// Estimates the stack frame of every function in a disassembly
+///
+/// # Arguments
+/// * 'disassembly' - The disassembly to read stack frames from
+/// * 'graph' - Call graph of the same disassembly, whose indices the frames are keyed by
+pub fn disassembly_frames(disassembly: &Disassembly, graph: &CallGraph) -> BTreeMap<usize, Frame> {
+    let (word_size, x86) = architecture(disassembly);
+    let starts: BTreeSet<u32> = disassembly.iter().map(|x| x.address).collect();
+    disassembly
+        .iter()
+        .filter_map(|x| {
+            let node = graph.find_in_section(&x.section, x.address)?;
+            Some((node.index, frame(x, word_size, x86, &starts)))
+        })
+        .collect()
+}
+
+/// Replaces estimated frames with the ones GCC reported in `.su` files, which have been attached
+/// to the symbol table, and `.ci` files. Indirect calls found in `.ci` files are added as well.
+/// Static functions are told apart by their source file, so the call graph info must have been
+/// added to the graph with `CallGraph::add_call_graph_info`
+///
+/// # Arguments
+/// * 'frames' - Frames to update, e.g. the ones estimated from the disassembly
+/// * 'graph' - Call graph the frames are keyed by
+/// * 'symbol_table' - Symbol table with the `.su` frames attached
+/// * 'info' - Optional call graph info with frame sizes and indirect calls
+pub fn apply_compiler_frames(
+    frames: &mut BTreeMap<usize, Frame>,
+    graph: &CallGraph,
+    symbol_table: &SymbolTable,
+    info: Option<&CallGraphInfo>,
+) {
+    let mut set = |index: usize, size: FrameSize| {
+        let frame = frames.entry(index).or_default();
+        frame.size = size.bytes;
+        frame.dynamic = size.kind == FrameKind::Dynamic;
+        frame.from_compiler = true;
+    };
+    for entry in symbol_table.iter() {
+        let node = graph.find_in_section(&entry.section, entry.address);
+        if let (Some(size), Some(node)) = (entry.frame_size, node) {
+            set(node.index, size);
+        }
+    }
+    if let Some(info) = info {
+        for node in info.nodes.iter().filter(|x| !x.external) {
+            if let (Some(size), Some(function)) = (node.frame, graph.find_title(&node.title)) {
+                set(function.index, size);
+            }
+        }
+        for node in info.nodes.iter().filter(|x| !x.external && info.has_indirect_calls(&x.title)) {
+            if let Some(function) = graph.find_title(&node.title) {
+                frames.entry(function.index).or_default().indirect_calls = true;
+            }
+        }
+    }
+}
+
+/// Depth first search for the deepest call chain, remembering the result for each function
+struct Analysis<'a, 'g> {
+    graph: &'a CallGraph<'g>,
+    frames: &'a BTreeMap<usize, Frame>,
+    memo: BTreeMap<usize, (u32, Vec<String>)>,
+    /// Functions on the current call chain, used to find recursion
+    active: Vec<usize>,
+    recursive: BTreeSet<usize>,
+}
+
+impl Analysis<'_, '_> {
+    fn worst_case(&mut self, index: usize) -> (u32, Vec<String>) {
+        self.search(index).0
+    }
+
+    /// Finds the deepest call chain from a function, along with the position on the active call
+    /// chain of the earliest function that a cycle below it returns to. A result that doesn't
+    /// return above the function itself is the same wherever the search came from, so it's kept
+    fn search(&mut self, index: usize) -> ((u32, Vec<String>), usize) {
+        if let Some(result) = self.memo.get(&index) {
+            return (result.clone(), usize::MAX);
+        }
+        if let Some(position) = self.active.iter().position(|&x| x == index) {
+            // A cycle only adds one pass through each function, the real depth is unbounded
+            self.recursive.extend(self.active[position..].iter().copied());
+            return ((0, Vec::new()), position);
+        }
+
+        let depth = self.active.len();
+        self.active.push(index);
+        let node = self.graph.node(index);
+        let mut deepest = (0, Vec::new());
+        let mut returns_to = usize::MAX;
+        for &callee in &node.callees {
+            let (result, position) = self.search(callee);
+            returns_to = returns_to.min(position);
+            if result.0 > deepest.0 || deepest.1.is_empty() {
+                deepest = result;
+            }
+        }
+        self.active.pop();
+
+        let mut path = vec![node.name.clone()];
+        path.extend(deepest.1);
+        let result = (self.frames.get(&index).map_or(0, |x| x.size) + deepest.0, path);
+        if returns_to >= depth {
+            self.memo.insert(index, result.clone());
+            returns_to = usize::MAX;
+        }
+        (result, returns_to)
+    }
+}
+
+/// Gets the word size and whether the disassembly is x86 from the file format
+fn architecture(disassembly: &Disassembly) -> (u32, bool) {
+    let format = disassembly.format.as_deref().unwrap_or("");
+    let word_size = if format.contains("64") { 8 } else { 4 };
+    let x86 = format.contains("x86") || format.contains("i386");
+    (word_size, x86)
+}
+
+/// Calculates the stack frame of a function from the instructions that move the stack pointer
+/// down in its prologue, which ends at the first call, jump, return or conditional branch. Stack
+/// that shrink wrapped code only allocates after an early exit check is not counted
+///
+/// # Arguments
+/// * 'function' - The function to measure
+/// * 'word_size' - Size of a pushed register in bytes
+/// * 'x86' - Whether calls push a return address onto the stack
+/// * 'starts' - Start addresses of every function, used to tell direct calls from indirect ones
+pub fn frame(function: &DisassembledFunction, word_size: u32, x86: bool, starts: &BTreeSet<u32>) -> Frame {
+    let mut frame = Frame {
+        // The return address pushed by the caller's call instruction
+        size: if x86 { word_size } else { 0 },
+        ..Default::default()
+    };
+    let prologue = function.instructions.iter().take_while(|x| !is_branch(x));
+    for instruction in prologue {
+        match stack_adjustment(instruction, word_size) {
+            Some(Adjustment::Fixed(size)) => frame.size += size,
+            Some(Adjustment::Dynamic) => frame.dynamic = true,
+            None => (),
+        }
+    }
+    frame.indirect_calls = function
+        .instructions
+        .iter()
+        .any(|x| x.is_call() && !x.references().iter().any(|(x, _)| starts.contains(x)));
+    frame
+}
+
+/// Checks if an instruction can leave the straight line code at the start of a function: a call,
+/// jump or return, or a conditional branch to a labelled address like "bne 8010 <parse+0x10>"
+fn is_branch(instruction: &Instruction) -> bool {
+    let target = instruction.operands.rsplit(',').next().unwrap_or("").trim();
+    let labelled = target
+        .split_once(" <")
+        .is_some_and(|(address, _)| u32::from_str_radix(address, 16).is_ok());
+    instruction.is_call()
+        || instruction.is_jump()
+        || instruction.mnemonic.starts_with("ret")
+        || instruction.operands.replace(' ', "").ends_with("pc}")
+        || labelled
+}
+
+/// How an instruction moves the stack pointer down
+#[derive(Debug, PartialEq)]
+enum Adjustment {
+    Fixed(u32),
+    Dynamic,
+}
+
+/// Finds how much an instruction grows the stack, for the push, subtract and pre-indexed store
+/// forms that x86, ARM, AArch64 and RISC-V compilers use in prologues
+fn stack_adjustment(instruction: &Instruction, word_size: u32) -> Option<Adjustment> {
+    let mnemonic = instruction.mnemonic.as_str();
+    let operands = instruction.operands.replace(' ', "");
+    let last = operands.rsplit(',').next().unwrap_or("");
+    match mnemonic {
+        // x86 pushes a single register, ARM pushes a register list
+        "push" | "pushq" | "pushl" | "stmdb" | "stmfd" | "vpush" | "vstmdb" => {
+            if operands.starts_with('{') || operands.starts_with("sp!,{") {
+                Some(Adjustment::Fixed(register_list_size(&operands)))
+            } else if mnemonic.starts_with("push") {
+                Some(Adjustment::Fixed(word_size))
+            } else {
+                None
+            }
+        }
+        "sub" | "subq" | "subl" | "sub.w" | "subw" | "subs" => {
+            let x86_destination = operands.ends_with(",%rsp") || operands.ends_with(",%esp");
+            let arm_destination = operands.starts_with("sp,");
+            if x86_destination {
+                let source = operands.split(',').next().unwrap_or("");
+                Some(parse_immediate(source).map_or(Adjustment::Dynamic, |x| Adjustment::Fixed(x as u32)))
+            } else if arm_destination {
+                // AArch64 can shift the immediate, e.g. "sub sp, sp, #0x1, lsl #12"
+                let (value, shift) = match operands.find(",lsl#") {
+                    Some(position) => {
+                        let value = operands[..position].rsplit(',').next().unwrap_or("");
+                        (value, parse_immediate(&operands[position + 5..]).unwrap_or(0))
+                    }
+                    None => (last, 0),
+                };
+                Some(parse_immediate(value).map_or(Adjustment::Dynamic, |x| Adjustment::Fixed((x << shift) as u32)))
+            } else {
+                None
+            }
+        }
+        "enter" | "enterq" => {
+            let size = parse_immediate(operands.split(',').next().unwrap_or(""))?;
+            Some(Adjustment::Fixed(size as u32 + word_size))
+        }
+        "stp" | "str" | "stur" | "stp.w" | "str.w" => {
+            // Pre-indexed stores like "stp x29, x30, [sp, #-32]!" push and allocate in one go
+            let start = operands.find("[sp,#-")?;
+            let end = operands[start..].find("]!")?;
+            let size = parse_immediate(&operands[start + 4..start + end])?;
+            Some(Adjustment::Fixed(size.unsigned_abs() as u32))
+        }
+        "addi" | "c.addi" | "c.addi16sp" | "addiw" => {
+            if !operands.starts_with("sp,") {
+                return None;
+            }
+            match parse_immediate(last) {
+                Some(x) if x < 0 => Some(Adjustment::Fixed(x.unsigned_abs() as u32)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Gets the number of bytes an ARM register list takes on the stack, e.g. "{r4, r5, r6, lr}" or
+/// "{d8-d9}". Core and single precision registers take 4 bytes, double precision registers 8
+fn register_list_size(operands: &str) -> u32 {
+    let start = operands.find('{').map_or(0, |x| x + 1);
+    let end = operands.rfind('}').unwrap_or(operands.len());
+    operands[start..end]
+        .split(',')
+        .filter(|x| !x.is_empty())
+        .map(|register| {
+            let width = if register.starts_with('d') {
+                8
+            } else if register.starts_with('q') {
+                16
+            } else {
+                4
+            };
+            let count = match register.split_once('-') {
+                Some((first, last)) => {
+                    let number = |x: &str| x.trim_start_matches(|c: char| c.is_ascii_alphabetic()).parse::<u32>().ok();
+                    match (number(first), number(last)) {
+                        (Some(first), Some(last)) if last >= first => last - first + 1,
+                        _ => 1,
+                    }
+                }
+                None => 1,
+            };
+            width * count
+        })
+        .sum()
+}
+
+/// Parses an immediate operand in any of the forms objdump prints, e.g. "$0x10", "#16", "#-32" or "-16"
+fn parse_immediate(input: &str) -> Option<i64> {
+    let input = input.trim_start_matches(['$', '#']);
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, input),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+impl fmt::Display for StackReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for usage in &self.entries {
+            writeln!(
+                f,
+                "{}: {} bytes worst case{}",
+                usage.entry,
+                usage.worst_case,
+                if usage.is_bounded() { "" } else { " (lower bound)" }
+            )?;
+            writeln!(f, "  Path: {}", usage.path.join(" -> "))?;
+            if !usage.recursion.is_empty() {
+                writeln!(f, "  Recursion: {}", usage.recursion.join(", "))?;
+            }
+            if !usage.indirect_calls.is_empty() {
+                writeln!(f, "  Indirect calls: {}", usage.indirect_calls.join(", "))?;
+            }
+            if !usage.dynamic_frames.is_empty() {
+                writeln!(f, "  Dynamic frames: {}", usage.dynamic_frames.join(", "))?;
+            }
+            if !usage.estimated_frames.is_empty() {
+                writeln!(f, "  Estimated from disassembly: {}", usage.estimated_frames.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Report for StackReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Stack Usage";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Worst Case Stack Usage";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Entry";
+                                th: "Frame";
+                                th: "Worst Case";
+                                th: "Path";
+                                th: "Recursion";
+                                th: "Indirect Calls";
+                                th: "Dynamic Frames";
+                                th: "Estimated Frames";
+                            }
+                        }
+                        tbody {
+                            @ for usage in &self.entries {
+                                tr {
+                                    td: &usage.entry;
+                                    td: usage.frame_size;
+                                    td: usage.worst_case;
+                                    td: usage.path.join(" -> ");
+                                    td: usage.recursion.join(", ");
+                                    td: usage.indirect_calls.join(", ");
+                                    td: usage.dynamic_frames.join(", ");
+                                    td: usage.estimated_frames.join(", ");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("entry,frame_size,worst_case,bounded,path,recursion,indirect_calls,dynamic_frames,estimated_frames\n");
+        for usage in &self.entries {
+            csv += &format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                csv_field(&usage.entry),
+                usage.frame_size,
+                usage.worst_case,
+                usage.is_bounded(),
+                csv_field(&usage.path.join(" ")),
+                csv_field(&usage.recursion.join(" ")),
+                csv_field(&usage.indirect_calls.join(" ")),
+                csv_field(&usage.dynamic_frames.join(" ")),
+                csv_field(&usage.estimated_frames.join(" "))
+            );
+        }
+        csv
+    }
+}
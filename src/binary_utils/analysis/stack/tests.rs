@@ -0,0 +1,275 @@
+use super::*;
+
+use crate::binary_utils::parser::disassembly::parse_instruction;
+use crate::binary_utils::parser::stack_usage::StackUsageTable;
+use crate::binary_utils::test_utils::lines;
+
+fn adjustment(line: &str, word_size: u32) -> Option<Adjustment> {
+    stack_adjustment(&parse_instruction(line).unwrap().1, word_size)
+}
+
+#[test]
+fn test_parse_immediate() {
+    assert_eq!(parse_immediate("$0x18"), Some(0x18));
+    assert_eq!(parse_immediate("#16"), Some(16));
+    assert_eq!(parse_immediate("#-32"), Some(-32));
+    assert_eq!(parse_immediate("-0x20"), Some(-0x20));
+    assert_eq!(parse_immediate("r3"), None);
+}
+
+#[test]
+fn test_register_list_size() {
+    assert_eq!(register_list_size("{r4,r5,r6,lr}"), 16);
+    assert_eq!(register_list_size("{r4-r7,lr}"), 20);
+    assert_eq!(register_list_size("{d8-d9}"), 16);
+    assert_eq!(register_list_size("sp!,{r4,lr}"), 8);
+}
+
+#[test]
+fn test_x86_stack_adjustments() {
+    assert_eq!(adjustment("    1060:	53                   	push   %rbx", 8), Some(Adjustment::Fixed(8)));
+    assert_eq!(
+        adjustment("    1061:	48 83 ec 18          	sub    $0x18,%rsp", 8),
+        Some(Adjustment::Fixed(0x18))
+    );
+    assert_eq!(adjustment("    1065:	48 29 c4             	sub    %rax,%rsp", 8), Some(Adjustment::Dynamic));
+    assert_eq!(adjustment("    1068:	48 83 e8 18          	sub    $0x18,%rax", 8), None);
+}
+
+#[test]
+fn test_arm_stack_adjustments() {
+    assert_eq!(adjustment("    8000:	b5f0      	push	{r4, r5, r6, r7, lr}", 4), Some(Adjustment::Fixed(20)));
+    assert_eq!(adjustment("    8002:	b082      	sub	sp, #8", 4), Some(Adjustment::Fixed(8)));
+    assert_eq!(
+        adjustment("    8004:	e92d 4ff0 	stmdb	sp!, {r4, r5, r6, r7, r8, r9, sl, fp, lr}", 4),
+        Some(Adjustment::Fixed(36))
+    );
+    assert_eq!(adjustment("    8008:	ed2d 8b04 	vpush	{d8-d9}", 4), Some(Adjustment::Fixed(16)));
+    assert_eq!(adjustment("    800c:	ebad 0d03 	sub.w	sp, sp, r3", 4), Some(Adjustment::Dynamic));
+    assert_eq!(adjustment("    8010:	1e5b      	subs	r3, r3, #1", 4), None);
+}
+
+#[test]
+fn test_aarch64_and_riscv_stack_adjustments() {
+    assert_eq!(
+        adjustment("  400580:	a9be7bfd 	stp	x29, x30, [sp, #-32]!", 8),
+        Some(Adjustment::Fixed(32))
+    );
+    assert_eq!(
+        adjustment("  400584:	d14007ff 	sub	sp, sp, #0x1, lsl #12", 8),
+        Some(Adjustment::Fixed(0x1000))
+    );
+    assert_eq!(adjustment("   10078:	7179                	addi	sp,sp,-48", 8), Some(Adjustment::Fixed(48)));
+    assert_eq!(adjustment("   1007a:	6145                	addi	sp,sp,48", 8), None);
+}
+
+fn disassembly() -> Disassembly {
+    Disassembly::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "Disassembly of section .text:",
+        "",
+        "00008000 <main>:",
+        "    8000:	b510      	push	{r4, lr}",
+        "    8002:	b084      	sub	sp, #16",
+        "    8004:	f000 f804 	bl	8010 <parse>",
+        "    8008:	f000 f80a 	bl	8020 <log>",
+        "",
+        "00008010 <parse>:",
+        "    8010:	b500      	push	{lr}",
+        "    8012:	4798      	blx	r3",
+        "    8014:	f7ff fffc 	bl	8010 <parse>",
+        "",
+        "00008020 <log>:",
+        "    8020:	b5f0      	push	{r4, r5, r6, r7, lr}",
+        "    8022:	b088      	sub	sp, #32",
+        "    8024:	bdf0      	pop	{r4, r5, r6, r7, pc}",
+        "",
+        "00008030 <SysTick_Handler>:",
+        "    8030:	b508      	push	{r3, lr}",
+        "    8032:	f7ff fff5 	bl	8020 <log>",
+    ]))
+}
+
+#[test]
+fn test_worst_case_per_entry_point() {
+    let disassembly = disassembly();
+    let symbol_table = SymbolTable::new();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = StackReport::new(&disassembly, &graph, &[]);
+
+    let entries: Vec<&str> = report.entries.iter().map(|x| x.entry.as_str()).collect();
+    assert_eq!(entries, vec!["main", "SysTick_Handler"]);
+
+    let main = &report.entries[0];
+    assert_eq!(main.frame_size, 24);
+    assert_eq!(main.worst_case, 24 + 52);
+    assert_eq!(main.path, vec!["main".to_string(), "log".to_string()]);
+    assert_eq!(main.recursion, vec!["parse".to_string()]);
+    assert_eq!(main.indirect_calls, vec!["parse".to_string()]);
+    assert!(!main.is_bounded());
+
+    let handler = &report.entries[1];
+    assert_eq!(handler.worst_case, 8 + 52);
+    assert!(handler.is_bounded());
+}
+
+#[test]
+fn test_x86_frames_include_the_return_address() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "hello_world:     file format elf64-x86-64",
+        "Disassembly of section .text:",
+        "0000000000001060 <main>:",
+        "    1060:	53                   	push   %rbx",
+        "    1061:	48 83 ec 10          	sub    $0x10,%rsp",
+        "    1065:	c3                   	ret",
+    ]));
+    let symbol_table = SymbolTable::new();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = StackReport::new(&disassembly, &graph, &["main"]);
+    assert_eq!(report.entries[0].worst_case, 8 + 8 + 0x10);
+}
+
+#[test]
+fn test_frame_only_counts_the_prologue() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "Disassembly of section .text:",
+        "",
+        "00008000 <send>:",
+        "    8000:	b510      	push	{r4, lr}",
+        "    8002:	b082      	sub	sp, #8",
+        "    8004:	2800      	cmp	r0, #0",
+        "    8006:	d003      	beq.n	8010 <send+0x10>",
+        "    8008:	b082      	sub	sp, #8",
+        "    800a:	f000 f805 	bl	8018 <write>",
+        "    800e:	b002      	add	sp, #8",
+        "    8010:	b002      	add	sp, #8",
+        "    8012:	bd10      	pop	{r4, pc}",
+        "",
+        "00008018 <write>:",
+        "    8018:	b500      	push	{lr}",
+        "    801a:	4798      	blx	r3",
+        "    801c:	b082      	sub	sp, #8",
+        "    801e:	bd00      	pop	{pc}",
+    ]));
+    let symbol_table = SymbolTable::new();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let frames = disassembly_frames(&disassembly, &graph);
+    let frame = |name: &str| frames[&graph.find(name).unwrap().index];
+    assert_eq!(frame("send").size, 8 + 8);
+    assert!(!frame("send").indirect_calls);
+    assert_eq!(frame("write").size, 4);
+    assert!(frame("write").indirect_calls);
+}
+
+#[test]
+fn test_recursion_keeps_results_below_the_cycle() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "Disassembly of section .text:",
+        "",
+        "00008000 <main>:",
+        "    8000:	f000 f806 	bl	8010 <eval>",
+        "",
+        "00008010 <eval>:",
+        "    8010:	b510      	push	{r4, lr}",
+        "    8012:	f000 f805 	bl	8020 <apply>",
+        "",
+        "00008020 <apply>:",
+        "    8020:	b508      	push	{r3, lr}",
+        "    8022:	f7ff fff5 	bl	8010 <eval>",
+        "    8026:	f000 f803 	bl	8030 <lookup>",
+        "",
+        "00008030 <lookup>:",
+        "    8030:	b082      	sub	sp, #8",
+        "    8032:	4770      	bx	lr",
+    ]));
+    let symbol_table = SymbolTable::new();
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let frames = disassembly_frames(&disassembly, &graph);
+    let mut analysis = Analysis {
+        graph: &graph,
+        frames: &frames,
+        memo: BTreeMap::new(),
+        active: Vec::new(),
+        recursive: BTreeSet::new(),
+    };
+    let main = graph.find("main").unwrap().index;
+    assert_eq!(analysis.worst_case(main).0, 8 + 8 + 8);
+    // apply's result depends on eval being on the call chain, lookup's doesn't
+    let memo: Vec<&str> = analysis.memo.keys().map(|&x| graph.node(x).name.as_str()).collect();
+    assert_eq!(memo, vec!["main", "eval", "lookup"]);
+}
+
+#[test]
+fn test_compiler_frames_replace_estimates() {
+    let disassembly = disassembly();
+    let mut symbol_table = SymbolTable::from_lines(&lines(&[
+        "00008000 g     F .text	0000000c main",
+        "00008020 g     F .text	00000008 log",
+    ]));
+    symbol_table.attach_stack_usage(&StackUsageTable::from_lines(&lines(&["main.c:3:5:main\t40\tstatic"])));
+    let info = CallGraphInfo::from_lines(&lines(&[
+        r#"node: { title: "log" label: "log\nlog.c:3:6\n56 bytes (dynamic,bounded)\n1 dynamic objects" }"#,
+        r#"node: { title: "__indirect_call" label: "Indirect Call Placeholder" shape : ellipse }"#,
+        r#"edge: { sourcename: "log" targetname: "__indirect_call" label: "log.c:5:3" }"#,
+    ]));
+    let mut graph = CallGraph::new(&disassembly, &symbol_table);
+    graph.add_call_graph_info(&info, &symbol_table);
+
+    let mut frames = disassembly_frames(&disassembly, &graph);
+    apply_compiler_frames(&mut frames, &graph, &symbol_table, Some(&info));
+    let frame = |name: &str| frames[&graph.find(name).unwrap().index];
+    assert_eq!(frame("main").size, 40);
+    assert!(frame("log").indirect_calls && !frame("log").dynamic);
+    assert!(!frame("parse").from_compiler);
+
+    let report = StackReport::from_frames(&graph, &frames, &["main"]);
+    let main = &report.entries[0];
+    assert_eq!(main.worst_case, 40 + 56);
+    assert_eq!(main.indirect_calls, vec!["log".to_string(), "parse".to_string()]);
+    assert_eq!(main.estimated_frames, vec!["parse".to_string()]);
+}
+
+#[test]
+fn test_compiler_frames_of_static_functions() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "Disassembly of section .text:",
+        "",
+        "00008000 <main>:",
+        "    8000:	f000 f806 	bl	8010 <init>",
+        "    8004:	f000 f808 	bl	8018 <init>",
+        "",
+        "00008010 <init>:",
+        "    8010:	4770      	bx	lr",
+        "",
+        "00008018 <init>:",
+        "    8018:	4770      	bx	lr",
+    ]));
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 uart.c",
+        "00008010 l     F .text	00000002 init",
+        "00000000 l    df *ABS*	00000000 timer.c",
+        "00008018 l     F .text	00000002 init",
+        "00008000 g     F .text	00000008 main",
+    ]));
+    let info = CallGraphInfo::from_lines(&lines(&[
+        r#"node: { title: "timer.c:init" label: "init\ntimer.c:4:13\n24 bytes (static)\n0 dynamic objects" }"#,
+        r#"node: { title: "uart.c:init" label: "init\nuart.c:9:13\n8 bytes (static)\n0 dynamic objects" }"#,
+        r#"node: { title: "__indirect_call" label: "Indirect Call Placeholder" shape : ellipse }"#,
+        r#"edge: { sourcename: "uart.c:init" targetname: "__indirect_call" label: "uart.c:10:3" }"#,
+    ]));
+    let mut graph = CallGraph::new(&disassembly, &symbol_table);
+    graph.add_call_graph_info(&info, &symbol_table);
+    assert_eq!(graph.iter().count(), 3);
+
+    let mut frames = disassembly_frames(&disassembly, &graph);
+    apply_compiler_frames(&mut frames, &graph, &symbol_table, Some(&info));
+    let frame = |address: u32| frames[&graph.find_address(address).unwrap().index];
+    assert_eq!((frame(0x8010).size, frame(0x8010).indirect_calls), (8, true));
+    assert_eq!((frame(0x8018).size, frame(0x8018).indirect_calls), (24, false));
+
+    let report = StackReport::from_frames(&graph, &frames, &["main"]);
+    assert_eq!(report.entries[0].worst_case, 24);
+}
@@ -0,0 +1,235 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{image_size, is_thread_local_section, SymbolTable};
+use crate::binary_utils::report::{csv_field, percent, size_string, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Totals for a single section of the image
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SectionSummary {
+    pub section: String,
+    /// Number of symbols placed in the section, not counting the section symbol itself
+    pub symbol_count: usize,
+    /// Bytes covered by the symbols in the section, counting aliases and overlaps once
+    pub symbol_size: u32,
+    pub largest_symbol: Option<String>,
+    pub largest_symbol_size: u32,
+    /// Whether the size of the largest symbol was inferred from the next symbol
+    pub largest_symbol_inferred: bool,
+    /// Size from the section header, if a section table is available
+    pub section_size: Option<u32>,
+    /// Bytes of the section that are not covered by any symbol, if a section table is available
+    pub uncovered: Option<u32>,
+    pub percent_of_image: f64,
+}
+
+/// Per section totals of a symbol table, similar to `size -A`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub sections: Vec<SectionSummary>,
+    /// Total size of all allocated sections, or of all symbols if there is no section table
+    pub image_size: u32,
+}
+
+impl Summary {
+    /// Summarizes the symbol table per section. When a section table is given, every allocated
+    /// section is listed in header order along with the bytes not covered by symbols
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to summarize
+    /// * 'section_table' - Optional section headers of the same binary
+    /// * 'section_sizes' - Optional size of each section to use when there are no section headers,
+    ///   for when symbols were filtered out of the table. Defaults to the sizes of the table itself
+    pub fn new(
+        symbol_table: &SymbolTable,
+        section_table: Option<&SectionTable>,
+        section_sizes: Option<&BTreeMap<String, u32>>,
+    ) -> Self {
+        let symbol_sizes = symbol_table.section_sizes();
+        let section_sizes = section_sizes.unwrap_or(&symbol_sizes);
+        let section_table = section_table.filter(|x| !x.is_empty());
+
+        let mut names: Vec<String> = match section_table {
+            Some(table) => table.iter().filter(|x| x.is_allocated()).map(|x| x.name.clone()).collect(),
+            None => Vec::new(),
+        };
+        for name in section_sizes.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        let image_size = match section_table {
+            Some(table) => table
+                .iter()
+                .filter(|x| x.is_allocated() && !is_thread_local_section(&x.name))
+                .map(|x| x.size)
+                .sum(),
+            None => image_size(section_sizes),
+        };
+
+        let sections = names
+            .into_iter()
+            .map(|name| {
+                let symbols: Vec<_> = symbol_table
+                    .iter()
+                    .filter(|x| x.section == name && !x.is_section_symbol())
+                    .collect();
+                let largest = symbols
+                    .iter()
+                    .filter(|x| x.alignment_or_size > 0)
+                    .max_by_key(|x| x.alignment_or_size);
+                let symbol_size = symbol_sizes.get(&name).copied().unwrap_or(0);
+                let section_size = section_table.and_then(|x| x.find(&name)).map(|x| x.size);
+                let full_size = section_sizes.get(&name).copied().unwrap_or(symbol_size);
+                SectionSummary {
+                    symbol_count: symbols.len(),
+                    symbol_size,
+                    largest_symbol: largest.map(|x| x.name.clone()),
+                    largest_symbol_size: largest.map(|x| x.alignment_or_size).unwrap_or(0),
+                    largest_symbol_inferred: largest.is_some_and(|x| x.size_inferred),
+                    section_size,
+                    uncovered: section_size.map(|x| x.saturating_sub(symbol_size)),
+                    percent_of_image: percent(section_size.unwrap_or(full_size), image_size),
+                    section: name,
+                }
+            })
+            .collect();
+
+        Summary { sections, image_size }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .sections
+            .iter()
+            .map(|x| x.section.len())
+            .chain(std::iter::once("Section".len()))
+            .max()
+            .unwrap();
+        let header = format!(
+            "{:width$} {:>8} {:>10} {:>8} {:>12} {:>10} {}",
+            "Section",
+            "Symbols",
+            "Size",
+            "% Image",
+            "Section Size",
+            "Uncovered",
+            "Largest Symbol",
+            width = width
+        );
+        let banner_break = "-".repeat(header.len());
+        writeln!(f, "{}", banner_break)?;
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", banner_break)?;
+        for section in &self.sections {
+            let largest = match &section.largest_symbol {
+                Some(name) if section.largest_symbol_inferred => {
+                    format!("{} ({}, inferred)", name, section.largest_symbol_size)
+                }
+                Some(name) => format!("{} ({})", name, section.largest_symbol_size),
+                None => "-".to_string(),
+            };
+            writeln!(
+                f,
+                "{:width$} {:>8} {:>10} {:>7.1}% {:>12} {:>10} {}",
+                section.section,
+                section.symbol_count,
+                section.symbol_size,
+                section.percent_of_image,
+                optional(section.section_size),
+                optional(section.uncovered),
+                largest,
+                width = width
+            )?;
+        }
+        writeln!(f, "{}", banner_break)?;
+        writeln!(f, "{:width$} {:>8} {:>10}", "Total", "", self.image_size, width = width)
+    }
+}
+
+impl Report for Summary {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Section Summary";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Section Summary";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                th: "Symbols";
+                                th: "Size";
+                                th: "% Image";
+                                th: "Section Size";
+                                th: "Uncovered";
+                                th: "Largest Symbol";
+                                th: "Largest Symbol Size";
+                            }
+                        }
+                        tbody {
+                            @ for section in &self.sections {
+                                tr {
+                                    td: &section.section;
+                                    td: section.symbol_count;
+                                    td: section.symbol_size;
+                                    td: format!("{:.1}", section.percent_of_image);
+                                    td: optional(section.section_size);
+                                    td: optional(section.uncovered);
+                                    td: section.largest_symbol.as_deref().unwrap_or("-");
+                                    td: size_string(section.largest_symbol_size, section.largest_symbol_inferred);
+                                }
+                            }
+                            tr {
+                                th: "Total";
+                                td;
+                                td: self.image_size;
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "section,symbol_count,symbol_size,percent_of_image,section_size,uncovered,largest_symbol,largest_symbol_size,largest_symbol_inferred\n",
+        );
+        for section in &self.sections {
+            csv += &format!(
+                "{},{},{},{:.2},{},{},{},{},{}\n",
+                csv_field(&section.section),
+                section.symbol_count,
+                section.symbol_size,
+                section.percent_of_image,
+                section.section_size.map(|x| x.to_string()).unwrap_or_default(),
+                section.uncovered.map(|x| x.to_string()).unwrap_or_default(),
+                csv_field(section.largest_symbol.as_deref().unwrap_or("")),
+                section.largest_symbol_size,
+                section.largest_symbol_inferred
+            );
+        }
+        csv
+    }
+}
+
+/// Formats an optional size, using a dash when it is unknown
+fn optional(value: Option<u32>) -> String {
+    value.map(|x| x.to_string()).unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,65 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn sample_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "08000000 l    d  .text	00000000 .text",
+        "08000000 g     F .text	00000100 main",
+        "08000100 l     F .text	00000040 print_hello",
+        "20000000 l     O .bss	00000400 print_buffer",
+    ]))
+}
+
+#[test]
+fn test_summary_without_section_table() {
+    let summary = Summary::new(&sample_table(), None, None);
+    assert_eq!(summary.image_size, 0x540);
+    assert_eq!(summary.sections.len(), 2);
+    let text = &summary.sections[1];
+    assert_eq!(text.section, ".text");
+    assert_eq!(text.symbol_count, 2);
+    assert_eq!(text.symbol_size, 0x140);
+    assert_eq!(text.largest_symbol, Some("main".to_string()));
+    assert_eq!(text.largest_symbol_size, 0x100);
+    assert_eq!(text.section_size, None);
+    assert_eq!(text.uncovered, None);
+}
+
+#[test]
+fn test_summary_with_section_table() {
+    let sections = SectionTable::from_lines(&lines(&[
+        "  0 .text         00000180  08000000  08000000  00010000  2**2",
+        "                  CONTENTS, ALLOC, LOAD, READONLY, CODE",
+        "  1 .bss          00000400  20000000  20000000  00020000  2**3",
+        "                  ALLOC",
+        "  2 .comment      00000010  00000000  00000000  00020000  2**0",
+        "                  CONTENTS, READONLY",
+    ]));
+    let summary = Summary::new(&sample_table(), Some(&sections), None);
+    assert_eq!(summary.image_size, 0x580);
+    let names: Vec<&str> = summary.sections.iter().map(|x| x.section.as_str()).collect();
+    assert_eq!(names, vec![".text", ".bss"]);
+    assert_eq!(summary.sections[0].section_size, Some(0x180));
+    assert_eq!(summary.sections[0].uncovered, Some(0x40));
+    assert_eq!(summary.sections[1].uncovered, Some(0));
+}
+
+#[test]
+fn test_summary_csv_output() {
+    let csv = Summary::new(&sample_table(), None, None).to_csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2], ".text,2,320,23.81,,,main,256,false");
+}
+
+#[test]
+fn test_summary_of_filtered_table() {
+    let section_sizes = sample_table().section_sizes();
+    let symbol_table: SymbolTable = sample_table().into_iter().filter(|x| x.name == "main").collect();
+    let summary = Summary::new(&symbol_table, None, Some(&section_sizes));
+    assert_eq!(summary.image_size, 0x540);
+    let names: Vec<&str> = summary.sections.iter().map(|x| x.section.as_str()).collect();
+    assert_eq!(names, vec![".bss", ".text"]);
+    assert_eq!(summary.sections[1].symbol_size, 0x100);
+    assert!((summary.sections[1].percent_of_image - 23.81).abs() < 0.01);
+}
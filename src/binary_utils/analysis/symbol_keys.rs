@@ -0,0 +1,183 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::binary_utils::parser::symbol_table::{SymbolScope, SymbolTable, SymbolTableEntry, SymbolType};
+
+#[cfg(test)]
+mod tests;
+
+/// Compilers with built-in rules, used when a rules file doesn't pick any
+pub const COMPILERS: [&str; 4] = ["gcc", "llvm", "rust", "msvc"];
+
+/// Built-in rules for the parts of symbol names that change between builds of the same source
+const BUILTIN_RULES: [(&str, &str, &str); 9] = [
+    // Clone numbers, e.g. foo.constprop.3 or foo.lto_priv.0
+    ("gcc", r"\.(constprop|isra|part|cold|lto_priv)\.[0-9]+", ".$1"),
+    // Static locals and switch tables numbered per translation unit, e.g. completed.8060
+    ("gcc", r"^([A-Za-z_][A-Za-z0-9_$]*)\.[0-9]+$", "$1"),
+    // Static constructors of LTO partitions, e.g. _GLOBAL__sub_I_65535_0_main.cpp
+    ("gcc", r"^_GLOBAL__sub_I_[0-9]+_[0-9]+_", "_GLOBAL__sub_I_"),
+    // Locals promoted by ThinLTO and unique internal linkage names
+    ("llvm", r"\.(llvm|__uniq)\.[0-9]+", ".$1"),
+    ("llvm", r"^(\.L__unnamed_)[0-9]+$", "$1"),
+    // Legacy mangled and demangled function hashes
+    ("rust", r"17h[0-9a-f]{16}E$", "E"),
+    ("rust", r"::h[0-9a-f]{16}$", ""),
+    // Unnamed constants, e.g. anon.5f3c0d1e0a0b9c8d7e6f5a4b3c2d1e0f.12
+    ("rust", r"^anon\.[0-9a-f]{32}\.", "anon."),
+    // Anonymous namespace hashes
+    ("msvc", r"\?A0x[0-9a-f]{8}", "?A0x"),
+];
+
+/// Settings for symbol keys, typically loaded from a TOML file. Rules are regular expressions
+/// applied in order after the built-in rules of the chosen compilers, with `$1` style references
+/// to their groups in the replacement:
+///
+/// ```toml
+/// compilers = ["gcc", "rust"]
+///
+/// [[rules]]
+/// pattern = "_v[0-9]+$"
+///
+/// [[rules]]
+/// pattern = "^build_[0-9a-f]+_(.*)"
+/// replacement = "$1"
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRules {
+    /// Compilers to apply the built-in rules of, or every compiler if not given
+    pub compilers: Option<Vec<String>>,
+    #[serde(default)]
+    pub rules: Vec<KeyRule>,
+}
+
+/// A regular expression whose matches are replaced in every symbol name
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+}
+
+/// Normalizes symbol names into keys that stay the same between builds, so symbols can be
+/// matched when comparing them
+#[derive(Debug)]
+pub struct SymbolKeys {
+    rules: Vec<(Regex, String)>,
+}
+
+impl SymbolKeys {
+    /// Compiles the built-in rules of the chosen compilers and the user rules
+    ///
+    /// # Arguments
+    /// * 'rules' - The compilers and user rules to use
+    pub fn new(rules: &KeyRules) -> io::Result<Self> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let compilers: Vec<&str> = match &rules.compilers {
+            Some(names) => names.iter().map(|x| x.as_str()).collect(),
+            None => COMPILERS.to_vec(),
+        };
+        if let Some(unknown) = compilers.iter().find(|x| !COMPILERS.contains(x)) {
+            return Err(invalid(format!("unknown compiler: {}", unknown)));
+        }
+        let builtin = BUILTIN_RULES
+            .iter()
+            .filter(|(compiler, _, _)| compilers.contains(compiler))
+            .map(|&(_, pattern, replacement)| (pattern, replacement));
+        let user = rules.rules.iter().map(|x| (x.pattern.as_str(), x.replacement.as_str()));
+        let rules = builtin
+            .chain(user)
+            .map(|(pattern, replacement)| {
+                Regex::new(pattern)
+                    .map(|x| (x, replacement.to_string()))
+                    .map_err(|e| invalid(e.to_string()))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(SymbolKeys { rules })
+    }
+
+    /// Loads the key rules from a TOML file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename of the rules file
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let keys = SymbolKeys::from_file("keys.toml")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(filename)?.parse()
+    }
+
+    /// Normalizes a symbol name by applying every rule in order
+    ///
+    /// # Examples
+    /// ```
+    /// use binarytools::binary_utils::analysis::symbol_keys::SymbolKeys;
+    /// let keys = SymbolKeys::default();
+    /// assert_eq!(keys.normalize("completed.8060"), "completed");
+    /// assert_eq!(keys.normalize("scale.constprop.3"), "scale.constprop");
+    /// ```
+    pub fn normalize(&self, name: &str) -> String {
+        self.rules.iter().fold(name.to_string(), |name, (regex, replacement)| {
+            regex.replace_all(&name, replacement.as_str()).into_owned()
+        })
+    }
+
+    /// Gives every symbol of a table a key that is unique within the table. Local symbols are
+    /// prefixed with the file symbol they follow, e.g. "uart.c:init", and symbols that still share
+    /// a key are numbered in table order, e.g. "counter#2"
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table to key
+    pub fn keys<'a>(&self, symbol_table: &'a SymbolTable) -> Vec<(String, &'a SymbolTableEntry)> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut file: Option<&str> = None;
+        let mut keys = Vec::new();
+        for entry in symbol_table.iter() {
+            if entry.flags.symbol_type == SymbolType::File {
+                file = Some(&entry.name).filter(|x| !x.is_empty()).map(|x| x.as_str());
+                continue;
+            }
+            if !entry.is_allocated() || entry.is_section_symbol() {
+                continue;
+            }
+            let name = self.normalize(entry.plain_name());
+            let key = match file.filter(|_| entry.flags.scope == SymbolScope::Local) {
+                Some(file) => format!("{}:{}", file, name),
+                None => name,
+            };
+            let count = counts.entry(key.clone()).or_insert(0);
+            *count += 1;
+            match *count {
+                1 => keys.push((key, entry)),
+                n => keys.push((format!("{}#{}", key, n), entry)),
+            }
+        }
+        keys
+    }
+}
+
+impl Default for SymbolKeys {
+    /// Creates symbol keys with the built-in rules of every compiler
+    fn default() -> Self {
+        SymbolKeys::new(&KeyRules {
+            compilers: None,
+            rules: Vec::new(),
+        })
+        .expect("built-in rules are valid")
+    }
+}
+
+impl std::str::FromStr for SymbolKeys {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rules: KeyRules = toml::from_str(s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        SymbolKeys::new(&rules)
+    }
+}
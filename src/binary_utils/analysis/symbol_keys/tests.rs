@@ -0,0 +1,79 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_builtin_rules() {
+    let keys = SymbolKeys::default();
+    assert_eq!(keys.normalize("completed.8060"), "completed");
+    assert_eq!(keys.normalize("CSWTCH.16"), "CSWTCH");
+    assert_eq!(keys.normalize("parse.isra.0.constprop.3"), "parse.isra.constprop");
+    assert_eq!(keys.normalize("state.lto_priv.0"), "state.lto_priv");
+    assert_eq!(keys.normalize("_GLOBAL__sub_I_65535_0_main.cpp"), "_GLOBAL__sub_I_main.cpp");
+    assert_eq!(keys.normalize("helper.llvm.8512346611248012345"), "helper.llvm");
+    assert_eq!(keys.normalize("_ZN3app4main17h0123456789abcdefE"), "_ZN3app4mainE");
+    assert_eq!(keys.normalize("app::main::h0123456789abcdef"), "app::main");
+    assert_eq!(keys.normalize("anon.5f3c0d1e0a0b9c8d7e6f5a4b3c2d1e0f.12"), "anon.12");
+    assert_eq!(keys.normalize("??_7Widget@?A0x1b2c3d4e@@6B@"), "??_7Widget@?A0x@@6B@");
+    // Names that only contain numbers are left alone
+    assert_eq!(keys.normalize("uart2_irq"), "uart2_irq");
+    assert_eq!(keys.normalize("_ZN6sensor4readEv"), "_ZN6sensor4readEv");
+}
+
+#[test]
+fn test_user_rules() {
+    let keys: SymbolKeys = "
+        compilers = [\"rust\"]
+
+        [[rules]]
+        pattern = \"_v[0-9]+$\"
+
+        [[rules]]
+        pattern = \"^build_[0-9a-f]+_(.*)\"
+        replacement = \"$1\"
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(keys.normalize("table_v12"), "table");
+    assert_eq!(keys.normalize("build_3fa9_init"), "init");
+    // The GCC rules were not chosen
+    assert_eq!(keys.normalize("completed.8060"), "completed.8060");
+    assert_eq!(keys.normalize("app::main::h0123456789abcdef"), "app::main");
+}
+
+#[test]
+fn test_invalid_rules() {
+    assert!("compilers = [\"icc\"]".parse::<SymbolKeys>().is_err());
+    assert!("[[rules]]\npattern = \"(\"".parse::<SymbolKeys>().is_err());
+    assert!("[[rules]]\nregex = \"x\"".parse::<SymbolKeys>().is_err());
+}
+
+#[test]
+fn test_keys_are_unique() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 uart.c",
+        "08000100 l     F .text	00000010 init",
+        "20000000 l     O .bss	00000001 completed.8060",
+        "00000000 l    df *ABS*	00000000 spi.c",
+        "08000110 l     F .text	00000010 init",
+        "20000004 l     O .bss	00000004 count.1",
+        "20000008 l     O .bss	00000004 count.2",
+        "08000120 g     F .text	00000020 main",
+        "08000000 l    d  .text	00000000 .text",
+        "00000000       F *UND*	00000000 printf",
+    ]));
+    let keys: Vec<String> = SymbolKeys::default().keys(&symbol_table).into_iter().map(|(key, _)| key).collect();
+    assert_eq!(
+        keys,
+        vec!["uart.c:init", "uart.c:completed", "spi.c:init", "spi.c:count", "spi.c:count#2", "main"]
+    );
+}
+
+#[test]
+fn test_keys_ignore_visibility_and_version() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "08000130 g     F .text	00000010 .hidden helper",
+        "08000140 g     F .text	00000010 memcpy@@GLIBC_2.14",
+    ]));
+    let keys: Vec<String> = SymbolKeys::default().keys(&symbol_table).into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["helper", "memcpy"]);
+}
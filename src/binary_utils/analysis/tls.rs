@@ -0,0 +1,228 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::fmt;
+
+use crate::binary_utils::analysis::size::{SizeClass, SizeReport};
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{is_thread_local_section, SymbolTable};
+use crate::binary_utils::report::{csv_field, size_string, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// A thread local variable and where it sits in each thread's TLS block
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TlsSymbol {
+    pub name: String,
+    pub section: String,
+    /// Offset from the start of the TLS block, which objdump prints in place of an address
+    pub offset: u32,
+    pub size: u32,
+    /// Whether the size was inferred from the next symbol
+    pub size_inferred: bool,
+}
+
+/// The thread local storage of an image, and the RAM it needs for a number of threads
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TlsReport {
+    pub symbols: Vec<TlsSymbol>,
+    /// Initialized thread local data (.tdata), which is stored in the image and copied into the
+    /// block of every new thread
+    pub template_size: u32,
+    /// Zero initialized thread local data (.tbss), which only takes space in each block
+    pub zero_size: u32,
+    /// Largest alignment of the TLS sections, or 1 if there are no section headers
+    pub alignment: u32,
+    /// Size of the TLS block that each thread gets, including padding between the sections
+    pub per_thread: u32,
+    pub threads: u32,
+    /// The TLS blocks of every thread
+    pub tls_total: u32,
+    /// RAM used by the ordinary data and bss sections, which exist once no matter how many threads
+    /// are running
+    pub static_ram: u32,
+    /// Static RAM plus the TLS blocks of every thread
+    pub ram_estimate: u32,
+}
+
+impl TlsReport {
+    /// Measures the thread local storage of an image. Section headers give the exact layout of the
+    /// TLS block; without them the block is measured from the symbol offsets
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - The symbol table of the image
+    /// * 'section_table' - Optional section headers of the same image
+    /// * 'threads' - Number of threads to estimate RAM for, counting the main thread
+    pub fn new(symbol_table: &SymbolTable, section_table: Option<&SectionTable>, threads: u32) -> Self {
+        let mut symbols: Vec<TlsSymbol> = symbol_table
+            .iter()
+            .filter(|x| x.is_thread_local() && !x.is_section_symbol())
+            .map(|x| TlsSymbol {
+                name: x.name.clone(),
+                section: x.section.clone(),
+                offset: x.address,
+                size: x.alignment_or_size,
+                size_inferred: x.size_inferred,
+            })
+            .collect();
+        symbols.sort_by(|x, y| x.offset.cmp(&y.offset).then(x.name.cmp(&y.name)));
+
+        let tls_headers = section_table.filter(|x| x.iter().any(|x| x.is_thread_local()));
+        let (template_size, zero_size, alignment, per_thread) = match tls_headers {
+            Some(table) => {
+                let sections: Vec<_> = table.iter().filter(|x| x.is_thread_local()).collect();
+                let size = |contents: bool| -> u32 {
+                    sections.iter().filter(|x| x.has_contents() == contents).map(|x| x.size).sum()
+                };
+                let start = sections.iter().map(|x| x.vma).min().unwrap_or(0);
+                let end = sections.iter().map(|x| x.vma as u64 + x.size as u64).max().unwrap_or(0);
+                let alignment = sections.iter().map(|x| x.alignment).max().unwrap_or(1).max(1);
+                (size(true), size(false), alignment, (end - start as u64) as u32)
+            }
+            None => {
+                let sizes = symbol_table.section_sizes();
+                let size = |prefix: &str| -> u32 {
+                    sizes
+                        .iter()
+                        .filter(|(name, _)| name.starts_with(prefix))
+                        .map(|(_, &size)| size)
+                        .sum()
+                };
+                let end = symbols
+                    .iter()
+                    .map(|x| x.offset as u64 + x.size as u64)
+                    .max()
+                    .unwrap_or(0) as u32;
+                let (template, zero) = (size(".tdata"), size(".tbss"));
+                (template, zero, 1, end.max(template + zero))
+            }
+        };
+        let per_thread = align_up(per_thread, alignment);
+
+        let static_ram = SizeReport::new("", symbol_table, section_table)
+            .sections
+            .iter()
+            .filter(|x| matches!(x.class, SizeClass::Data | SizeClass::Bss))
+            .filter(|x| !is_thread_local_section(&x.name))
+            .map(|x| x.size)
+            .sum::<u32>();
+        let tls_total = per_thread.saturating_mul(threads);
+
+        TlsReport {
+            symbols,
+            template_size,
+            zero_size,
+            alignment,
+            per_thread,
+            threads,
+            tls_total,
+            static_ram,
+            ram_estimate: static_ram.saturating_add(tls_total),
+        }
+    }
+}
+
+/// Rounds a size up to a multiple of an alignment
+fn align_up(size: u32, alignment: u32) -> u32 {
+    size.div_ceil(alignment) * alignment
+}
+
+impl fmt::Display for TlsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:8} {:>8} {:10} Symbol", "Offset", "Size", "Section")?;
+        for symbol in &self.symbols {
+            writeln!(
+                f,
+                "{:08x} {:>8} {:10} {}",
+                symbol.offset,
+                size_string(symbol.size, symbol.size_inferred),
+                symbol.section,
+                symbol.name
+            )?;
+        }
+        writeln!(f, "TLS template: {} bytes", self.template_size)?;
+        writeln!(f, "Zero initialized TLS: {} bytes", self.zero_size)?;
+        writeln!(f, "Per thread: {} bytes (aligned to {})", self.per_thread, self.alignment)?;
+        writeln!(f, "TLS for {} threads: {} bytes", self.threads, self.tls_total)?;
+        writeln!(f, "Static RAM: {} bytes", self.static_ram)?;
+        writeln!(f, "RAM estimate: {} bytes", self.ram_estimate)
+    }
+}
+
+impl Report for TlsReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Thread Local Storage";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Thread Local Storage";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Offset";
+                                th: "Size";
+                                th: "Section";
+                                th: "Symbol";
+                            }
+                        }
+                        tbody {
+                            @ for symbol in &self.symbols {
+                                tr {
+                                    td: format!("{:08x}", symbol.offset);
+                                    td: size_string(symbol.size, symbol.size_inferred);
+                                    td: &symbol.section;
+                                    td: &symbol.name;
+                                }
+                            }
+                        }
+                    }
+                    h2 : "RAM Estimate";
+                    table(class="tg") {
+                        tbody {
+                            tr { th: "TLS template"; td: self.template_size; }
+                            tr { th: "Zero initialized TLS"; td: self.zero_size; }
+                            tr { th: format!("Per thread (aligned to {})", self.alignment); td: self.per_thread; }
+                            tr { th: format!("TLS for {} threads", self.threads); td: self.tls_total; }
+                            tr { th: "Static RAM"; td: self.static_ram; }
+                            tr { th: "RAM estimate"; td: self.ram_estimate; }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,name,section,offset,size,size_inferred\n");
+        for symbol in &self.symbols {
+            csv += &format!(
+                "symbol,{},{},{:08x},{},{}\n",
+                csv_field(&symbol.name),
+                csv_field(&symbol.section),
+                symbol.offset,
+                symbol.size,
+                symbol.size_inferred
+            );
+        }
+        let totals = [
+            ("template", self.template_size),
+            ("zero_initialized", self.zero_size),
+            ("per_thread", self.per_thread),
+            ("threads", self.threads),
+            ("tls_total", self.tls_total),
+            ("static_ram", self.static_ram),
+            ("ram_estimate", self.ram_estimate),
+        ];
+        for (name, value) in totals {
+            csv += &format!("total,{},,,{},false\n", name, value);
+        }
+        csv
+    }
+}
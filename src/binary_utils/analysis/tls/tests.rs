@@ -0,0 +1,64 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn sample_table() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "00000000 g       .tdata	00000020 name",
+        "00000020 g       .tdata	00000004 counter",
+        "00000030 l       .tbss	00000004 depth",
+        "00000040 g       .tbss	00000080 scratch",
+        "20000000 g     O .data	00000010 initialised",
+        "20000010 g     O .bss	00000100 shared",
+        "08000000 g     F .text	00000040 main",
+    ]))
+}
+
+#[test]
+fn test_tls_from_symbols() {
+    let report = TlsReport::new(&sample_table(), None, 4);
+    let names: Vec<&str> = report.symbols.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, vec!["name", "counter", "depth", "scratch"]);
+    assert_eq!(report.template_size, 0x24);
+    assert_eq!(report.zero_size, 0x84);
+    assert_eq!(report.alignment, 1);
+    assert_eq!(report.per_thread, 0xc0);
+    assert_eq!(report.tls_total, 0x300);
+    assert_eq!(report.static_ram, 0x110);
+    assert_eq!(report.ram_estimate, 0x410);
+}
+
+#[test]
+fn test_tls_from_section_headers() {
+    let mut input = lines(&[
+        " 18 .tdata        00000024  00003dd0  00003dd0  00002dd0  2**4",
+        "                  CONTENTS, ALLOC, LOAD, DATA, THREAD_LOCAL",
+        " 19 .tbss         00000090  00003e00  00003e00  00002df4  2**4",
+        "                  ALLOC, THREAD_LOCAL",
+        " 20 .data         00000014  00004000  00004000  00003000  2**3",
+        "                  CONTENTS, ALLOC, LOAD, DATA",
+    ]);
+    input.push("00000030 l       .tbss	00000004 depth".to_string());
+    let report = TlsReport::new(&SymbolTable::from_lines(&input), Some(&SectionTable::from_lines(&input)), 2);
+    assert_eq!(report.template_size, 0x24);
+    assert_eq!(report.zero_size, 0x90);
+    assert_eq!(report.alignment, 16);
+    assert_eq!(report.per_thread, 0xc0);
+    assert_eq!(report.static_ram, 0x14);
+    assert_eq!(report.ram_estimate, 0x14 + 2 * 0xc0);
+}
+
+#[test]
+fn test_no_tls() {
+    let table = SymbolTable::from_lines(&lines(&["20000000 g     O .bss	00000100 shared"]));
+    let report = TlsReport::new(&table, None, 8);
+    assert!(report.symbols.is_empty());
+    assert_eq!(report.per_thread, 0);
+    assert_eq!(report.ram_estimate, 0x100);
+}
+
+#[test]
+fn test_align_up() {
+    assert_eq!(align_up(0xb4, 16), 0xc0);
+    assert_eq!(align_up(0xc0, 16), 0xc0);
+    assert_eq!(align_up(0, 16), 0);
+}
@@ -0,0 +1,323 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::disassembly::{DisassembledFunction, Disassembly, Instruction, InstructionIndex};
+use crate::binary_utils::parser::section_contents::SectionContents;
+use crate::binary_utils::parser::symbol_table::{covered_range_size, SymbolScope, SymbolTable, SymbolTableEntry, SymbolType};
+use crate::binary_utils::report::{csv_field, size_string, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Symbols that the startup code, C runtime or debugger use, so they are always reachable
+pub const ENTRY_SYMBOLS: [&str; 6] = ["_start", "Reset_Handler", "main", "_init", "_fini", "_IO_stdin_used"];
+
+/// Sections whose contents are tables of addresses that the hardware or C runtime calls through
+pub const ROOT_SECTIONS: [&str; 9] = [
+    ".isr_vector",
+    ".vectors",
+    ".vector_table",
+    ".intvec",
+    ".preinit_array",
+    ".init_array",
+    ".fini_array",
+    ".ctors",
+    ".dtors",
+];
+
+/// Whether an unreachable symbol is code or data
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Function,
+    Object,
+}
+
+/// A function or object that can't be reached from any root
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnreachableSymbol {
+    pub name: String,
+    pub kind: Kind,
+    pub section: String,
+    pub address: u32,
+    /// Size from the symbol table, or of the disassembled instructions if the symbol has no size
+    pub size: u32,
+    /// Whether the size in the symbol table was inferred from the next symbol
+    pub size_inferred: bool,
+}
+
+/// Functions and objects that no root reaches through direct calls or data references, largest first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnreachableReport {
+    pub symbols: Vec<UnreachableSymbol>,
+    /// Bytes of unreachable code, counting aliased functions once
+    pub function_size: u32,
+    /// Bytes of unreachable data, counting aliased objects once
+    pub object_size: u32,
+}
+
+/// Options that control which symbols the search starts from
+#[derive(Debug, Default)]
+pub struct Roots<'a> {
+    /// Extra symbols to treat as reachable, e.g. handlers that are only looked up by name
+    pub symbols: Vec<&'a str>,
+    /// Treat every global symbol as reachable, e.g. when the binary is a shared library
+    pub exported: bool,
+}
+
+/// A function or object symbol along with the end of the bytes it covers
+struct Node<'a> {
+    entry: &'a SymbolTableEntry,
+    end: u64,
+}
+
+impl UnreachableReport {
+    /// Walks the direct calls and data references from every root and reports the functions and
+    /// objects that are never reached. The roots are the entry symbols, symbols placed in a vector
+    /// table, constructor or note section, the addresses stored in those sections and any extra roots.
+    /// Code that is only referenced from an unreachable function pointer table is unreachable as
+    /// well, even though the linker keeps it. Calls through registers can't be followed, so
+    /// functions that are only called that way should be given as extra roots
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - Symbol table of the binary
+    /// * 'disassembly' - Disassembly of the binary, used to follow references from code
+    /// * 'contents' - Section contents (`objdump -s`), used to follow pointers stored in data
+    /// * 'roots' - Extra roots to start from
+    pub fn new(symbol_table: &SymbolTable, disassembly: &Disassembly, contents: &SectionContents, roots: &Roots) -> Self {
+        let functions: BTreeMap<(u32, &str), &DisassembledFunction> =
+            disassembly.iter().map(|x| ((x.address, x.name.as_str()), x)).collect();
+        let mut nodes: Vec<Node> = symbol_table
+            .iter()
+            .filter(|x| x.is_allocated())
+            .filter(|x| matches!(x.flags.symbol_type, SymbolType::Function | SymbolType::Object))
+            .map(|entry| {
+                let size = match entry.alignment_or_size {
+                    0 => functions
+                        .get(&(entry.address, entry.name.as_str()))
+                        .map_or(0, |x| x.size()),
+                    size => size,
+                };
+                Node {
+                    entry,
+                    end: entry.address as u64 + size as u64,
+                }
+            })
+            .collect();
+        nodes.sort_by_key(|x| x.entry.address);
+        // The furthest end of any node up to each one, so the search for nodes that cover an
+        // address can stop once no earlier node reaches it
+        let max_end: Vec<u64> = nodes
+            .iter()
+            .scan(0, |max, x| {
+                *max = x.end.max(*max);
+                Some(*max)
+            })
+            .collect();
+        // Every node at an address, or every node that covers it if none starts there
+        let resolve = |address: u32| -> Vec<usize> {
+            let first = nodes.partition_point(|x| x.entry.address < address);
+            let last = nodes.partition_point(|x| x.entry.address <= address);
+            if last > first {
+                return (first..last).collect();
+            }
+            (0..first)
+                .rev()
+                .take_while(|&i| max_end[i] > address as u64)
+                .filter(|&i| (address as u64) < nodes[i].end)
+                .collect()
+        };
+        let instructions: BTreeMap<&str, InstructionIndex> = disassembly
+            .iter()
+            .map(|x| (x.section.as_str(), disassembly.index_section(&x.section)))
+            .collect();
+
+        let mut pending: Vec<usize> = Vec::new();
+        for entry in symbol_table.iter().filter(|x| x.is_allocated()) {
+            if ENTRY_SYMBOLS.contains(&entry.name.as_str()) || roots.symbols.contains(&entry.name.as_str()) {
+                pending.extend(resolve(entry.address));
+            }
+        }
+        for section in ROOT_SECTIONS.iter() {
+            for address in contents.pointers_in_section(section) {
+                pending.extend(resolve(address));
+            }
+        }
+        // Notes are read by the loader and tools rather than the program itself
+        pending.extend((0..nodes.len()).filter(|&i| {
+            let entry = nodes[i].entry;
+            ROOT_SECTIONS.contains(&entry.section.as_str())
+                || entry.section.starts_with(".note")
+                || (roots.exported && entry.flags.scope == SymbolScope::Global)
+        }));
+
+        let mut reached = vec![false; nodes.len()];
+        while let Some(i) = pending.pop() {
+            if reached[i] {
+                continue;
+            }
+            reached[i] = true;
+            let node = &nodes[i];
+            let targets = match node.entry.flags.symbol_type {
+                SymbolType::Function => instructions
+                    .get(node.entry.section.as_str())
+                    .map_or(&[][..], |x| x.in_range(node.entry.address, node.end))
+                    .iter()
+                    .flat_map(|x| referenced_addresses(x))
+                    .collect(),
+                _ => contents.pointers_in(node.entry.address, node.end),
+            };
+            for address in targets {
+                pending.extend(resolve(address).into_iter().filter(|&x| !reached[x]));
+            }
+        }
+
+        let mut symbols: Vec<UnreachableSymbol> = nodes
+            .iter()
+            .zip(reached)
+            .filter(|(x, reached)| !reached && x.end > x.entry.address as u64)
+            .map(|(node, _)| UnreachableSymbol {
+                name: node.entry.name.clone(),
+                kind: match node.entry.flags.symbol_type {
+                    SymbolType::Function => Kind::Function,
+                    _ => Kind::Object,
+                },
+                section: node.entry.section.clone(),
+                address: node.entry.address,
+                size: (node.end - node.entry.address as u64) as u32,
+                size_inferred: node.entry.size_inferred,
+            })
+            .collect();
+        symbols.sort_by(|x, y| y.size.cmp(&x.size).then(x.name.cmp(&y.name)));
+        let covered = |kind: Kind| {
+            let symbols = symbols.iter().filter(|x| x.kind == kind);
+            covered_range_size(symbols.map(|x| (x.section.as_str(), x.address, x.size)))
+        };
+
+        UnreachableReport {
+            function_size: covered(Kind::Function),
+            object_size: covered(Kind::Object),
+            symbols,
+        }
+    }
+
+    /// Gets the bytes of code and data that could be removed
+    pub fn total_size(&self) -> u32 {
+        self.function_size + self.object_size
+    }
+}
+
+/// Gets the addresses an instruction refers to, including the addresses of functions and objects
+/// loaded as immediate values and the values of literal pool words
+fn referenced_addresses(instruction: &Instruction) -> Vec<u32> {
+    let mut addresses: Vec<u32> = instruction.references().iter().map(|(x, _)| *x).collect();
+    for operand in instruction.operands.split([',', ' ', '[', ']', '{', '}']) {
+        let value = match operand.strip_prefix('$').or_else(|| operand.strip_prefix('#')) {
+            Some(value) => value,
+            None if instruction.is_data() => operand,
+            None => continue,
+        };
+        if let Some(hex) = value.strip_prefix("0x") {
+            addresses.extend(u32::from_str_radix(hex, 16).ok());
+        }
+    }
+    addresses
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Function => write!(f, "function"),
+            Kind::Object => write!(f, "object"),
+        }
+    }
+}
+
+impl fmt::Display for UnreachableReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .symbols
+            .iter()
+            .map(|x| x.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Symbol".len());
+        writeln!(f, "{:w$} {:8} {:10} {:8} {:>8}", "Symbol", "Kind", "Section", "Address", "Size", w = width)?;
+        for symbol in &self.symbols {
+            writeln!(
+                f,
+                "{:w$} {:8} {:10} {:08x} {}",
+                symbol.name,
+                symbol.kind.to_string(),
+                symbol.section,
+                symbol.address,
+                size_string(format!("{:>8}", symbol.size), symbol.size_inferred),
+                w = width
+            )?;
+        }
+        writeln!(f, "Unreachable code: {} bytes", self.function_size)?;
+        writeln!(f, "Unreachable data: {} bytes", self.object_size)?;
+        writeln!(f, "Total: {} bytes", self.total_size())
+    }
+}
+
+impl Report for UnreachableReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Unreachable Symbols";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Unreachable Symbols";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Symbol";
+                                th: "Kind";
+                                th: "Section";
+                                th: "Address";
+                                th: "Size";
+                            }
+                        }
+                        tbody {
+                            @ for symbol in &self.symbols {
+                                tr {
+                                    td: &symbol.name;
+                                    td: symbol.kind.to_string();
+                                    td: &symbol.section;
+                                    td: format!("{:08x}", symbol.address);
+                                    td: size_string(symbol.size, symbol.size_inferred);
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Unreachable code: {} bytes", self.function_size);
+                    h2 : format!("Unreachable data: {} bytes", self.object_size);
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("symbol,kind,section,address,size,size_inferred\n");
+        for symbol in &self.symbols {
+            csv += &format!(
+                "{},{},{},{:08x},{},{}\n",
+                csv_field(&symbol.name),
+                symbol.kind,
+                csv_field(&symbol.section),
+                symbol.address,
+                symbol.size,
+                symbol.size_inferred
+            );
+        }
+        csv
+    }
+}
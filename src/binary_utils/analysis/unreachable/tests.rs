@@ -0,0 +1,130 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn firmware() -> Vec<String> {
+    lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "08000000 g     O .isr_vector	00000010 g_pfnVectors",
+        "08000010 g     F .text	00000008 Reset_Handler",
+        "08000018 g     F .text	00000008 main",
+        "08000020 l     F .text	00000004 helper",
+        "08000024 g     F .text	00000004 on_event",
+        "08000028 g     F .text	00000008 SysTick_Handler",
+        "08000030 g     O .rodata	00000004 callbacks",
+        "08000034 g     O .rodata	00000004 orphan_callbacks",
+        "08000038 g     F .text	00000004 orphan",
+        "20000000 g     O .bss	00000004 ticks",
+        "20000004 g     O .bss	00000004 scratch",
+        "",
+        "Contents of section .isr_vector:",
+        " 8000000 00500020 11000008 00000000 29000008  .P. ........)...",
+        "Contents of section .rodata:",
+        " 8000030 25000008 39000008                    %...9...        ",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "08000010 <Reset_Handler>:",
+        " 8000010:	f000 f802 	bl	8000018 <main>",
+        " 8000014:	e7fe      	b.n	8000014 <Reset_Handler+0x4>",
+        " 8000016:	bf00      	nop",
+        "",
+        "08000018 <main>:",
+        " 8000018:	4801      	ldr	r0, [pc, #4]	@ (8000020 <helper>)",
+        " 800001a:	f000 f801 	bl	8000020 <helper>",
+        " 800001e:	bd10      	pop	{r4, pc}",
+        "",
+        "08000020 <helper>:",
+        " 8000020:	4770      	bx	lr",
+        " 8000022:	bf00      	nop",
+        "",
+        "08000024 <on_event>:",
+        " 8000024:	4770      	bx	lr",
+        " 8000026:	bf00      	nop",
+        "",
+        "08000028 <SysTick_Handler>:",
+        " 8000028:	4b00      	ldr	r3, [pc, #0]	@ (800002c <SysTick_Handler+0x4>)",
+        " 800002a:	4770      	bx	lr",
+        " 800002c:	20000004 	.word	0x20000004",
+        "",
+        "08000038 <orphan>:",
+        " 8000038:	4770      	bx	lr",
+        " 800003a:	bf00      	nop",
+    ])
+}
+
+fn names(report: &UnreachableReport) -> Vec<&str> {
+    let mut names: Vec<&str> = report.symbols.iter().map(|x| x.name.as_str()).collect();
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn test_unreachable_from_vector_table() {
+    let lines = firmware();
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let disassembly = Disassembly::from_lines(&lines);
+    let contents = SectionContents::from_lines(&lines);
+    let report = UnreachableReport::new(&symbol_table, &disassembly, &contents, &Roots::default());
+
+    // SysTick_Handler is only reached through the vector table, where its address has the Thumb
+    // bit set, and reaches scratch through the address in its literal pool
+    assert_eq!(names(&report), vec!["callbacks", "on_event", "orphan", "orphan_callbacks", "ticks"]);
+    assert_eq!(report.function_size, 8);
+    assert_eq!(report.object_size, 12);
+    assert_eq!(report.total_size(), 20);
+}
+
+#[test]
+fn test_unreachable_with_extra_roots() {
+    let lines = firmware();
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let disassembly = Disassembly::from_lines(&lines);
+    let contents = SectionContents::from_lines(&lines);
+
+    // The callback table keeps on_event alive, but nothing reaches the orphan table or function
+    let roots = Roots {
+        symbols: vec!["callbacks"],
+        exported: false,
+    };
+    let report = UnreachableReport::new(&symbol_table, &disassembly, &contents, &roots);
+    assert_eq!(names(&report), vec!["orphan", "orphan_callbacks", "ticks"]);
+
+    let roots = Roots {
+        symbols: Vec::new(),
+        exported: true,
+    };
+    let report = UnreachableReport::new(&symbol_table, &disassembly, &contents, &roots);
+    assert!(report.symbols.is_empty());
+}
+
+#[test]
+fn test_unreachable_symbol_ending_at_the_top_of_memory() {
+    let lines = lines(&["SYMBOL TABLE:", "fffffff0 g     O .data	00000010 vectors"]);
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let report = UnreachableReport::new(
+        &symbol_table,
+        &Disassembly::from_lines(&lines),
+        &SectionContents::from_lines(&lines),
+        &Roots::default(),
+    );
+    assert_eq!(names(&report), vec!["vectors"]);
+    assert_eq!(report.object_size, 0x10);
+}
+
+#[test]
+fn test_unreachable_report_formats() {
+    let lines = firmware();
+    let report = UnreachableReport::new(
+        &SymbolTable::from_lines(&lines),
+        &Disassembly::from_lines(&lines),
+        &SectionContents::from_lines(&lines),
+        &Roots::default(),
+    );
+    assert_eq!(report.symbols[0].name, "callbacks");
+    assert!(report.to_string().contains("Unreachable code: 8 bytes"));
+    assert!(report.to_csv().contains("orphan,function,.text,08000038,4,false\n"));
+    assert!(report.to_html().contains("<td>orphan_callbacks</td>"));
+    assert!(report.to_json().contains("\"kind\": \"object\""));
+}
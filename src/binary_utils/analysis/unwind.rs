@@ -0,0 +1,511 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::eh_frame::{EhFrame, FrameEntry};
+use crate::binary_utils::parser::section_contents::SectionContents;
+use crate::binary_utils::parser::section_table::SectionTable;
+use crate::binary_utils::parser::symbol_table::{SymbolTable, SymbolTableEntry, SymbolType};
+use crate::binary_utils::report::{csv_field, percent, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// Sections that only exist to unwind the stack for exceptions, panics and backtraces
+pub const UNWIND_SECTIONS: [&str; 5] = [".eh_frame", ".eh_frame_hdr", ".gcc_except_table", ".ARM.exidx", ".ARM.extab"];
+
+/// Size of each entry in the binary search table of `.eh_frame_hdr`, and in `.ARM.exidx`
+const TABLE_ENTRY_SIZE: u32 = 8;
+
+/// Marks an `.ARM.exidx` function that can't be unwound, in place of its unwind instructions
+const EXIDX_CANTUNWIND: u64 = 1;
+
+/// The unwind bytes that describe one function
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct FunctionUnwind {
+    pub function: String,
+    pub address: u32,
+    /// Size of the function's code
+    pub code_size: u32,
+    /// Bytes of the function's FDE in `.eh_frame`
+    pub fde: u32,
+    /// Bytes of the function's entry in the `.eh_frame_hdr` search table
+    pub search_table: u32,
+    /// Bytes of the function's language specific data area (LSDA) in `.gcc_except_table`, which
+    /// holds its call site, landing pad and catch tables
+    pub lsda: u32,
+    /// Bytes of the function's `.ARM.exidx` index entry
+    pub exidx: u32,
+    /// Bytes of the function's `.ARM.extab` unwind instructions and LSDA
+    pub extab: u32,
+    pub total: u32,
+    /// Unwind bytes as a percentage of the code size
+    pub percent_of_code: f64,
+}
+
+/// The size of an unwind section and how much of it is attributed to functions
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnwindSection {
+    pub section: String,
+    /// Size of the section, if it is known from the section headers or contents
+    pub size: Option<u32>,
+    pub attributed: u32,
+    /// Bytes that are shared or not attributed, like CIEs, headers and padding
+    pub shared: u32,
+}
+
+/// Unwind table bytes attributed to the functions they describe, largest first
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnwindReport {
+    pub functions: Vec<FunctionUnwind>,
+    pub sections: Vec<UnwindSection>,
+    /// Size of every unwind section, or of the attributed bytes if the section sizes are unknown
+    pub total_size: u32,
+}
+
+impl UnwindReport {
+    /// Attributes the unwind tables to functions. FDEs come from the `.eh_frame` entries printed by
+    /// `objdump --dwarf=frames`, and LSDAs from the pointers in their augmentation data. The ARM
+    /// index and table entries are read from the `.ARM.exidx` section contents. An LSDA or ARM table
+    /// entry is taken to run up to the next one, or to the end of its section
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - Symbol table used to name the functions
+    /// * 'section_table' - Section headers, needed to find the LSDAs and the section sizes
+    /// * 'eh_frame' - The `.eh_frame` entries
+    /// * 'contents' - Section contents, needed for the ARM unwind tables
+    pub fn new(
+        symbol_table: &SymbolTable,
+        section_table: &SectionTable,
+        eh_frame: &EhFrame,
+        contents: &SectionContents,
+    ) -> Self {
+        let section_bounds = |name: &str| -> Option<(u32, u64)> {
+            match section_table.find(name) {
+                Some(x) => Some((x.vma, x.vma as u64 + x.size as u64)),
+                None => contents.find(name).map(|x| (x.address, x.address as u64 + x.bytes.len() as u64)),
+            }
+        };
+
+        let index = SymbolIndex::new(symbol_table);
+        let mut functions: BTreeMap<u32, FunctionUnwind> = BTreeMap::new();
+        let has_search_table = section_bounds(".eh_frame_hdr").is_some();
+        let eh_frame_start = section_bounds(".eh_frame").map(|(start, _)| start);
+        let mut lsdas = Vec::new();
+        for fde in eh_frame.fdes() {
+            let function = functions
+                .entry(fde.pc_begin)
+                .or_insert_with(|| new_function(&index, section_table, fde.pc_begin, fde.pc_end - fde.pc_begin));
+            function.fde += fde.size;
+            if has_search_table {
+                function.search_table += TABLE_ENTRY_SIZE;
+            }
+            let cie = fde.cie.and_then(|x| eh_frame.find_cie(x));
+            if let (Some(cie), Some(start)) = (cie, eh_frame_start) {
+                if let Some(lsda) = lsda_address(fde, cie, start, contents) {
+                    lsdas.push((lsda, fde.pc_begin));
+                }
+            }
+        }
+        for (address, size) in table_ranges(lsdas, section_bounds(".gcc_except_table")) {
+            if let Some(function) = functions.get_mut(&address) {
+                function.lsda += size;
+            }
+        }
+
+        let exidx = contents.words_in_section(".ARM.exidx");
+        let mut extab = Vec::new();
+        for pair in exidx.chunks_exact(2) {
+            let ((entry, first), (_, second)) = (pair[0], pair[1]);
+            let address = prel31(entry, first);
+            let function = functions
+                .entry(address)
+                .or_insert_with(|| new_function(&index, section_table, address, 0));
+            function.exidx += TABLE_ENTRY_SIZE;
+            // Bit 31 marks unwind instructions stored inline in the index entry
+            if second != EXIDX_CANTUNWIND && second & 0x8000_0000 == 0 {
+                extab.push((prel31(entry + 4, second), address));
+            }
+        }
+        for (address, size) in table_ranges(extab, section_bounds(".ARM.extab")) {
+            if let Some(function) = functions.get_mut(&address) {
+                function.extab += size;
+            }
+        }
+
+        let mut functions: Vec<FunctionUnwind> = functions
+            .into_values()
+            .map(|mut x| {
+                x.total = x.fde + x.search_table + x.lsda + x.exidx + x.extab;
+                x.percent_of_code = percent(x.total, x.code_size);
+                x
+            })
+            .collect();
+        functions.sort_by(|x, y| y.total.cmp(&x.total).then(x.address.cmp(&y.address)));
+
+        let attributed = |section: &str| -> u32 {
+            functions
+                .iter()
+                .map(|x| match section {
+                    ".eh_frame" => x.fde,
+                    ".eh_frame_hdr" => x.search_table,
+                    ".gcc_except_table" => x.lsda,
+                    ".ARM.exidx" => x.exidx,
+                    _ => x.extab,
+                })
+                .sum()
+        };
+        let sections: Vec<UnwindSection> = UNWIND_SECTIONS
+            .iter()
+            .filter_map(|&name| {
+                let size = section_bounds(name).map(|(start, end)| (end - start as u64) as u32);
+                let attributed = attributed(name);
+                if size.is_none() && attributed == 0 {
+                    return None;
+                }
+                Some(UnwindSection {
+                    section: name.to_string(),
+                    size,
+                    attributed,
+                    shared: size.map_or(0, |x| x.saturating_sub(attributed)),
+                })
+            })
+            .collect();
+        let total_size = sections.iter().map(|x| x.size.unwrap_or(x.attributed)).sum();
+
+        UnwindReport {
+            functions,
+            sections,
+            total_size,
+        }
+    }
+
+    /// Gets the unwind bytes that were attributed to functions
+    pub fn attributed(&self) -> u32 {
+        self.functions.iter().map(|x| x.total).sum()
+    }
+
+    /// Finds a function by name
+    pub fn find(&self, function: &str) -> Option<&FunctionUnwind> {
+        self.functions.iter().find(|x| x.function == function)
+    }
+}
+
+/// The symbols that unwind entries can be named after, sorted by address so each entry is
+/// resolved without scanning the whole symbol table
+struct SymbolIndex<'a> {
+    /// Placed symbols, sorted by address with the Thumb bit cleared
+    symbols: Vec<&'a SymbolTableEntry>,
+    /// Function symbols, sorted by address
+    functions: Vec<&'a SymbolTableEntry>,
+    /// Highest end address of the functions up to and including each index
+    max_end: Vec<u64>,
+}
+
+impl<'a> SymbolIndex<'a> {
+    fn new(symbol_table: &'a SymbolTable) -> Self {
+        let mut symbols: Vec<&SymbolTableEntry> = symbol_table
+            .iter()
+            .filter(|x| x.is_allocated() && !x.is_section_symbol() && x.flags.symbol_type != SymbolType::File)
+            .collect();
+        symbols.sort_by_key(|x| x.address & !1);
+        let mut functions: Vec<&SymbolTableEntry> = symbols
+            .iter()
+            .copied()
+            .filter(|x| x.flags.symbol_type == SymbolType::Function)
+            .collect();
+        functions.sort_by_key(|x| x.address);
+        let max_end = functions
+            .iter()
+            .scan(0, |max, x| {
+                *max = (*max).max(x.address as u64 + x.alignment_or_size as u64);
+                Some(*max)
+            })
+            .collect();
+        SymbolIndex {
+            symbols,
+            functions,
+            max_end,
+        }
+    }
+
+    /// Finds the symbol at an address, preferring functions and then the largest symbol, or else
+    /// the function the address is inside of
+    fn find(&self, address: u32) -> Option<&'a SymbolTableEntry> {
+        let start = self.symbols.partition_point(|x| x.address & !1 < address & !1);
+        let at_address = self.symbols[start..]
+            .iter()
+            .take_while(|x| x.address & !1 == address & !1)
+            .max_by_key(|x| (x.flags.symbol_type == SymbolType::Function, x.alignment_or_size));
+        if let Some(symbol) = at_address {
+            return Some(symbol);
+        }
+        let end = self.functions.partition_point(|x| x.address < address);
+        (0..end)
+            .rev()
+            .take_while(|&i| self.max_end[i] > address as u64)
+            .map(|i| self.functions[i])
+            .find(|x| (address as u64) < x.address as u64 + x.alignment_or_size as u64)
+    }
+}
+
+/// Creates an empty entry for the function at an address, named after its symbol. Code without a
+/// symbol, like the PLT, is named after its section and offset
+fn new_function(index: &SymbolIndex, section_table: &SectionTable, address: u32, range: u32) -> FunctionUnwind {
+    let symbol = index.find(address);
+    let function = match symbol {
+        Some(x) if x.address & !1 == address & !1 => x.name.clone(),
+        Some(x) => format!("{}+0x{:x}", x.name, address - x.address),
+        None => match section_table.iter().find(|x| x.contains(address)) {
+            Some(section) if section.vma == address => section.name.clone(),
+            Some(section) => format!("{}+0x{:x}", section.name, address - section.vma),
+            None => format!("{:08x}", address),
+        },
+    };
+    let code_size = symbol
+        .filter(|x| x.address & !1 == address & !1 && x.alignment_or_size > 0)
+        .map_or(range, |x| x.alignment_or_size);
+    FunctionUnwind {
+        function,
+        address,
+        code_size,
+        ..Default::default()
+    }
+}
+
+/// Sizes the LSDAs or ARM table entries that functions point to. Each one runs up to the next
+/// address that any function points to, and the last one runs to the end of its section
+///
+/// # Arguments
+/// * 'starts' - Start address of each table entry along with the function that points to it
+/// * 'bounds' - Address range of the section holding the entries
+///
+/// Returns the function and size of each entry
+fn table_ranges(mut starts: Vec<(u32, u32)>, bounds: Option<(u32, u64)>) -> Vec<(u32, u32)> {
+    let (section_start, section_end) = match bounds {
+        Some(x) => x,
+        None => return Vec::new(),
+    };
+    starts.retain(|&(x, _)| x >= section_start && (x as u64) < section_end);
+    starts.sort_unstable();
+    // Functions that share an entry are charged for it once, by the first one
+    starts.dedup_by_key(|x| x.0);
+    let ends: Vec<u64> = starts.iter().skip(1).map(|x| x.0 as u64).chain(std::iter::once(section_end)).collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(|((start, function), end)| (function, (end - start as u64) as u32))
+        .collect()
+}
+
+/// Resolves a 31 bit offset relative to the address it was read from, as used by `.ARM.exidx`
+fn prel31(address: u32, value: u64) -> u32 {
+    let offset = ((value as u32) << 1) as i32 >> 1;
+    address.wrapping_add(offset as u32)
+}
+
+/// Gets the number of bytes a DWARF pointer encoding takes, for the fixed size encodings
+fn encoded_size(encoding: u8, pointer_size: u32) -> Option<u32> {
+    match encoding & 0x0f {
+        0x00 => Some(pointer_size),
+        0x02 | 0x0a => Some(2),
+        0x03 | 0x0b => Some(4),
+        0x04 | 0x0c => Some(8),
+        _ => None,
+    }
+}
+
+/// Finds the LSDA an FDE points to. The CIE's augmentation data gives the encoding of the pointer,
+/// which is stored in the FDE's augmentation data and is usually relative to its own address
+///
+/// # Arguments
+/// * 'fde' - The FDE of the function
+/// * 'cie' - The CIE that the FDE uses
+/// * 'eh_frame' - Address of the `.eh_frame` section
+/// * 'contents' - Section contents, used for the pointer size and byte order
+fn lsda_address(fde: &FrameEntry, cie: &FrameEntry, eh_frame: u32, contents: &SectionContents) -> Option<u32> {
+    let pointer_size = contents.pointer_size() as u32;
+    let (mut lsda_encoding, mut fde_encoding) = (None, 0);
+    let mut data = cie.augmentation_data.iter();
+    for c in cie.augmentation.strip_prefix('z')?.chars() {
+        match c {
+            'P' => {
+                let encoding = *data.next()?;
+                for _ in 0..encoded_size(encoding, pointer_size)? {
+                    data.next()?;
+                }
+            }
+            'L' => lsda_encoding = Some(*data.next()?),
+            'R' => fde_encoding = *data.next()?,
+            'S' | 'B' | 'G' => (),
+            _ => return None,
+        }
+    }
+    let encoding = lsda_encoding.filter(|&x| x != 0xff)?;
+    let size = encoded_size(encoding, pointer_size)? as usize;
+    let bytes = fde.augmentation_data.get(..size)?;
+    let value = if contents.is_big_endian() {
+        bytes.iter().fold(0u64, |value, &x| (value << 8) | x as u64)
+    } else {
+        bytes.iter().rev().fold(0u64, |value, &x| (value << 8) | x as u64)
+    };
+    // Sign extend the signed encodings
+    let value = match encoding & 0x08 {
+        0 => value,
+        _ => ((value << (64 - size * 8)) as i64 >> (64 - size * 8)) as u64,
+    };
+    let base = match encoding & 0x70 {
+        0x00 => 0,
+        // The pointer follows the length, CIE pointer, code range and augmentation data length
+        0x10 => eh_frame as u64 + fde.offset as u64 + 8 + 2 * encoded_size(fde_encoding, pointer_size)? as u64 + 1,
+        _ => return None,
+    };
+    let address = base.wrapping_add(value) & 0xffff_ffff;
+    Some(address as u32)
+}
+
+impl fmt::Display for UnwindReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .functions
+            .iter()
+            .map(|x| x.function.len())
+            .max()
+            .unwrap_or(0)
+            .max("Function".len());
+        writeln!(
+            f,
+            "{:w$} {:>8} {:>6} {:>6} {:>6} {:>6} {:>6} {:>8} {:>7}",
+            "Function",
+            "Code",
+            "FDE",
+            "Table",
+            "LSDA",
+            "EXIDX",
+            "EXTAB",
+            "Unwind",
+            "% Code",
+            w = width
+        )?;
+        for function in &self.functions {
+            writeln!(
+                f,
+                "{:w$} {:>8} {:>6} {:>6} {:>6} {:>6} {:>6} {:>8} {:>6.1}%",
+                function.function,
+                function.code_size,
+                function.fde,
+                function.search_table,
+                function.lsda,
+                function.exidx,
+                function.extab,
+                function.total,
+                function.percent_of_code,
+                w = width
+            )?;
+        }
+        writeln!(f, "Sections:")?;
+        for section in &self.sections {
+            writeln!(
+                f,
+                "  {:18} {:>8} bytes, {} attributed, {} shared",
+                section.section,
+                section.size.map(|x| x.to_string()).unwrap_or_else(|| "-".to_string()),
+                section.attributed,
+                section.shared
+            )?;
+        }
+        writeln!(f, "Total unwind size: {} bytes ({} attributed)", self.total_size, self.attributed())
+    }
+}
+
+impl Report for UnwindReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Unwind Table Overhead";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Unwind Table Overhead";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Function";
+                                th: "Code";
+                                th: "FDE";
+                                th: "Search Table";
+                                th: "LSDA";
+                                th: "EXIDX";
+                                th: "EXTAB";
+                                th: "Unwind";
+                                th: "% Code";
+                            }
+                        }
+                        tbody {
+                            @ for function in &self.functions {
+                                tr {
+                                    td: &function.function;
+                                    td: function.code_size;
+                                    td: function.fde;
+                                    td: function.search_table;
+                                    td: function.lsda;
+                                    td: function.exidx;
+                                    td: function.extab;
+                                    td: function.total;
+                                    td: format!("{:.1}", function.percent_of_code);
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Sections";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Section";
+                                th: "Size";
+                                th: "Attributed";
+                                th: "Shared";
+                            }
+                        }
+                        tbody {
+                            @ for section in &self.sections {
+                                tr {
+                                    td: &section.section;
+                                    td: section.size.map(|x| x.to_string()).unwrap_or_else(|| "-".to_string());
+                                    td: section.attributed;
+                                    td: section.shared;
+                                }
+                            }
+                        }
+                    }
+                    h2 : format!("Total unwind size: {} bytes ({} attributed)", self.total_size, self.attributed());
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("function,address,code_size,fde,search_table,lsda,exidx,extab,total,percent_of_code\n");
+        for function in &self.functions {
+            csv += &format!(
+                "{},{:08x},{},{},{},{},{},{},{},{:.2}\n",
+                csv_field(&function.function),
+                function.address,
+                function.code_size,
+                function.fde,
+                function.search_table,
+                function.lsda,
+                function.exidx,
+                function.extab,
+                function.total,
+                function.percent_of_code
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn arm_input() -> Vec<String> {
+    lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "00000100 g     F .text	00000020 no_unwind",
+        "00000201 g     F .text	00000010 compact",
+        "00000300 g     F .text	00000040 with_extab",
+        "00000400 g     F .text	00000040 catcher",
+        "Contents of section .ARM.exidx:",
+        " 8000 0081ff7f 01000000 f881ff7f b0b0b080  ................",
+        " 8010 f082ff7f ec0f0000 e883ff7f f00f0000  ................",
+        "Contents of section .ARM.extab:",
+        " 9000 00000000 00000000 00000000 00000000  ................",
+        " 9010 00000000 00000000 00000000           ............",
+    ])
+}
+
+#[test]
+fn test_arm_exception_tables() {
+    let input = arm_input();
+    let report = UnwindReport::new(
+        &SymbolTable::from_lines(&input),
+        &SectionTable::new(),
+        &EhFrame::new(),
+        &SectionContents::from_lines(&input),
+    );
+    let functions: Vec<(&str, u32, u32)> = report
+        .functions
+        .iter()
+        .map(|x| (x.function.as_str(), x.exidx, x.extab))
+        .collect();
+    assert_eq!(
+        functions,
+        vec![("catcher", 8, 16), ("with_extab", 8, 12), ("no_unwind", 8, 0), ("compact", 8, 0)]
+    );
+    assert_eq!(report.total_size, 32 + 28);
+    assert_eq!(report.attributed(), 60);
+}
+
+#[test]
+fn test_lsda_address() {
+    let cie = FrameEntry {
+        offset: 0x88,
+        size: 0x20,
+        cie: None,
+        pc_begin: 0,
+        pc_end: 0,
+        augmentation: "zPLR".to_string(),
+        augmentation_data: vec![0x9b, 0x65, 0x1f, 0x00, 0x00, 0x1b, 0x1b],
+    };
+    let fde = FrameEntry {
+        offset: 0xa8,
+        size: 0x24,
+        cie: Some(0x88),
+        pc_begin: 0x1199,
+        pc_end: 0x11ed,
+        augmentation: String::new(),
+        augmentation_data: vec![0x63, 0x00, 0x00, 0x00],
+    };
+    let contents = SectionContents::from_lines(&lines(&["ex:     file format elf64-x86-64"]));
+    assert_eq!(lsda_address(&fde, &cie, 0x2050, &contents), Some(0x216c));
+
+    let no_lsda = FrameEntry {
+        augmentation: "zR".to_string(),
+        augmentation_data: vec![0x1b],
+        ..cie
+    };
+    assert_eq!(lsda_address(&fde, &no_lsda, 0x2050, &contents), None);
+}
+
+#[test]
+fn test_table_ranges() {
+    let ranges = table_ranges(vec![(0x210, 2), (0x200, 1), (0x210, 3)], Some((0x200, 0x220)));
+    assert_eq!(ranges, vec![(1, 0x10), (2, 0x10)]);
+    assert!(table_ranges(vec![(0x200, 1)], None).is_empty());
+    let ranges = table_ranges(vec![(0xffff_fff0, 1)], Some((0xffff_ff00, 0x1_0000_0000)));
+    assert_eq!(ranges, vec![(1, 0x10)]);
+}
+
+#[test]
+fn test_prel31() {
+    assert_eq!(prel31(0x8000, 0x7fff8100), 0x100);
+    assert_eq!(prel31(0x8014, 0x00000fec), 0x9000);
+}
+
+#[test]
+fn test_symbol_index() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "00000100 g     O .text	00000004 table",
+        "00000100 g     F .text	00000020 start",
+        "00000201 g     F .text	00000040 thumb",
+        "00000220 l       .text	00000000 label",
+        "00000300 l    df *ABS*	00000000 file.c",
+    ]));
+    let index = SymbolIndex::new(&symbol_table);
+    assert_eq!(index.find(0x100).map(|x| x.name.as_str()), Some("start"));
+    assert_eq!(index.find(0x200).map(|x| x.name.as_str()), Some("thumb"));
+    assert_eq!(index.find(0x210).map(|x| x.name.as_str()), Some("thumb"));
+    assert_eq!(index.find(0x220).map(|x| x.name.as_str()), Some("label"));
+    assert!(index.find(0x241).is_none());
+    assert_eq!(new_function(&index, &SectionTable::new(), 0x210, 0).function, "thumb+0xf");
+}
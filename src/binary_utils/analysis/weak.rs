@@ -0,0 +1,306 @@
+use serde::Serialize;
+
+use horrorshow::html;
+use horrorshow::helper::doctype;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::binary_utils::parser::section_table::parse_file_format;
+use crate::binary_utils::parser::symbol_table::{
+    parse_symbol_table_entry, SymbolScope, SymbolTable, SymbolTableEntry, SymbolWeakness,
+};
+use crate::binary_utils::report::{csv_field, Report, TABLE_STYLE};
+
+#[cfg(test)]
+mod tests;
+
+/// The symbol table of one object file that was linked into the image
+pub struct ObjectSymbols {
+    /// Name of the object, e.g. "main.o" or "libc.a(printf.o)" for archive members
+    pub object: String,
+    pub symbol_table: SymbolTable,
+}
+
+/// A weak definition that is still in the final image, so nothing overrode it
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WeakDefinition {
+    pub name: String,
+    pub section: String,
+    pub address: u32,
+    pub size: u32,
+    /// Other symbols at the same address, e.g. the `Default_Handler` an interrupt handler defaults to
+    pub aliases: Vec<String>,
+}
+
+/// A weak reference that nothing defined, so it resolved to address 0
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnresolvedReference {
+    pub name: String,
+    pub address: u32,
+}
+
+/// A symbol with both weak and strong definitions in the linked objects
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Override {
+    pub name: String,
+    /// Object whose strong definition is in the image, or none if the weak definition was kept
+    pub winner: Option<String>,
+    /// Objects with a strong definition of the symbol
+    pub strong: Vec<String>,
+    /// Objects with a weak definition of the symbol
+    pub weak: Vec<String>,
+}
+
+/// How the weak symbols of an image were resolved
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WeakReport {
+    pub definitions: Vec<WeakDefinition>,
+    pub unresolved: Vec<UnresolvedReference>,
+    pub overrides: Vec<Override>,
+}
+
+impl ObjectSymbols {
+    /// Splits the output of `objdump -t` run on several objects or archives into one symbol table
+    /// per object. Each object starts at the header line objdump prints before its symbols, and
+    /// members of an archive are named after the archive as well. objdump doesn't mark the end of
+    /// an archive, so archives should be listed after the plain objects
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Vec<Self> {
+        let mut objects: Vec<ObjectSymbols> = Vec::new();
+        let mut archive: Option<&str> = None;
+        for line in lines {
+            if let Some(name) = line.strip_prefix("In archive ") {
+                archive = Some(name.trim_end().trim_end_matches(':'));
+            } else if let Ok((_, (name, _))) = parse_file_format(line) {
+                objects.push(ObjectSymbols {
+                    object: match archive {
+                        Some(archive) => format!("{}({})", archive, name),
+                        None => name.to_string(),
+                    },
+                    symbol_table: SymbolTable::new(),
+                });
+            } else if let (Some(object), Ok((_, entry))) = (objects.last_mut(), parse_symbol_table_entry(line)) {
+                object.symbol_table.push(entry);
+            }
+        }
+        objects
+    }
+}
+
+impl WeakReport {
+    /// Finds the weak definitions left in the image and the weak references that resolved to 0.
+    /// When the symbol tables of the linked objects are given, symbols that have both weak and
+    /// strong definitions are listed along with the object whose definition won
+    ///
+    /// # Arguments
+    /// * 'symbol_table' - Symbol table of the final image
+    /// * 'objects' - Symbol tables of the objects linked into the image, if available
+    pub fn new(symbol_table: &SymbolTable, objects: &[ObjectSymbols]) -> Self {
+        let weak = |x: &&SymbolTableEntry| x.flags.weakness == SymbolWeakness::Weak;
+
+        // The names of the symbols at each section and address, to find the aliases of a definition
+        let mut by_address: BTreeMap<(&str, u32), Vec<&str>> = BTreeMap::new();
+        for entry in symbol_table.iter().filter(|x| !x.is_section_symbol()) {
+            by_address.entry((&entry.section, entry.address)).or_default().push(&entry.name);
+        }
+        let mut definitions: Vec<WeakDefinition> = symbol_table
+            .iter()
+            .filter(weak)
+            .filter(|x| x.is_allocated() && !(x.address == 0 && x.alignment_or_size == 0))
+            .map(|entry| WeakDefinition {
+                name: entry.name.clone(),
+                section: entry.section.clone(),
+                address: entry.address,
+                size: entry.alignment_or_size,
+                aliases: by_address
+                    .get(&(entry.section.as_str(), entry.address))
+                    .into_iter()
+                    .flatten()
+                    .filter(|&&x| x != entry.name)
+                    .map(|x| x.to_string())
+                    .collect(),
+            })
+            .collect();
+        definitions.sort_by(|x, y| x.address.cmp(&y.address).then(x.name.cmp(&y.name)));
+
+        // Unresolved weak references stay undefined, or become absolute symbols at 0 when linked statically
+        let unresolved = symbol_table
+            .iter()
+            .filter(weak)
+            .filter(|x| !x.is_allocated() && x.section != "*COM*" && x.address == 0)
+            .map(|x| UnresolvedReference {
+                name: x.name.clone(),
+                address: x.address,
+            })
+            .collect();
+
+        let mut candidates: BTreeMap<&str, (Vec<&str>, Vec<&str>)> = BTreeMap::new();
+        for object in objects {
+            let defined = object
+                .symbol_table
+                .iter()
+                .filter(|x| x.is_allocated() && !x.is_section_symbol())
+                .filter(|x| x.flags.weakness == SymbolWeakness::Weak || x.flags.scope == SymbolScope::Global);
+            for entry in defined {
+                let (strong, weak) = candidates.entry(entry.plain_name()).or_default();
+                match entry.flags.weakness {
+                    SymbolWeakness::Weak => weak.push(&object.object),
+                    SymbolWeakness::Strong => strong.push(&object.object),
+                }
+            }
+        }
+        let defined = symbol_table.defined_by_name();
+        let overrides = candidates
+            .into_iter()
+            .filter(|(_, (strong, weak))| !strong.is_empty() && !weak.is_empty())
+            .map(|(name, (strong, weak))| {
+                let kept_strong = defined
+                    .get(name)
+                    .is_some_and(|x| x.is_allocated() && x.flags.weakness == SymbolWeakness::Strong);
+                Override {
+                    name: name.to_string(),
+                    // A strong definition in an archive member that wasn't pulled in loses to the weak one
+                    winner: if kept_strong && strong.len() == 1 { Some(strong[0].to_string()) } else { None },
+                    strong: strong.iter().map(|x| x.to_string()).collect(),
+                    weak: weak.iter().map(|x| x.to_string()).collect(),
+                }
+            })
+            .collect();
+
+        WeakReport {
+            definitions,
+            unresolved,
+            overrides,
+        }
+    }
+}
+
+impl Override {
+    /// Describes which definition ended up in the image
+    fn winner_string(&self) -> String {
+        match &self.winner {
+            Some(object) => object.clone(),
+            None if self.strong.len() > 1 => "(multiple strong)".to_string(),
+            None => "(weak kept)".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for WeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Weak definitions in the image:")?;
+        writeln!(f, "  {:8} {:>8} {:10} {:30} Aliases", "Address", "Size", "Section", "Symbol")?;
+        for definition in &self.definitions {
+            writeln!(
+                f,
+                "  {:08x} {:>8} {:10} {:30} {}",
+                definition.address,
+                definition.size,
+                definition.section,
+                definition.name,
+                definition.aliases.join(", ")
+            )?;
+        }
+        writeln!(f, "Weak references resolved to 0:")?;
+        for reference in &self.unresolved {
+            writeln!(f, "  {}", reference.name)?;
+        }
+        if !self.overrides.is_empty() {
+            writeln!(f, "Overrides:")?;
+            writeln!(f, "  {:30} {:20} Weak definitions", "Symbol", "Winner")?;
+            for item in &self.overrides {
+                writeln!(f, "  {:30} {:20} {}", item.name, item.winner_string(), item.weak.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Report for WeakReport {
+    fn to_html(&self) -> String {
+        format!("{}", html! {
+            : doctype::HTML;
+            html {
+                head {
+                    title : "Weak Symbols";
+                    style : TABLE_STYLE;
+                }
+                body {
+                    h1(id="heading", class="title") : "Weak Definitions in the Image";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Address";
+                                th: "Size";
+                                th: "Section";
+                                th: "Symbol";
+                                th: "Aliases";
+                            }
+                        }
+                        tbody {
+                            @ for definition in &self.definitions {
+                                tr {
+                                    td: format!("{:08x}", definition.address);
+                                    td: definition.size;
+                                    td: &definition.section;
+                                    td: &definition.name;
+                                    td: definition.aliases.join(", ");
+                                }
+                            }
+                        }
+                    }
+                    h2 : "Weak References Resolved to 0";
+                    ul {
+                        @ for reference in &self.unresolved {
+                            li : &reference.name;
+                        }
+                    }
+                    h2 : "Overrides";
+                    table(class="tg") {
+                        thead {
+                            tr {
+                                th: "Symbol";
+                                th: "Winner";
+                                th: "Strong Definitions";
+                                th: "Weak Definitions";
+                            }
+                        }
+                        tbody {
+                            @ for item in &self.overrides {
+                                tr {
+                                    td: &item.name;
+                                    td: item.winner_string();
+                                    td: item.strong.join(", ");
+                                    td: item.weak.join(", ");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,symbol,section,address,size,other\n");
+        for definition in &self.definitions {
+            csv += &format!(
+                "weak,{},{},{:08x},{},{}\n",
+                csv_field(&definition.name),
+                csv_field(&definition.section),
+                definition.address,
+                definition.size,
+                csv_field(&definition.aliases.join(" "))
+            );
+        }
+        for reference in &self.unresolved {
+            csv += &format!("unresolved,{},,{:08x},0,\n", csv_field(&reference.name), reference.address);
+        }
+        for item in &self.overrides {
+            csv += &format!("override,{},,,,{}\n", csv_field(&item.name), csv_field(&item.winner_string()));
+        }
+        csv
+    }
+}
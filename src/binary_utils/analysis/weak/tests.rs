@@ -0,0 +1,132 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+fn image() -> SymbolTable {
+    SymbolTable::from_lines(&lines(&[
+        "08000000 l    d  .text	00000000 .text",
+        "08000100 g     F .text	00000002 Default_Handler",
+        "08000100  w    F .text	00000002 SysTick_Handler",
+        "08000100  w    F .text	00000002 HardFault_Handler",
+        "08000104 g     F .text	00000010 UART_Handler",
+        "08000114 g     F .text	00000008 board_init",
+        "0800011c  w    F .text	00000004 clock_config",
+        "00000000  w      *UND*	00000000 optional_hook",
+        "00000000  w      *ABS*	00000000 __trace_start",
+    ]))
+}
+
+fn objects() -> Vec<ObjectSymbols> {
+    ObjectSymbols::from_lines(&lines(&[
+        "",
+        "startup.o:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "00000000 l    d  .text	00000000 .text",
+        "00000000 g     F .text	00000002 Default_Handler",
+        "00000000  w    F .text	00000002 SysTick_Handler",
+        "00000000  w    F .text	00000002 HardFault_Handler",
+        "00000000  w    F .text	00000002 UART_Handler",
+        "",
+        "main.o:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "00000000 l     F .text	00000004 helper",
+        "00000004 g     F .text	00000010 UART_Handler",
+        "00000014 g     F .text	00000008 board_init",
+        "00000000         *UND*	00000000 Default_Handler",
+        "",
+        "In archive libboard.a:",
+        "",
+        "board.o:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "00000000  w    F .text	00000008 board_init",
+        "00000008  w    F .text	00000004 clock_config",
+        "",
+        "board_custom.o:     file format elf32-littlearm",
+        "",
+        "SYMBOL TABLE:",
+        "00000000 g     F .text	00000004 clock_config",
+    ]))
+}
+
+#[test]
+fn test_object_symbols_from_lines() {
+    let objects = objects();
+    let names: Vec<&str> = objects.iter().map(|x| x.object.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["startup.o", "main.o", "libboard.a(board.o)", "libboard.a(board_custom.o)"]
+    );
+    assert_eq!(objects[0].symbol_table.len(), 5);
+    assert_eq!(objects[1].symbol_table.len(), 4);
+}
+
+#[test]
+fn test_weak_definitions_and_references() {
+    let report = WeakReport::new(&image(), &[]);
+    let names: Vec<&str> = report.definitions.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, vec!["HardFault_Handler", "SysTick_Handler", "clock_config"]);
+    assert_eq!(
+        report.definitions[1].aliases,
+        vec!["Default_Handler".to_string(), "HardFault_Handler".to_string()]
+    );
+    assert!(report.definitions[2].aliases.is_empty());
+
+    let unresolved: Vec<&str> = report.unresolved.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(unresolved, vec!["optional_hook", "__trace_start"]);
+    assert!(report.overrides.is_empty());
+}
+
+#[test]
+fn test_weak_overrides() {
+    let report = WeakReport::new(&image(), &objects());
+    assert_eq!(
+        report.overrides,
+        vec![
+            Override {
+                name: "UART_Handler".to_string(),
+                winner: Some("main.o".to_string()),
+                strong: vec!["main.o".to_string()],
+                weak: vec!["startup.o".to_string()],
+            },
+            Override {
+                name: "board_init".to_string(),
+                winner: Some("main.o".to_string()),
+                strong: vec!["main.o".to_string()],
+                weak: vec!["libboard.a(board.o)".to_string()],
+            },
+            // The archive member with the strong definition wasn't linked in
+            Override {
+                name: "clock_config".to_string(),
+                winner: None,
+                strong: vec!["libboard.a(board_custom.o)".to_string()],
+                weak: vec!["libboard.a(board.o)".to_string()],
+            },
+        ]
+    );
+
+    let text = report.to_string();
+    assert!(text.contains("(weak kept)"));
+    assert!(report.to_csv().contains("override,UART_Handler,,,,main.o\n"));
+    assert!(report.to_csv().contains("unresolved,optional_hook,,00000000,0,\n"));
+    assert!(report.to_html().contains("<li>optional_hook</li>"));
+}
+
+#[test]
+fn test_weak_overrides_hidden_names() {
+    let objects = ObjectSymbols::from_lines(&lines(&[
+        "weak.o:     file format elf32-littlearm",
+        "SYMBOL TABLE:",
+        "00000000  w    F .text	00000002 .hidden hook",
+        "strong.o:     file format elf32-littlearm",
+        "SYMBOL TABLE:",
+        "00000000 g     F .text	00000004 .hidden hook",
+    ]));
+    let image = SymbolTable::from_lines(&lines(&["08000100 l     F .text	00000004 hook"]));
+    let report = WeakReport::new(&image, &objects);
+    assert_eq!(report.overrides.len(), 1);
+    assert_eq!(report.overrides[0].name, "hook");
+    assert_eq!(report.overrides[0].weak, vec!["weak.o".to_string()]);
+    assert_eq!(report.overrides[0].winner, Some("strong.o".to_string()));
+}
@@ -1 +1,6 @@
-pub mod parser;
\ No newline at end of file
+pub mod analysis;
+pub mod parser;
+pub mod report;
+
+#[cfg(test)]
+mod test_utils;
@@ -1 +1,9 @@
+pub mod call_graph_info;
+pub mod disassembly;
+pub mod eh_frame;
+pub mod map_file;
+pub mod relocations;
+pub mod section_contents;
+pub mod section_table;
+pub mod stack_usage;
 pub mod symbol_table;
@@ -0,0 +1,201 @@
+use nom::{
+    bytes::complete::{tag, take_until},
+    sequence::{preceded, tuple},
+    IResult,
+};
+
+use std::{io, path::Path};
+
+use super::stack_usage::{parse_frame_size, FrameSize};
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// Title GCC gives the node that stands in for every call through a function pointer
+pub const INDIRECT_CALL: &str = "__indirect_call";
+
+/// A function in a GCC `-fcallgraph-info` file
+#[derive(Debug, PartialEq)]
+pub struct CallGraphNode {
+    /// Unique name of the node. Static functions are prefixed with their source file, e.g. "app.c:depth"
+    pub title: String,
+    /// Name of the function
+    pub name: String,
+    /// Source location of the function's definition or declaration
+    pub location: Option<String>,
+    /// Stack frame of the function, when compiled with `-fcallgraph-info=su`
+    pub frame: Option<FrameSize>,
+    /// Set for functions that are only declared in the compiled file
+    pub external: bool,
+}
+
+/// A call from one node to another in a GCC `-fcallgraph-info` file
+#[derive(Debug, PartialEq)]
+pub struct CallGraphEdge {
+    /// Title of the calling node
+    pub caller: String,
+    /// Title of the called node
+    pub callee: String,
+    /// Source location of the call
+    pub location: Option<String>,
+}
+
+/// The nodes and edges of one or more GCC `.ci` files, which are written in the VCG graph format
+pub struct CallGraphInfo {
+    pub nodes: Vec<CallGraphNode>,
+    pub edges: Vec<CallGraphEdge>,
+}
+
+impl CallGraphInfo {
+    /// Creates a new empty CallGraphInfo object
+    pub fn new() -> Self {
+        CallGraphInfo {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Creates call graph info from a `.ci` file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let info = call_graph_info::CallGraphInfo::from_file("main.ci")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(CallGraphInfo::from_lines(&input))
+    }
+
+    /// Creates call graph info from the lines of `.ci` files. Each node and edge is on its own line,
+    /// and any other lines are ignored
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut info = CallGraphInfo::new();
+        for line in lines {
+            if let Ok((_, node)) = parse_call_graph_node(line) {
+                info.nodes.push(node);
+            } else if let Ok((_, edge)) = parse_call_graph_edge(line) {
+                info.edges.push(edge);
+            }
+        }
+        info
+    }
+
+    /// Adds the nodes and edges of another file, e.g. to combine the `.ci` files of every source file
+    pub fn extend(&mut self, other: CallGraphInfo) {
+        self.nodes.extend(other.nodes);
+        self.edges.extend(other.edges);
+    }
+
+    /// Finds a node by title. A function defined in one file and called from another appears in
+    /// both, so the definition is preferred over the external declaration
+    pub fn find(&self, title: &str) -> Option<&CallGraphNode> {
+        let mut matching = self.nodes.iter().filter(|x| x.title == title);
+        let first = matching.next()?;
+        if first.external {
+            Some(matching.find(|x| !x.external).unwrap_or(first))
+        } else {
+            Some(first)
+        }
+    }
+
+    /// Gets the nodes a node calls directly, not counting indirect calls
+    pub fn callees(&self, title: &str) -> Vec<&CallGraphNode> {
+        self.edges
+            .iter()
+            .filter(|x| x.caller == title && x.callee != INDIRECT_CALL)
+            .filter_map(|x| self.find(&x.callee))
+            .collect()
+    }
+
+    /// Checks if a node calls through a function pointer
+    pub fn has_indirect_calls(&self, title: &str) -> bool {
+        self.edges.iter().any(|x| x.caller == title && x.callee == INDIRECT_CALL)
+    }
+}
+
+impl CallGraphNode {
+    /// Gets the source file of a static function from its title, e.g. "app.c" for "app.c:depth"
+    pub fn file(&self) -> Option<&str> {
+        self.title.strip_suffix(self.name.as_str())?.strip_suffix(':')
+    }
+}
+
+impl Default for CallGraphInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses the quoted value of a named field, e.g. `title: "main"`
+fn parse_field<'a>(input: &'a str, name: &str) -> IResult<&'a str, &'a str> {
+    let (rest, (_, _, value, _)) = tuple((take_until(name), tag(name), preceded(tag(": \""), take_until("\"")), tag("\"")))(input)?;
+    Ok((rest, value))
+}
+
+/// Parses a node line. The label holds the function name, its location and, when available, its
+/// frame size on separate lines, which GCC writes as escaped newlines
+///
+/// # Arguments
+/// * 'input' - The input string to parse the node from
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::call_graph_info::parse_call_graph_node;
+/// let line = r#"node: { title: "main" label: "main\napp.c:7:5\n32 bytes (static)\n0 dynamic objects" }"#;
+/// let (_, node) = parse_call_graph_node(line).unwrap();
+/// assert_eq!(node.name, "main");
+/// assert_eq!(node.frame.unwrap().bytes, 32);
+/// ```
+pub fn parse_call_graph_node(input: &str) -> IResult<&str, CallGraphNode> {
+    let (rest, _) = tag("node: {")(input.trim_start())?;
+    let (rest, title) = parse_field(rest, "title")?;
+    let (rest, label) = parse_field(rest, "label")?;
+    let mut lines = label.split("\\n");
+    let name = lines.next().unwrap_or(title);
+    let location = lines.next().filter(|x| x.contains(':')).map(|x| x.to_string());
+    Ok((
+        "",
+        CallGraphNode {
+            title: title.to_string(),
+            name: name.to_string(),
+            location,
+            frame: label.split("\\n").find_map(parse_frame_size),
+            external: rest.contains("shape : ellipse") || rest.contains("shape: ellipse"),
+        },
+    ))
+}
+
+/// Parses an edge line. Returns a results type containing the parsed result if successful
+///
+/// # Arguments
+/// * 'input' - The input string to parse the edge from
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::call_graph_info::parse_call_graph_edge;
+/// let line = r#"edge: { sourcename: "main" targetname: "printf" label: "app.c:7:18" }"#;
+/// let (_, edge) = parse_call_graph_edge(line).unwrap();
+/// assert_eq!(edge.caller, "main");
+/// assert_eq!(edge.callee, "printf");
+/// ```
+pub fn parse_call_graph_edge(input: &str) -> IResult<&str, CallGraphEdge> {
+    let (rest, _) = tag("edge: {")(input.trim_start())?;
+    let (rest, caller) = parse_field(rest, "sourcename")?;
+    let (rest, callee) = parse_field(rest, "targetname")?;
+    let location = parse_field(rest, "label").ok().map(|(_, x)| x.to_string());
+    Ok((
+        "",
+        CallGraphEdge {
+            caller: caller.to_string(),
+            callee: callee.to_string(),
+            location,
+        },
+    ))
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+use crate::binary_utils::parser::stack_usage::FrameKind;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_call_graph_node() {
+    let (_, node) = parse_call_graph_node(
+        r#"node: { title: "app.c:depth" label: "depth\napp.c:3:12\n64 bytes (static)\n0 dynamic objects" }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        node,
+        CallGraphNode {
+            title: "app.c:depth".to_string(),
+            name: "depth".to_string(),
+            location: Some("app.c:3:12".to_string()),
+            frame: Some(FrameSize {
+                bytes: 64,
+                kind: FrameKind::Static
+            }),
+            external: false,
+        }
+    );
+    assert_eq!(node.file(), Some("app.c"));
+
+    let (_, node) =
+        parse_call_graph_node(r#"node: { title: "memset" label: "memset\n/usr/include/string.h:61:14" shape : ellipse }"#)
+            .unwrap();
+    assert!(node.external);
+    assert_eq!(node.frame, None);
+    assert_eq!(node.file(), None);
+
+    let (_, node) =
+        parse_call_graph_node(r#"node: { title: "__indirect_call" label: "Indirect Call Placeholder" shape : ellipse }"#)
+            .unwrap();
+    assert_eq!(node.location, None);
+}
+
+#[test]
+fn test_parse_call_graph_edge() {
+    let (_, edge) =
+        parse_call_graph_edge(r#"edge: { sourcename: "main" targetname: "app.c:depth" label: "app.c:7:33" }"#).unwrap();
+    assert_eq!(
+        edge,
+        CallGraphEdge {
+            caller: "main".to_string(),
+            callee: "app.c:depth".to_string(),
+            location: Some("app.c:7:33".to_string()),
+        }
+    );
+    assert!(parse_call_graph_edge(r#"graph: { title: "app.c""#).is_err());
+}
+
+#[test]
+fn test_find_prefers_definitions() {
+    let mut info = CallGraphInfo::from_lines(&lines(&[
+        r#"node: { title: "main" label: "main\nmain.c:3:5\n16 bytes (static)\n0 dynamic objects" }"#,
+        r#"node: { title: "uart_write" label: "uart_write\nuart.h:4:6" shape : ellipse }"#,
+        r#"edge: { sourcename: "main" targetname: "uart_write" label: "main.c:4:3" }"#,
+        r#"node: { title: "__indirect_call" label: "Indirect Call Placeholder" shape : ellipse }"#,
+        r#"edge: { sourcename: "main" targetname: "__indirect_call" label: "main.c:5:3" }"#,
+    ]));
+    info.extend(CallGraphInfo::from_lines(&lines(&[
+        r#"node: { title: "uart_write" label: "uart_write\nuart.c:4:6\n24 bytes (static)\n0 dynamic objects" }"#,
+    ])));
+
+    let callees = info.callees("main");
+    assert_eq!(callees.len(), 1);
+    assert_eq!(callees[0].frame.unwrap().bytes, 24);
+    assert!(info.has_indirect_calls("main"));
+    assert!(!info.has_indirect_calls("uart_write"));
+}
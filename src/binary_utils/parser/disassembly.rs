@@ -0,0 +1,407 @@
+use nom::{
+    bytes::complete::{tag, take_until, take_while},
+    character::complete::{char, hex_digit1, multispace0, not_line_ending, space1},
+    multi::separated_list1,
+    sequence::tuple,
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use super::relocations::{parse_relocation, Relocation};
+use super::section_table::parse_file_format;
+use super::symbol_table::parse_u32;
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// The disassembled code of a binary, as output by `objdump -d`
+pub struct Disassembly {
+    /// The file format from the objdump header, e.g. "elf32-littlearm"
+    pub format: Option<String>,
+    functions: Vec<DisassembledFunction>,
+}
+
+/// A labelled block of instructions in the disassembly
+#[derive(Debug, PartialEq)]
+pub struct DisassembledFunction {
+    pub name: String,
+    pub address: u32,
+    pub section: String,
+    pub instructions: Vec<Instruction>,
+}
+
+/// A single disassembled instruction or data directive
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    pub address: u32,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operands: String,
+    /// Annotation objdump adds after the operands, e.g. the symbol a PC relative load refers to
+    pub comment: Option<String>,
+    /// Relocations that `objdump -r` lists under the instruction, which fill in the addresses an
+    /// object file leaves blank
+    pub relocations: Vec<Relocation>,
+}
+
+/// The instructions of one section sorted by address, so ranges can be looked up without
+/// scanning the whole disassembly
+pub struct InstructionIndex<'a> {
+    instructions: Vec<&'a Instruction>,
+}
+
+impl Disassembly {
+    /// Creates a new empty Disassembly object
+    pub fn new() -> Self {
+        Disassembly {
+            format: None,
+            functions: Vec::new(),
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, DisassembledFunction> {
+        self.functions.iter()
+    }
+
+    /// Creates a disassembly from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let disassembly = disassembly::Disassembly::from_file("binary.txt")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(Disassembly::from_lines(&input))
+    }
+
+    /// Creates a disassembly from lines of objdump output. Instructions are collected under the
+    /// label that precedes them, and any lines outside of a disassembled section are ignored
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut disassembly = Disassembly::new();
+        let mut section: Option<String> = None;
+        for line in lines {
+            if disassembly.format.is_none() {
+                if let Ok((_, (_, format))) = parse_file_format(line) {
+                    disassembly.format = Some(format.to_string());
+                    continue;
+                }
+            }
+            if let Ok((_, name)) = parse_section_header(line) {
+                section = Some(name.to_string());
+                continue;
+            }
+            let section = match &section {
+                Some(x) => x,
+                None => continue,
+            };
+            if let Ok((_, (address, name))) = parse_function_header(line) {
+                disassembly.functions.push(DisassembledFunction {
+                    name: name.to_string(),
+                    address,
+                    section: section.clone(),
+                    instructions: Vec::new(),
+                });
+            } else if let Ok((_, instruction)) = parse_instruction(line) {
+                if let Some(function) = disassembly.functions.last_mut() {
+                    function.instructions.push(instruction);
+                }
+            } else if let Some(relocation) = parse_instruction_relocation(line) {
+                let previous = disassembly.functions.last_mut().and_then(|x| x.instructions.last_mut());
+                if let Some(instruction) = previous {
+                    instruction.relocations.push(relocation);
+                }
+            } else if let Ok((_, (_, bytes))) = parse_instruction_bytes(line) {
+                // Long instructions wrap their encoding onto a second line without a mnemonic
+                let previous = disassembly.functions.last_mut().and_then(|x| x.instructions.last_mut());
+                if let Some(instruction) = previous {
+                    instruction.bytes.extend(bytes);
+                }
+            }
+        }
+        disassembly
+    }
+
+    /// Finds a function by name
+    pub fn find(&self, name: &str) -> Option<&DisassembledFunction> {
+        self.iter().find(|x| x.name == name)
+    }
+
+    /// Finds the instructions that start inside an address range [start, end)
+    pub fn instructions_in_range(&self, start: u32, end: u32) -> impl Iterator<Item = &Instruction> {
+        self.iter()
+            .flat_map(|x| x.instructions.iter())
+            .filter(move |x| x.address >= start && x.address < end)
+    }
+
+    /// Indexes the instructions of a section by address
+    ///
+    /// # Arguments
+    /// * 'section' - Name of the section, since every section of an object file starts at zero
+    pub fn index_section(&self, section: &str) -> InstructionIndex<'_> {
+        let mut instructions: Vec<&Instruction> = self
+            .iter()
+            .filter(|x| x.section == section)
+            .flat_map(|x| x.instructions.iter())
+            .collect();
+        instructions.sort_by_key(|x| x.address);
+        InstructionIndex { instructions }
+    }
+}
+
+impl<'a> InstructionIndex<'a> {
+    /// Finds the instructions that start inside an address range [start, end)
+    pub fn in_range(&self, start: u32, end: u64) -> &[&'a Instruction] {
+        let first = self.instructions.partition_point(|x| x.address < start);
+        let last = self.instructions.partition_point(|x| (x.address as u64) < end).max(first);
+        &self.instructions[first..last]
+    }
+}
+
+impl Default for Disassembly {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for Disassembly {
+    type Target = Vec<DisassembledFunction>;
+    fn deref(&self) -> &Self::Target {
+        &self.functions
+    }
+}
+
+impl DisassembledFunction {
+    /// Gets the number of bytes covered by the function's instructions
+    pub fn size(&self) -> u32 {
+        match self.instructions.last() {
+            Some(last) => last.address + last.bytes.len() as u32 - self.address,
+            None => 0,
+        }
+    }
+
+    /// Gets all of the function's instruction bytes in order
+    pub fn bytes(&self) -> Vec<u8> {
+        self.instructions.iter().flat_map(|x| x.bytes.iter().copied()).collect()
+    }
+
+    /// Gets the instructions of the function without the padding that objdump lists after its end
+    pub fn body(&self) -> &[Instruction] {
+        let end = self
+            .instructions
+            .iter()
+            .rposition(|x| !x.is_padding())
+            .map_or(0, |x| x + 1);
+        &self.instructions[..end]
+    }
+}
+
+impl Instruction {
+    /// Checks if the instruction is a data directive, like an ARM literal pool `.word`, rather
+    /// than real code
+    pub fn is_data(&self) -> bool {
+        self.mnemonic.starts_with('.')
+    }
+
+    /// Checks if the instruction does nothing and is only there for padding
+    pub fn is_padding(&self) -> bool {
+        let mnemonic = self.mnemonic.as_str();
+        mnemonic.starts_with("nop")
+            || mnemonic == "c.nop"
+            || mnemonic == "int3"
+            || (mnemonic == "xchg" && self.operands == "%ax,%ax")
+            || (mnemonic == "mov" && (self.operands == "r8, r8" || self.operands == "r0, r0"))
+            || (self.is_data() && self.bytes.iter().all(|&x| x == 0))
+    }
+
+    /// Checks if the instruction calls a subroutine and returns to the next instruction
+    pub fn is_call(&self) -> bool {
+        matches!(self.mnemonic.as_str(), "call" | "callq" | "bl" | "blx" | "jal" | "jalr" | "c.jal" | "c.jalr")
+    }
+
+    /// Checks if the instruction is an unconditional jump, which can be a tail call when it leaves
+    /// the function
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self.mnemonic.as_str(),
+            "jmp" | "jmpq" | "b" | "b.w" | "b.n" | "bx" | "j" | "jr" | "c.j" | "c.jr"
+        )
+    }
+
+    /// Gets the addresses and symbols the instruction refers to, like the target of a branch or
+    /// the data a PC relative load reads. Symbols can include an offset, e.g. "_init+0x20"
+    pub fn references(&self) -> Vec<(u32, &str)> {
+        let mut references = parse_references(&self.operands);
+        if let Some(comment) = &self.comment {
+            references.extend(parse_references(comment));
+        }
+        references
+    }
+
+    /// Gets the operands without the addresses and offsets that depend on where the code and data
+    /// were placed, e.g. "lea 0x2fd9(%rip),%rdi" becomes "(%rip),%rdi". The symbols they refer to
+    /// are still available from `references`
+    pub fn masked_operands(&self) -> String {
+        let mut operands = match self.operands.find(" <") {
+            Some(position) => {
+                // Drop the address printed in front of the symbol along with the symbol itself
+                let operands = &self.operands[..position];
+                operands[..operands.rfind([' ', ',']).map_or(0, |x| x + 1)].to_string()
+            }
+            None => self.operands.clone(),
+        };
+        if let Some(end) = operands.find("(%rip)") {
+            let start = operands[..end]
+                .rfind(|c: char| !(c.is_ascii_hexdigit() || c == 'x' || c == '-'))
+                .map_or(0, |x| x + 1);
+            operands.replace_range(start..end, "");
+        }
+        if let Some(start) = operands.find("[pc, #") {
+            if let Some(end) = operands[start..].find(']') {
+                operands.replace_range(start..start + end + 1, "[pc]");
+            }
+        }
+        operands
+    }
+}
+
+/// Finds every "address <symbol>" reference objdump printed in an operand or comment string
+fn parse_references(text: &str) -> Vec<(u32, &str)> {
+    let mut references = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(" <") {
+        let end = match rest[start..].find('>') {
+            Some(x) => start + x,
+            None => break,
+        };
+        let address = rest[..start]
+            .rsplit(|c: char| c.is_whitespace() || c == '(' || c == ',')
+            .next()
+            .and_then(|x| u32::from_str_radix(x, 16).ok());
+        if let Some(address) = address {
+            references.push((address, &rest[start + 2..end]));
+        }
+        rest = &rest[end + 1..];
+    }
+    references
+}
+
+/// Parses a relocation listed under an instruction by `objdump -dr`, e.g.
+/// "\t\t\t9: R_X86_64_PLT32\tputs-0x4"
+fn parse_instruction_relocation(line: &str) -> Option<Relocation> {
+    if !line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let (offset, record) = line.trim_start().split_once(": ")?;
+    parse_relocation(&format!("{} {}", offset, record)).ok().map(|(_, x)| x)
+}
+
+/// Prefixes that objdump prints in front of the x86 mnemonic they modify
+const INSTRUCTION_PREFIXES: [&str; 12] = [
+    "bnd", "notrack", "lock", "rep", "repz", "repnz", "repe", "repne", "data16", "addr32", "cs", "ds",
+];
+
+/// Splits the instruction text at the first whitespace into the mnemonic and its operands
+fn split_mnemonic(text: &str) -> (&str, &str) {
+    let text = text.trim();
+    match text.find(char::is_whitespace) {
+        Some(position) => (&text[..position], text[position..].trim()),
+        None => (text, ""),
+    }
+}
+
+/// Parses the line that starts each disassembled section, e.g. "Disassembly of section .text:"
+fn parse_section_header(input: &str) -> IResult<&str, &str> {
+    let (rest, (_, name, _)) = tuple((tag("Disassembly of section "), take_until(":"), char(':')))(input)?;
+    Ok((rest, name))
+}
+
+/// Parses a function label line, e.g. "0000000000001080 <main>:"
+fn parse_function_header(input: &str) -> IResult<&str, (u32, &str)> {
+    let (rest, (address, _, _, name, _)) =
+        tuple((parse_u32, space1, char('<'), take_until(">:"), tag(">:")))(input)?;
+    Ok((rest, (address, name)))
+}
+
+/// Parses the address and encoded bytes at the start of an instruction line. Bytes are shown
+/// either one at a time (x86) or grouped into 16/32 bit words (ARM)
+fn parse_instruction_bytes(input: &str) -> IResult<&str, (u32, Vec<u8>)> {
+    let (rest, (_, address, _, _, groups, _)) = tuple((
+        space1,
+        parse_u32,
+        char(':'),
+        space1,
+        separated_list1(char(' '), hex_digit1),
+        take_while(|c| c == ' '),
+    ))(input)?;
+    let mut bytes = Vec::new();
+    for group in groups {
+        if group.len() % 2 != 0 {
+            return Err(nom::Err::Error(nom::error::Error {
+                input: group,
+                code: nom::error::ErrorKind::HexDigit,
+            }));
+        }
+        for i in (0..group.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(&group[i..i + 2], 16).unwrap());
+        }
+    }
+    Ok((rest, (address, bytes)))
+}
+
+/// Parses a disassembled instruction line. Returns a results type containing the parsed result if successful
+///
+/// # Arguments
+/// * 'input' - The input string to parse the instruction from
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::disassembly::parse_instruction;
+/// let (_, instruction) = parse_instruction("    1004:\t48 83 ec 08          \tsub    $0x8,%rsp").unwrap();
+/// assert_eq!(instruction.address, 0x1004);
+/// assert_eq!(instruction.bytes, vec![0x48, 0x83, 0xec, 0x08]);
+/// assert_eq!(instruction.mnemonic, "sub");
+/// assert_eq!(instruction.operands, "$0x8,%rsp");
+/// ```
+pub fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+    let (rest, (address, bytes)) = parse_instruction_bytes(input)?;
+    let (rest, (_, _, text)) = tuple((char('\t'), multispace0, not_line_ending))(rest)?;
+    if text.trim().is_empty() {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: text,
+            code: nom::error::ErrorKind::Space,
+        }));
+    }
+
+    // Split off any comment objdump appended, then any prefixes in front of the real mnemonic
+    let comment_start = [" # ", "\t# ", "\t; ", "\t@ "].iter().filter_map(|x| text.find(x)).min();
+    let (code, comment) = match comment_start {
+        Some(position) => (&text[..position], Some(text[position + 3..].trim().to_string())),
+        None => (text, None),
+    };
+    let (mut mnemonic, mut operands) = split_mnemonic(code);
+    while INSTRUCTION_PREFIXES.contains(&mnemonic) && !operands.is_empty() {
+        let (next_mnemonic, next_operands) = split_mnemonic(operands);
+        mnemonic = next_mnemonic;
+        operands = next_operands;
+    }
+
+    Ok((
+        rest,
+        Instruction {
+            address,
+            bytes,
+            mnemonic: mnemonic.to_string(),
+            operands: operands.to_string(),
+            comment,
+            relocations: Vec::new(),
+        },
+    ))
+}
@@ -0,0 +1,143 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_section_header() {
+    assert_eq!(parse_section_header("Disassembly of section .text:"), Ok(("", ".text")));
+    assert!(parse_section_header("SYMBOL TABLE:").is_err());
+}
+
+#[test]
+fn test_parse_function_header() {
+    assert_eq!(parse_function_header("0000000000001080 <main>:"), Ok(("", (0x1080, "main"))));
+    assert_eq!(
+        parse_function_header("0000000000001020 <printf@plt-0x10>:"),
+        Ok(("", (0x1020, "printf@plt-0x10")))
+    );
+    assert!(parse_function_header("    1080:	f3 0f 1e fa          	endbr64").is_err());
+}
+
+#[test]
+fn test_parse_x86_instruction_with_comment() {
+    let (_, instruction) =
+        parse_instruction("    1004:	48 8b 05 c5 2f 00 00 	mov    0x2fc5(%rip),%rax        # 3fd0 <__gmon_start__@Base>")
+            .unwrap();
+    assert_eq!(instruction.address, 0x1004);
+    assert_eq!(instruction.bytes, vec![0x48, 0x8b, 0x05, 0xc5, 0x2f, 0x00, 0x00]);
+    assert_eq!(instruction.mnemonic, "mov");
+    assert_eq!(instruction.operands, "0x2fc5(%rip),%rax");
+    assert_eq!(instruction.comment, Some("3fd0 <__gmon_start__@Base>".to_string()));
+}
+
+#[test]
+fn test_parse_x86_instruction_with_prefix() {
+    let (_, instruction) = parse_instruction("    1039:	f2 e9 e1 ff ff ff    	bnd jmpq 1020 <.plt>").unwrap();
+    assert_eq!(instruction.mnemonic, "jmpq");
+    assert_eq!(instruction.operands, "1020 <.plt>");
+    assert_eq!(instruction.comment, None);
+}
+
+#[test]
+fn test_parse_arm_instructions() {
+    let (_, push) = parse_instruction("    8000:	b580      	push	{r7, lr}").unwrap();
+    assert_eq!(push.bytes, vec![0xb5, 0x80]);
+    assert_eq!(push.mnemonic, "push");
+    assert_eq!(push.operands, "{r7, lr}");
+
+    let (_, call) = parse_instruction("    8004:	f000 f8a6 	bl	8154 <foo>").unwrap();
+    assert_eq!(call.bytes.len(), 4);
+    assert!(call.is_call());
+    assert!(!call.is_jump());
+    assert_eq!(call.operands, "8154 <foo>");
+
+    let (_, load) = parse_instruction("    8008:	4b02      	ldr	r3, [pc, #8]	; (8014 <main+0x14>)").unwrap();
+    assert_eq!(load.operands, "r3, [pc, #8]");
+    assert_eq!(load.comment, Some("(8014 <main+0x14>)".to_string()));
+
+    let (_, literal) = parse_instruction("    8014:	20000000 	.word	0x20000000").unwrap();
+    assert!(literal.is_data());
+    assert_eq!(literal.bytes.len(), 4);
+}
+
+#[test]
+fn test_disassembly_from_lines() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "hello_world:     file format elf64-x86-64",
+        "",
+        "Disassembly of section .text:",
+        "",
+        "00000000000010e3 <pad>:",
+        "    10e3:	66 2e 0f 1f 84 00 00 	nopw   %cs:0x0(%rax,%rax,1)",
+        "    10ea:	00 00 00 ",
+        "    10ed:	0f 1f 00             	nopl   (%rax)",
+        "",
+        "00000000000010f0 <_start>:",
+        "    10f0:	f3 0f 1e fa          	endbr64 ",
+        "    10f4:	31 ed                	xor    %ebp,%ebp",
+    ]));
+    assert_eq!(disassembly.format, Some("elf64-x86-64".to_string()));
+    assert_eq!(disassembly.len(), 2);
+    let pad = disassembly.find("pad").unwrap();
+    assert_eq!(pad.section, ".text");
+    assert_eq!(pad.instructions.len(), 2);
+    assert_eq!(pad.instructions[0].bytes.len(), 10);
+    assert_eq!(pad.size(), 13);
+    assert!(pad.instructions.iter().all(|x| x.is_padding()));
+    assert!(pad.body().is_empty());
+    assert_eq!(disassembly.find("_start").unwrap().body().len(), 2);
+    assert_eq!(disassembly.find("_start").unwrap().instructions[0].mnemonic, "endbr64");
+    assert_eq!(disassembly.instructions_in_range(0x10e3, 0x10f0).count(), 2);
+    assert_eq!(disassembly.index_section(".text").in_range(0x10e3, 0x10f0).len(), 2);
+    assert!(disassembly.index_section(".init").in_range(0x10e3, 0x10f0).is_empty());
+}
+
+#[test]
+fn test_instruction_references() {
+    let (_, call) = parse_instruction("    1087:	e8 b4 ff ff ff       	call   1040 <snprintf@plt>").unwrap();
+    assert_eq!(call.references(), vec![(0x1040, "snprintf@plt")]);
+
+    let (_, load) =
+        parse_instruction("    1098:	c6 05 b0 2f 00 00 00 	movb   $0x0,0x2fb0(%rip)        # 404f <print_buffer+0xf>")
+            .unwrap();
+    assert_eq!(load.references(), vec![(0x404f, "print_buffer+0xf")]);
+
+    let (_, literal) = parse_instruction(" 8000128:	4b03      	ldr	r3, [pc, #12]	@ (8000138 <main+0x18>)").unwrap();
+    assert_eq!(literal.references(), vec![(0x8000138, "main+0x18")]);
+
+    let (_, plain) = parse_instruction("    1004:	48 83 ec 08          	sub    $0x8,%rsp").unwrap();
+    assert!(plain.references().is_empty());
+}
+
+#[test]
+fn test_object_file_relocations() {
+    let disassembly = Disassembly::from_lines(&lines(&[
+        "Disassembly of section .text:",
+        "",
+        "0000000000000000 <main>:",
+        "   0:	48 83 ec 08          	sub    $0x8,%rsp",
+        "   4:	bf 00 00 00 00       	mov    $0x0,%edi",
+        "			5: R_X86_64_32	.rodata.str1.1",
+        "   9:	e8 00 00 00 00       	call   e <main+0xe>",
+        "			a: R_X86_64_PLT32	puts-0x4",
+        "   e:	31 c0                	xor    %eax,%eax",
+    ]));
+    let main = disassembly.find("main").unwrap();
+    assert_eq!(main.instructions.len(), 4);
+    assert!(main.instructions[0].relocations.is_empty());
+    assert_eq!(main.instructions[1].relocations[0].relocation_type, "R_X86_64_32");
+    assert_eq!(main.instructions[1].relocations[0].symbol, None);
+    let call = &main.instructions[2].relocations[0];
+    assert_eq!((call.offset, call.symbol.as_deref(), call.addend), (0xa, Some("puts"), -4));
+}
+
+#[test]
+fn test_masked_operands() {
+    let masked = |line: &str| parse_instruction(line).unwrap().1.masked_operands();
+    assert_eq!(
+        masked("    1020:	48 8d 3d d9 2f 00 00 	lea    0x2fd9(%rip),%rdi        # 4000 <log_buffer>"),
+        "(%rip),%rdi"
+    );
+    assert_eq!(masked("    1029:	e8 d2 ff ff ff       	call   1000 <vector_int_size>"), "");
+    assert_eq!(masked(" 8000104:	4b03      	ldr	r3, [pc, #12]	@ (8000114 <main+0x14>)"), "r3, [pc]");
+    assert_eq!(masked("    1004:	48 2b 07             	sub    (%rdi),%rax"), "(%rdi),%rax");
+}
@@ -0,0 +1,187 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, hex_digit1, space1},
+    combinator::{map, map_res},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair, tuple},
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// The call frame information entries of the `.eh_frame` section, as output by
+/// `objdump --dwarf=frames`
+pub struct EhFrame(Vec<FrameEntry>);
+
+/// A common information entry (CIE) shared by several functions, or a frame description entry
+/// (FDE) that describes how to unwind one function
+#[derive(Debug, PartialEq)]
+pub struct FrameEntry {
+    /// Offset of the entry from the start of the section
+    pub offset: u32,
+    /// Size of the entry in bytes, including its length field
+    pub size: u32,
+    /// Offset of the CIE that an FDE uses, or none for a CIE
+    pub cie: Option<u32>,
+    /// Code range [pc_begin, pc_end) described by an FDE
+    pub pc_begin: u32,
+    pub pc_end: u32,
+    /// Augmentation string of a CIE, e.g. "zPLR"
+    pub augmentation: String,
+    /// Augmentation data bytes, which hold the pointer encodings for a CIE and the LSDA pointer for
+    /// an FDE
+    pub augmentation_data: Vec<u8>,
+}
+
+impl EhFrame {
+    /// Creates a new empty EhFrame object
+    pub fn new() -> Self {
+        EhFrame(Vec::new())
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, FrameEntry> {
+        self.0.iter()
+    }
+
+    /// Creates the frame entries from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let eh_frame = eh_frame::EhFrame::from_file("frames.txt")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(EhFrame::from_lines(&input))
+    }
+
+    /// Creates the frame entries from lines of objdump output. Only the entries listed under the
+    /// `.eh_frame` heading are read, so the `.debug_frame` entries that objdump also prints, which
+    /// are not loaded at run time, are skipped
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut eh_frame = EhFrame::new();
+        let mut in_eh_frame = false;
+        for line in lines {
+            if let Some(heading) = line.strip_prefix("Contents of the ") {
+                in_eh_frame = heading.starts_with(".eh_frame ");
+                continue;
+            }
+            if !in_eh_frame {
+                continue;
+            }
+            if let Ok((_, entry)) = parse_frame_entry(line) {
+                eh_frame.0.push(entry);
+            } else if let Some(entry) = eh_frame.0.last_mut() {
+                let line = line.trim_start();
+                if let Some(augmentation) = line.strip_prefix("Augmentation:") {
+                    entry.augmentation = augmentation.trim().trim_matches('"').to_string();
+                } else if let Ok((_, data)) = parse_augmentation_data(line) {
+                    entry.augmentation_data = data;
+                }
+            }
+        }
+        eh_frame
+    }
+
+    /// Finds the CIE at an offset
+    pub fn find_cie(&self, offset: u32) -> Option<&FrameEntry> {
+        self.iter().find(|x| x.is_cie() && x.offset == offset)
+    }
+
+    /// Gets every FDE
+    pub fn fdes(&self) -> impl Iterator<Item = &FrameEntry> {
+        self.iter().filter(|x| !x.is_cie())
+    }
+}
+
+impl Default for EhFrame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for EhFrame {
+    type Target = Vec<FrameEntry>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FrameEntry {
+    /// Checks if the entry is a CIE rather than an FDE
+    pub fn is_cie(&self) -> bool {
+        self.cie.is_none()
+    }
+}
+
+/// Parses a hexadecimal value that fits in 32 bits
+fn parse_hex(input: &str) -> IResult<&str, u32> {
+    map_res(hex_digit1, |x| u32::from_str_radix(x, 16))(input)
+}
+
+/// Parses the length field of an entry. 64-bit objdump pads it to 16 digits
+fn parse_length(input: &str) -> IResult<&str, u32> {
+    map_res(hex_digit1, |x| u64::from_str_radix(x, 16).map(|x| x as u32))(input)
+}
+
+/// Parses the CIE offset and code range that follow "FDE", e.g. " cie=00000000 pc=10b0..10d2"
+fn parse_fde_fields(input: &str) -> IResult<&str, (u32, (u32, u32))> {
+    tuple((
+        preceded(tag(" cie="), parse_hex),
+        preceded(tag(" pc="), separated_pair(parse_hex, tag(".."), parse_hex)),
+    ))(input)
+}
+
+/// Parses the "Augmentation data:" line of an entry into its bytes
+fn parse_augmentation_data(input: &str) -> IResult<&str, Vec<u8>> {
+    preceded(
+        tuple((tag("Augmentation data:"), space1)),
+        separated_list1(char(' '), map_res(hex_digit1, |x| u8::from_str_radix(x, 16))),
+    )(input)
+}
+
+/// Parses the first line of a CIE or FDE
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::eh_frame::parse_frame_entry;
+/// let (_, entry) = parse_frame_entry(
+///     "00000018 0000000000000014 0000001c FDE cie=00000000 pc=00000000000010b0..00000000000010d2",
+/// )
+/// .unwrap();
+/// assert_eq!(entry.size, 0x18);
+/// assert_eq!(entry.cie, Some(0));
+/// assert_eq!((entry.pc_begin, entry.pc_end), (0x10b0, 0x10d2));
+/// ```
+pub fn parse_frame_entry(input: &str) -> IResult<&str, FrameEntry> {
+    let (rest, (offset, _, length, _, _, _)) =
+        tuple((parse_hex, space1, parse_length, space1, hex_digit1, space1))(input)?;
+    let (rest, fde) = alt((
+        map(tag("CIE"), |_| None),
+        map(preceded(tag("FDE"), parse_fde_fields), Some),
+    ))(rest)?;
+    let (cie, (pc_begin, pc_end)) = fde.unwrap_or((0, (0, 0)));
+    Ok((
+        rest,
+        FrameEntry {
+            offset,
+            size: length + 4,
+            cie: fde.map(|_| cie),
+            pc_begin,
+            pc_end,
+            augmentation: String::new(),
+            augmentation_data: Vec::new(),
+        },
+    ))
+}
@@ -0,0 +1,52 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_cie() {
+    let (_, entry) = parse_frame_entry("00000088 000000000000001c 00000000 CIE").unwrap();
+    assert_eq!(entry.offset, 0x88);
+    assert_eq!(entry.size, 0x20);
+    assert!(entry.is_cie());
+}
+
+#[test]
+fn test_parse_32_bit_fde() {
+    let (_, entry) = parse_frame_entry("00000010 0000000c 00000014 FDE cie=00000000 pc=08000100..08000140").unwrap();
+    assert_eq!(entry.size, 0x10);
+    assert_eq!(entry.cie, Some(0));
+    assert_eq!((entry.pc_begin, entry.pc_end), (0x08000100, 0x08000140));
+}
+
+#[test]
+fn test_parse_zero_terminator() {
+    assert!(parse_frame_entry("00000118 ZERO terminator").is_err());
+}
+
+#[test]
+fn test_eh_frame_from_lines() {
+    let eh_frame = EhFrame::from_lines(&lines(&[
+        "Contents of the .eh_frame section:",
+        "",
+        "00000088 000000000000001c 00000000 CIE",
+        "  Version:               1",
+        "  Augmentation:          \"zPLR\"",
+        "  Augmentation data:     9b 65 1f 00 00 1b 1b",
+        "  DW_CFA_def_cfa: r7 (rsp) ofs 8",
+        "",
+        "000000a8 0000000000000020 00000024 FDE cie=00000088 pc=0000000000001199..00000000000011ed",
+        "  Augmentation data:     63 00 00 00",
+        "  DW_CFA_advance_loc: 10 to 00000000000011a3",
+        "",
+        "Contents of the .debug_frame section:",
+        "",
+        "00000000 0000000000000014 ffffffff CIE",
+        "00000018 0000000000000014 00000000 FDE cie=00000000 pc=0000000000001000..0000000000001010",
+    ]));
+    assert_eq!(eh_frame.len(), 2);
+    let cie = eh_frame.find_cie(0x88).unwrap();
+    assert_eq!(cie.augmentation, "zPLR");
+    assert_eq!(cie.augmentation_data, vec![0x9b, 0x65, 0x1f, 0x00, 0x00, 0x1b, 0x1b]);
+    let fdes: Vec<&FrameEntry> = eh_frame.fdes().collect();
+    assert_eq!(fdes.len(), 1);
+    assert_eq!(fdes[0].augmentation_data, vec![0x63, 0, 0, 0]);
+}
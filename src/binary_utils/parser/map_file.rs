@@ -0,0 +1,172 @@
+use nom::{
+    bytes::complete::{tag, take_till1},
+    character::complete::{hex_digit1, multispace1, not_line_ending, space1},
+    combinator::{map_res, verify},
+    sequence::{preceded, tuple},
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// Heading that starts the placed sections of a GNU ld map file. The sections listed before it
+/// were discarded by `--gc-sections` and never made it into the image
+const MEMORY_MAP_HEADING: &str = "Linker script and memory map";
+
+/// The input sections placed in the image, as listed in a GNU ld map file (`-Wl,-Map=file.map`)
+pub struct MapFile(Vec<InputSection>);
+
+/// An input section of an object file and where the linker placed it
+#[derive(Debug, PartialEq)]
+pub struct InputSection {
+    pub section: String,
+    pub address: u32,
+    pub size: u32,
+    /// Path of the object file, or `archive.a(member.o)` for archive members
+    pub object: String,
+}
+
+impl MapFile {
+    /// Creates a new empty MapFile object
+    pub fn new() -> Self {
+        MapFile(Vec::new())
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, InputSection> {
+        self.0.iter()
+    }
+
+    /// Creates a map file from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let map_file = map_file::MapFile::from_file("firmware.map")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(MapFile::from_lines(&input))
+    }
+
+    /// Creates a map file from the lines of a GNU ld map. Only the input sections after the memory
+    /// map heading are read. The linker wraps section names that don't fit in their column, so a
+    /// line holding only a section name is joined with the line that follows it. The input sections
+    /// are sorted by address
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut map_file = MapFile::new();
+        let mut placed = false;
+        let mut wrapped: Option<&str> = None;
+        for line in lines {
+            if !placed {
+                placed = line.starts_with(MEMORY_MAP_HEADING);
+                continue;
+            }
+            if let Some(section) = wrapped.take() {
+                if let Ok((_, (address, size, object))) = parse_placement(line) {
+                    map_file.0.push(InputSection {
+                        section: section.to_string(),
+                        address,
+                        size,
+                        object: object.to_string(),
+                    });
+                    continue;
+                }
+            }
+            if let Ok((_, entry)) = parse_input_section(line) {
+                map_file.0.push(entry);
+            } else if let Ok((rest, section)) = parse_section_name(line) {
+                if rest.trim().is_empty() {
+                    wrapped = Some(section);
+                }
+            }
+        }
+        map_file.0.sort_by_key(|x| x.address);
+        map_file
+    }
+
+    /// Finds the input section that holds an address. Empty sections are never matched
+    pub fn find(&self, address: u32) -> Option<&InputSection> {
+        // Input sections don't overlap, so only the last non-empty one starting at or before the
+        // address can hold it
+        let end = self.0.partition_point(|x| x.address <= address);
+        self.0[..end]
+            .iter()
+            .rev()
+            .find(|x| x.size > 0)
+            .filter(|x| (address as u64) < x.address as u64 + x.size as u64)
+    }
+}
+
+impl Default for MapFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for MapFile {
+    type Target = Vec<InputSection>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Parses a `0x` prefixed hexadecimal value, failing on values that don't fit in 32 bits
+fn parse_hex(input: &str) -> IResult<&str, u32> {
+    preceded(tag("0x"), map_res(hex_digit1, |x| u32::from_str_radix(x, 16)))(input)
+}
+
+/// Parses the indented name that starts an input section line. Output sections start in the first
+/// column, and the linker script patterns and fill lines start with `*`
+fn parse_section_name(input: &str) -> IResult<&str, &str> {
+    preceded(
+        tag(" "),
+        verify(take_till1(char::is_whitespace), |x: &str| !x.starts_with('*')),
+    )(input)
+}
+
+/// Parses the address, size and object file that follow an input section name
+fn parse_placement(input: &str) -> IResult<&str, (u32, u32, &str)> {
+    let (rest, (_, address, _, size, _, object)) =
+        tuple((multispace1, parse_hex, space1, parse_hex, space1, not_line_ending))(input)?;
+    let object = object.trim();
+    if object.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Eof,
+        }));
+    }
+    Ok((rest, (address, size, object)))
+}
+
+/// Parses a single input section line of a map file
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::map_file::parse_input_section;
+/// let (_, entry) = parse_input_section(" .text.main     0x08000110       0x30 app/main.o").unwrap();
+/// assert_eq!(entry.section, ".text.main");
+/// assert_eq!(entry.address, 0x08000110);
+/// assert_eq!(entry.object, "app/main.o");
+/// ```
+pub fn parse_input_section(input: &str) -> IResult<&str, InputSection> {
+    let (rest, section) = parse_section_name(input)?;
+    let (rest, (address, size, object)) = parse_placement(rest)?;
+    Ok((
+        rest,
+        InputSection {
+            section: section.to_string(),
+            address,
+            size,
+            object: object.to_string(),
+        },
+    ))
+}
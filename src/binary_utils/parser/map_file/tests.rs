@@ -0,0 +1,83 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_input_section() {
+    let (_, entry) = parse_input_section(
+        " .text.main     0x0000000000401127       0x13 app/main.o",
+    )
+    .unwrap();
+    assert_eq!(
+        entry,
+        InputSection {
+            section: ".text.main".to_string(),
+            address: 0x401127,
+            size: 0x13,
+            object: "app/main.o".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_parse_input_section_from_archive() {
+    let (_, entry) = parse_input_section(
+        " .text          0x08000404        0x8 /opt/gcc/lib/libc_nano.a(memcpy.o)",
+    )
+    .unwrap();
+    assert_eq!(entry.object, "/opt/gcc/lib/libc_nano.a(memcpy.o)");
+}
+
+#[test]
+fn test_parse_input_section_ignores_other_lines() {
+    assert!(parse_input_section(".text           0x08000100      0x30c").is_err());
+    assert!(parse_input_section(" *fill*         0x08000140       0xc0 ").is_err());
+    assert!(parse_input_section(" *(.text .text.*)").is_err());
+    assert!(parse_input_section("                0x08000110                main").is_err());
+    assert!(parse_input_section("LOAD app/main.o").is_err());
+}
+
+#[test]
+fn test_map_file_from_lines() {
+    let map_file = MapFile::from_lines(&lines(&[
+        "Discarded input sections",
+        "",
+        " .text.unused   0x00000000       0x18 app/main.o",
+        "",
+        "Linker script and memory map",
+        "",
+        ".text           0x08000100       0x40",
+        " *(.text .text.*)",
+        " .text.main     0x08000100       0x30 app/main.o",
+        "                0x08000100                main",
+        " .text.HAL_UART_Transmit",
+        "                0x08000130       0x10 drivers/libhal.a(stm32_hal_uart.o)",
+        " *fill*         0x08000140        0x4 ",
+    ]));
+    let sections: Vec<(&str, u32, &str)> = map_file
+        .iter()
+        .map(|x| (x.section.as_str(), x.address, x.object.as_str()))
+        .collect();
+    assert_eq!(
+        sections,
+        vec![
+            (".text.main", 0x08000100, "app/main.o"),
+            (".text.HAL_UART_Transmit", 0x08000130, "drivers/libhal.a(stm32_hal_uart.o)"),
+        ]
+    );
+}
+
+#[test]
+fn test_find() {
+    let map_file = MapFile::from_lines(&lines(&[
+        "Linker script and memory map",
+        " .text          0x08000100        0x0 app/empty.o",
+        " .text          0x08000100       0x30 app/main.o",
+        " .text          0x08000000       0x80 app/startup.o",
+    ]));
+    assert_eq!(map_file.iter().next().map(|x| x.object.as_str()), Some("app/startup.o"));
+    assert_eq!(map_file.find(0x0800007f).map(|x| x.object.as_str()), Some("app/startup.o"));
+    assert!(map_file.find(0x08000080).is_none());
+    assert_eq!(map_file.find(0x08000100).map(|x| x.object.as_str()), Some("app/main.o"));
+    assert_eq!(map_file.find(0x0800012f).map(|x| x.object.as_str()), Some("app/main.o"));
+    assert!(map_file.find(0x08000130).is_none());
+}
@@ -0,0 +1,218 @@
+use nom::{
+    bytes::complete::{tag, take_till1, take_until},
+    character::complete::{hex_digit1, space0, space1},
+    combinator::{map_res, rest},
+    sequence::{delimited, tuple},
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// Heading of the dynamic relocations printed by `objdump -R`
+const DYNAMIC_HEADING: &str = "DYNAMIC RELOCATION RECORDS";
+
+/// The relocation records of a binary, as output by `objdump -R` for the dynamic relocations of a
+/// shared library or PIE executable, or by `objdump -r` for the relocations of an object file
+pub struct Relocations(Vec<Relocation>);
+
+/// What a relocation makes the dynamic linker or static linker do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RelocationKind {
+    /// Adds the load address to a value, without looking up a symbol
+    Relative,
+    /// Calls a resolver function to pick an implementation at load time
+    IRelative,
+    /// Fills a PLT slot in the GOT, looked up on the first call unless binding immediately
+    JumpSlot,
+    /// Fills a GOT entry with the address of a symbol
+    GlobalData,
+    /// Copies a shared library's variable into the executable
+    Copy,
+    /// Fills in a thread local storage module or offset
+    ThreadLocal,
+    /// Any other relocation against a symbol, e.g. an absolute pointer
+    Symbolic,
+}
+
+/// A single relocation record
+#[derive(Debug, PartialEq)]
+pub struct Relocation {
+    /// Address, or section offset for object files, of the value to relocate
+    pub offset: u32,
+    /// Type name of the relocation, e.g. "R_X86_64_JUMP_SLOT"
+    pub relocation_type: String,
+    /// The symbol the relocation refers to, without its version. Relocations that are relative to
+    /// the load address or to a section have no symbol
+    pub symbol: Option<String>,
+    /// Symbol version, e.g. "GLIBC_2.2.5"
+    pub version: Option<String>,
+    pub addend: i64,
+    /// Section that an object file relocation applies to, or none for dynamic relocations
+    pub section: Option<String>,
+}
+
+impl Relocations {
+    /// Creates a new empty Relocations object
+    pub fn new() -> Self {
+        Relocations(Vec::new())
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Relocation> {
+        self.0.iter()
+    }
+
+    /// Creates the relocations from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let relocations = relocations::Relocations::from_file("libfoo.txt")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(Relocations::from_lines(&input))
+    }
+
+    /// Creates the relocations from lines of objdump output. Records are read under either the
+    /// dynamic relocation heading or a "RELOCATION RECORDS FOR [section]" heading, and a blank line
+    /// ends each list
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut relocations = Relocations::new();
+        let mut list: Option<Option<String>> = None;
+        for line in lines {
+            if line.starts_with(DYNAMIC_HEADING) {
+                list = Some(None);
+            } else if let Ok((_, section)) = parse_relocation_heading(line) {
+                list = Some(Some(section.to_string()));
+            } else if line.trim().is_empty() {
+                list = None;
+            } else if let Some(section) = &list {
+                if let Ok((_, mut relocation)) = parse_relocation(line) {
+                    relocation.section = section.clone();
+                    relocations.0.push(relocation);
+                }
+            }
+        }
+        relocations
+    }
+
+    /// Gets the relocations that the dynamic linker processes when the binary is loaded
+    pub fn dynamic(&self) -> impl Iterator<Item = &Relocation> {
+        self.iter().filter(|x| x.is_dynamic())
+    }
+}
+
+impl Default for Relocations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for Relocations {
+    type Target = Vec<Relocation>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Relocation {
+    /// Checks if the relocation is processed by the dynamic linker
+    pub fn is_dynamic(&self) -> bool {
+        self.section.is_none()
+    }
+
+    /// Classifies the relocation by its type name, which works across architectures since they
+    /// share the same suffixes. Object file relocations that go through the PLT or GOT are
+    /// classified like the dynamic relocations the linker creates for them
+    pub fn kind(&self) -> RelocationKind {
+        let name = self.relocation_type.as_str();
+        if name.ends_with("IRELATIVE") {
+            RelocationKind::IRelative
+        } else if name.ends_with("RELATIVE") {
+            RelocationKind::Relative
+        } else if name.ends_with("JUMP_SLOT") || name.ends_with("JMP_SLOT") || name.contains("PLT") {
+            RelocationKind::JumpSlot
+        } else if name.ends_with("GLOB_DAT") || name.contains("GOT") {
+            RelocationKind::GlobalData
+        } else if name.ends_with("COPY") {
+            RelocationKind::Copy
+        } else if ["TPOFF", "DTPMOD", "DTPOFF", "TLS"].iter().any(|x| name.contains(x)) {
+            RelocationKind::ThreadLocal
+        } else {
+            RelocationKind::Symbolic
+        }
+    }
+}
+
+/// Parses the heading of an object file's relocations, e.g. "RELOCATION RECORDS FOR [.text]:"
+fn parse_relocation_heading(input: &str) -> IResult<&str, &str> {
+    delimited(tag("RELOCATION RECORDS FOR ["), take_until("]"), tag("]:"))(input)
+}
+
+/// Splits a relocation value into its symbol, version and addend, e.g. "counter@@Base",
+/// "names-0x0000000000000004" or "*ABS*+0x0000000000001130"
+fn parse_value(value: &str) -> (Option<String>, Option<String>, i64) {
+    let (name, addend) = match value.rfind(['+', '-']).filter(|&i| value[i + 1..].starts_with("0x")) {
+        Some(i) => {
+            let addend = i64::from_str_radix(&value[i + 3..], 16).unwrap_or(0);
+            (&value[..i], if value[i..].starts_with('-') { -addend } else { addend })
+        }
+        None => (value, 0),
+    };
+    let (name, version) = match name.find('@') {
+        Some(i) => (&name[..i], Some(name[i..].trim_start_matches('@').to_string())),
+        None => (name, None),
+    };
+    // Absolute values, sections and local labels like .LC0 aren't symbols
+    let symbol = Some(name).filter(|x| !x.is_empty() && *x != "*ABS*" && !x.starts_with('.'));
+    (symbol.map(String::from), version, addend)
+}
+
+/// Parses a single relocation record
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::relocations::parse_relocation;
+/// let (_, relocation) = parse_relocation("0000000000004000 R_X86_64_JUMP_SLOT  strlen@GLIBC_2.2.5").unwrap();
+/// assert_eq!(relocation.offset, 0x4000);
+/// assert_eq!(relocation.relocation_type, "R_X86_64_JUMP_SLOT");
+/// assert_eq!(relocation.symbol.as_deref(), Some("strlen"));
+/// assert_eq!(relocation.version.as_deref(), Some("GLIBC_2.2.5"));
+/// ```
+pub fn parse_relocation(input: &str) -> IResult<&str, Relocation> {
+    let (remaining, (offset, _, relocation_type, _, value)) = tuple((
+        map_res(hex_digit1, |x| u64::from_str_radix(x, 16).map(|x| x as u32)),
+        space1,
+        take_till1(char::is_whitespace),
+        space0,
+        rest,
+    ))(input)?;
+    if !relocation_type.starts_with("R_") {
+        return Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    }
+    let (symbol, version, addend) = parse_value(value.trim());
+    Ok((
+        remaining,
+        Relocation {
+            offset,
+            relocation_type: relocation_type.to_string(),
+            symbol,
+            version,
+            addend,
+            section: None,
+        },
+    ))
+}
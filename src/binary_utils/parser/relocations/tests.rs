@@ -0,0 +1,89 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_relocation() {
+    let (_, relocation) = parse_relocation("0000000000003fc8 R_X86_64_GLOB_DAT  counter@@Base").unwrap();
+    assert_eq!(
+        relocation,
+        Relocation {
+            offset: 0x3fc8,
+            relocation_type: "R_X86_64_GLOB_DAT".to_string(),
+            symbol: Some("counter".to_string()),
+            version: Some("Base".to_string()),
+            addend: 0,
+            section: None,
+        }
+    );
+    assert_eq!(relocation.kind(), RelocationKind::GlobalData);
+}
+
+#[test]
+fn test_parse_relocation_addends() {
+    let (_, relative) = parse_relocation("0000000000003de8 R_X86_64_RELATIVE  *ABS*+0x0000000000001130").unwrap();
+    assert_eq!((relative.symbol.as_deref(), relative.addend), (None, 0x1130));
+    assert_eq!(relative.kind(), RelocationKind::Relative);
+
+    let (_, call) = parse_relocation("0000000000000041 R_X86_64_PLT32    helper-0x0000000000000004").unwrap();
+    assert_eq!((call.symbol.as_deref(), call.addend), (Some("helper"), -4));
+    assert_eq!(call.kind(), RelocationKind::JumpSlot);
+
+    let (_, data) = parse_relocation("00000000 R_ARM_ABS32       .rodata").unwrap();
+    assert_eq!((data.symbol.as_deref(), data.addend), (None, 0));
+    assert_eq!(data.kind(), RelocationKind::Symbolic);
+
+    // ARM prints relative relocations without a value
+    let (_, arm) = parse_relocation("00010f0c R_ARM_RELATIVE    ").unwrap();
+    assert_eq!(arm.symbol, None);
+    assert_eq!(arm.kind(), RelocationKind::Relative);
+
+    assert!(parse_relocation("OFFSET           TYPE              VALUE").is_err());
+    assert!(parse_relocation("0000000000001146 g     F .text	0000000000000051 api_entry").is_err());
+}
+
+#[test]
+fn test_relocation_kinds() {
+    let kind = |name: &str| parse_relocation(&format!("00000000 {} foo", name)).unwrap().1.kind();
+    assert_eq!(kind("R_X86_64_IRELATIVE"), RelocationKind::IRelative);
+    assert_eq!(kind("R_ARM_JUMP_SLOT"), RelocationKind::JumpSlot);
+    assert_eq!(kind("R_386_JMP_SLOT"), RelocationKind::JumpSlot);
+    assert_eq!(kind("R_X86_64_REX_GOTPCRELX"), RelocationKind::GlobalData);
+    assert_eq!(kind("R_X86_64_COPY"), RelocationKind::Copy);
+    assert_eq!(kind("R_X86_64_DTPMOD64"), RelocationKind::ThreadLocal);
+    assert_eq!(kind("R_X86_64_64"), RelocationKind::Symbolic);
+}
+
+#[test]
+fn test_relocations_from_lines() {
+    let relocations = Relocations::from_lines(&lines(&[
+        "RELOCATION RECORDS FOR [.text]:",
+        "OFFSET           TYPE              VALUE",
+        "0000000000000010 R_X86_64_REX_GOTPCRELX  counter-0x0000000000000004",
+        "0000000000000041 R_X86_64_PLT32    helper-0x0000000000000004",
+        "",
+        "",
+        "RELOCATION RECORDS FOR [.data.rel.local]:",
+        "OFFSET           TYPE              VALUE",
+        "0000000000000000 R_X86_64_64       .rodata",
+        "",
+        "0000000000001146 g     F .text	0000000000000051 api_entry",
+        "0000000000000000 R_X86_64_64       ignored",
+        "DYNAMIC RELOCATION RECORDS",
+        "OFFSET           TYPE              VALUE",
+        "0000000000004000 R_X86_64_JUMP_SLOT  strlen@GLIBC_2.2.5",
+    ]));
+    let records: Vec<(u32, Option<&str>, Option<&str>)> = relocations
+        .iter()
+        .map(|x| (x.offset, x.symbol.as_deref(), x.section.as_deref()))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (0x10, Some("counter"), Some(".text")),
+            (0x41, Some("helper"), Some(".text")),
+            (0x0, None, Some(".data.rel.local")),
+            (0x4000, Some("strlen"), None),
+        ]
+    );
+    assert_eq!(relocations.dynamic().count(), 1);
+}
@@ -0,0 +1,225 @@
+use nom::{
+    bytes::complete::{tag, take_until},
+    character::complete::{char, hex_digit1},
+    multi::separated_list1,
+    sequence::tuple,
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use super::section_table::parse_file_format;
+use super::symbol_table::parse_u32;
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// The raw bytes of a single section
+#[derive(Debug, PartialEq)]
+pub struct SectionData {
+    pub name: String,
+    pub address: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// The contents of a binary's sections, as output by `objdump -s`
+pub struct SectionContents {
+    /// The file format from the objdump header, e.g. "elf32-littlearm"
+    pub format: Option<String>,
+    sections: Vec<SectionData>,
+}
+
+impl SectionContents {
+    /// Creates a new empty SectionContents object
+    pub fn new() -> Self {
+        SectionContents {
+            format: None,
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, SectionData> {
+        self.sections.iter()
+    }
+
+    /// Creates section contents from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let contents = section_contents::SectionContents::from_file("binary.txt")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(SectionContents::from_lines(&input))
+    }
+
+    /// Creates section contents from lines of objdump output. Data lines are collected under the
+    /// "Contents of section" line that precedes them, and any other lines are ignored
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut contents = SectionContents::new();
+        let mut in_section = false;
+        for line in lines {
+            if contents.format.is_none() {
+                if let Ok((_, (_, format))) = parse_file_format(line) {
+                    contents.format = Some(format.to_string());
+                    continue;
+                }
+            }
+            if let Ok((_, name)) = parse_contents_header(line) {
+                contents.sections.push(SectionData {
+                    name: name.to_string(),
+                    address: 0,
+                    bytes: Vec::new(),
+                });
+                in_section = true;
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            match (parse_contents_line(line), contents.sections.last_mut()) {
+                (Ok((_, (address, bytes))), Some(section)) => {
+                    if section.bytes.is_empty() {
+                        section.address = address;
+                    }
+                    section.bytes.extend(bytes);
+                }
+                _ => in_section = false,
+            }
+        }
+        contents
+    }
+
+    /// Finds a section by name
+    pub fn find(&self, name: &str) -> Option<&SectionData> {
+        self.iter().find(|x| x.name == name)
+    }
+
+    /// Gets the size of a pointer in bytes, based on the file format
+    pub fn pointer_size(&self) -> usize {
+        match &self.format {
+            Some(format) if format.contains("64") => 8,
+            _ => 4,
+        }
+    }
+
+    /// Checks if the binary stores values with the most significant byte first, based on the file format
+    pub fn is_big_endian(&self) -> bool {
+        self.format.as_deref().is_some_and(|x| x.contains("big"))
+    }
+
+    /// Reads every pointer sized word in an address range [start, end) that lies inside a section,
+    /// along with the address it was read from. Words are read at pointer aligned offsets from the
+    /// start of the range
+    pub fn words_in(&self, start: u32, end: u64) -> Vec<(u32, u64)> {
+        let size = self.pointer_size();
+        let mut words = Vec::new();
+        for section in self.iter() {
+            let section_end = section.address as u64 + section.bytes.len() as u64;
+            if (start as u64) < section.address as u64 || start as u64 >= section_end {
+                continue;
+            }
+            let offset = (start - section.address) as usize;
+            let end = end.min(section_end) as usize - section.address as usize;
+            for (i, chunk) in section.bytes[offset..end].chunks_exact(size).enumerate() {
+                let value = if self.is_big_endian() {
+                    chunk.iter().fold(0u64, |value, &x| (value << 8) | x as u64)
+                } else {
+                    chunk.iter().rev().fold(0u64, |value, &x| (value << 8) | x as u64)
+                };
+                words.push((start + (i * size) as u32, value));
+            }
+        }
+        words
+    }
+
+    /// Reads every pointer sized value in an address range [start, end) that lies inside a
+    /// section. Values that don't fit in 32 bits are skipped
+    pub fn pointers_in(&self, start: u32, end: u64) -> Vec<u32> {
+        self.words_in(start, end)
+            .into_iter()
+            .filter(|&(_, value)| value <= u32::MAX as u64)
+            .map(|(_, value)| value as u32)
+            .collect()
+    }
+
+    /// Reads every pointer sized word in a section along with its address, e.g. the slots of an
+    /// `.init_array`
+    pub fn words_in_section(&self, name: &str) -> Vec<(u32, u64)> {
+        match self.find(name) {
+            Some(section) => self.words_in(section.address, section.address as u64 + section.bytes.len() as u64),
+            None => Vec::new(),
+        }
+    }
+
+    /// Reads every pointer sized value in a section, e.g. the handlers of a vector table
+    pub fn pointers_in_section(&self, name: &str) -> Vec<u32> {
+        match self.find(name) {
+            Some(section) => self.pointers_in(section.address, section.address as u64 + section.bytes.len() as u64),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Default for SectionContents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for SectionContents {
+    type Target = Vec<SectionData>;
+    fn deref(&self) -> &Self::Target {
+        &self.sections
+    }
+}
+
+/// Parses the line that starts each section's contents, e.g. "Contents of section .data:"
+fn parse_contents_header(input: &str) -> IResult<&str, &str> {
+    let (rest, (_, name, _)) = tuple((tag("Contents of section "), take_until(":"), char(':')))(input)?;
+    Ok((rest, name))
+}
+
+/// Parses a line of section contents. Returns the address and bytes of the line if successful
+///
+/// # Arguments
+/// * 'input' - The input string to parse, e.g. " 3dd0 40110000 00000000                    @......."
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::section_contents::parse_contents_line;
+/// let (_, (address, bytes)) = parse_contents_line(" 3dd0 40110000 00000000                    @.......").unwrap();
+/// assert_eq!(address, 0x3dd0);
+/// assert_eq!(bytes, vec![0x40, 0x11, 0, 0, 0, 0, 0, 0]);
+/// ```
+pub fn parse_contents_line(input: &str) -> IResult<&str, (u32, Vec<u8>)> {
+    let (rest, (_, address, _, groups)) =
+        tuple((char(' '), parse_u32, char(' '), separated_list1(char(' '), hex_digit1)))(input)?;
+    // The hex dump is followed by at least two spaces and the same bytes as text
+    if !rest.is_empty() && !rest.starts_with("  ") {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: rest,
+            code: nom::error::ErrorKind::Space,
+        }));
+    }
+    let mut bytes = Vec::new();
+    for group in groups {
+        if group.len() % 2 != 0 {
+            return Err(nom::Err::Error(nom::error::Error {
+                input: group,
+                code: nom::error::ErrorKind::HexDigit,
+            }));
+        }
+        for i in (0..group.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(&group[i..i + 2], 16).unwrap());
+        }
+    }
+    Ok((rest, (address, bytes)))
+}
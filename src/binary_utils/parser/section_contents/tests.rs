@@ -0,0 +1,75 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_contents_line() {
+    assert_eq!(
+        parse_contents_line(" 2000 01000200 25640a00                    ....%d..        "),
+        Ok(("                    ....%d..        ", (0x2000, vec![1, 0, 2, 0, 0x25, 0x64, 0x0a, 0])))
+    );
+    // Text that looks like hex digits must not be read as bytes
+    let (_, (_, bytes)) = parse_contents_line(" 4010 00000000 00000000 18400000 00000000  .........@......").unwrap();
+    assert_eq!(bytes.len(), 16);
+    assert!(parse_contents_line("  0 .interp       0000001c  0000000000000318  0000000000000318  00000318  2**0").is_err());
+    assert!(parse_contents_line("    1004:	48 8b 05 c5 2f 00 00 	mov    0x2fc5(%rip),%rax").is_err());
+}
+
+#[test]
+fn test_section_contents_from_lines() {
+    let contents = SectionContents::from_lines(&lines(&[
+        "firmware.elf:     file format elf32-littlearm",
+        "",
+        "Contents of section .isr_vector:",
+        " 8000000 00500020 c1010008 c5010008 c5010008  .P. ............",
+        " 8000010 c7010008                             ....            ",
+        "Contents of section .data:",
+        " 20000000 2a000000                             *...            ",
+        "",
+        "SYMBOL TABLE:",
+        " 8000000 not contents",
+    ]));
+    assert_eq!(contents.len(), 2);
+    assert_eq!(contents.pointer_size(), 4);
+    assert!(!contents.is_big_endian());
+    assert_eq!(
+        contents.pointers_in_section(".isr_vector"),
+        vec![0x20005000, 0x080001c1, 0x080001c5, 0x080001c5, 0x080001c7]
+    );
+    assert_eq!(contents.pointers_in(0x08000004, 0x0800000c), vec![0x080001c1, 0x080001c5]);
+    assert_eq!(contents.find(".data").unwrap().bytes, vec![0x2a, 0, 0, 0]);
+}
+
+#[test]
+fn test_big_endian_and_64_bit_pointers() {
+    let contents = SectionContents::from_lines(&lines(&[
+        "app:     file format elf32-bigarm",
+        "Contents of section .init_array:",
+        " 10000 00008001                             ....            ",
+    ]));
+    assert_eq!(contents.pointers_in_section(".init_array"), vec![0x8001]);
+
+    let contents = SectionContents::from_lines(&lines(&[
+        "app:     file format elf64-x86-64",
+        "Contents of section .init_array:",
+        " 3dd0 40110000 00000000                    @.......        ",
+    ]));
+    assert_eq!(contents.pointers_in_section(".init_array"), vec![0x1140]);
+
+    let contents = SectionContents::from_lines(&lines(&[
+        "app:     file format elf64-x86-64",
+        "Contents of section .ctors:",
+        " 3dd0 ffffffff ffffffff 40110000 00000000  ........@.......",
+    ]));
+    assert_eq!(contents.words_in_section(".ctors"), vec![(0x3dd0, u64::MAX), (0x3dd8, 0x1140)]);
+    assert_eq!(contents.pointers_in_section(".ctors"), vec![0x1140]);
+}
+
+#[test]
+fn test_section_at_end_of_address_space() {
+    let contents = SectionContents::from_lines(&lines(&[
+        "app:     file format elf32-littlearm",
+        "Contents of section .init_array:",
+        " fffffff8 01000000 02000000                    ........        ",
+    ]));
+    assert_eq!(contents.words_in_section(".init_array"), vec![(0xfffffff8, 1), (0xfffffffc, 2)]);
+}
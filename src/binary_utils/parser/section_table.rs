@@ -0,0 +1,258 @@
+use nom::{
+    bytes::complete::{tag, take_till1, take_until},
+    character::complete::{digit1, multispace0, multispace1, not_line_ending},
+    combinator::{map_opt, map_res},
+    multi::separated_list1,
+    sequence::tuple,
+    IResult,
+};
+
+use std::{io, path::Path, slice};
+
+use super::symbol_table::parse_u32;
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// The section headers of a binary, as output by `objdump -h`
+pub struct SectionTable(Vec<SectionTableEntry>);
+
+impl SectionTable {
+    /// Creates a new empty SectionTable object
+    pub fn new() -> Self {
+        SectionTable(Vec::new())
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, SectionTableEntry> {
+        self.0.iter()
+    }
+
+    /// Creates a section table from a file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let section_table = section_table::SectionTable::from_file("binary.txt")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(SectionTable::from_lines(&input))
+    }
+
+    /// Creates a section table from lines of objdump output. Each section header line is followed
+    /// by a line with the section's flags, and any other lines are ignored
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut table = SectionTable::new();
+        let mut expecting_flags = false;
+        for line in lines {
+            if let Ok((_, entry)) = parse_section_table_entry(line) {
+                table.0.push(entry);
+                expecting_flags = true;
+            } else if expecting_flags {
+                if let Ok((_, flags)) = parse_section_flags(line) {
+                    if let Some(entry) = table.0.last_mut() {
+                        entry.flags = flags;
+                    }
+                }
+                expecting_flags = false;
+            }
+        }
+        table
+    }
+
+    /// Finds a section by name
+    pub fn find(&self, name: &str) -> Option<&SectionTableEntry> {
+        self.iter().find(|x| x.name == name)
+    }
+}
+
+impl Default for SectionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for SectionTable {
+    type Target = Vec<SectionTableEntry>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SectionTableEntry {
+    pub index: u32,
+    pub name: String,
+    pub size: u32,
+    pub vma: u32,
+    pub lma: u32,
+    pub file_offset: u32,
+    pub alignment: u32,
+    pub flags: Vec<SectionFlag>,
+}
+
+impl SectionTableEntry {
+    /// Checks if the section occupies memory when the program is loaded
+    pub fn is_allocated(&self) -> bool {
+        self.flags.contains(&SectionFlag::Alloc)
+    }
+
+    /// Checks if the section holds executable code
+    pub fn is_code(&self) -> bool {
+        self.flags.contains(&SectionFlag::Code)
+    }
+
+    /// Checks if the section is read only
+    pub fn is_readonly(&self) -> bool {
+        self.flags.contains(&SectionFlag::Readonly)
+    }
+
+    /// Checks if the section has contents stored in the file, which is false for zero-initialized sections
+    pub fn has_contents(&self) -> bool {
+        self.flags.contains(&SectionFlag::Contents)
+    }
+
+    /// Checks if the section holds the template of the thread local storage block
+    pub fn is_thread_local(&self) -> bool {
+        self.flags.contains(&SectionFlag::ThreadLocal)
+    }
+
+    /// Checks if the address is inside the section
+    pub fn contains(&self, address: u32) -> bool {
+        address >= self.vma && (address as u64) < self.vma as u64 + self.size as u64
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SectionFlag {
+    Contents,
+    Alloc,
+    Load,
+    Reloc,
+    Readonly,
+    Code,
+    Data,
+    Rom,
+    Debugging,
+    ThreadLocal,
+    Other(String),
+}
+
+/// Parses a single section flag name
+fn parse_section_flag(input: &str) -> IResult<&str, SectionFlag> {
+    let (rest, name) = take_till1(|c: char| c == ',' || c.is_whitespace())(input)?;
+    if !name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+        return Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Char,
+        }));
+    }
+    let flag = match name {
+        "CONTENTS" => SectionFlag::Contents,
+        "ALLOC" => SectionFlag::Alloc,
+        "LOAD" => SectionFlag::Load,
+        "RELOC" => SectionFlag::Reloc,
+        "READONLY" => SectionFlag::Readonly,
+        "CODE" => SectionFlag::Code,
+        "DATA" => SectionFlag::Data,
+        "ROM" => SectionFlag::Rom,
+        "DEBUGGING" => SectionFlag::Debugging,
+        "THREAD_LOCAL" => SectionFlag::ThreadLocal,
+        other => SectionFlag::Other(other.to_string()),
+    };
+    Ok((rest, flag))
+}
+
+/// Parses the comma separated flags line that follows each section header
+fn parse_section_flags(input: &str) -> IResult<&str, Vec<SectionFlag>> {
+    let (rest, (_, flags, _)) = tuple((
+        multispace1,
+        separated_list1(tag(", "), parse_section_flag),
+        multispace0,
+    ))(input)?;
+    if !rest.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: rest,
+            code: nom::error::ErrorKind::Eof,
+        }));
+    }
+    Ok((rest, flags))
+}
+
+/// Parses a section header line. Returns a results type containing the parsed result if successful
+///
+/// # Arguments
+/// * 'input' - The input string to parse the section header from
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::section_table::parse_section_table_entry;
+/// let line = "  0 .interp       0000001c  0000000000000318  0000000000000318  00000318  2**0";
+/// let (_, entry) = parse_section_table_entry(line).unwrap();
+/// assert_eq!(entry.name, ".interp");
+/// assert_eq!(entry.size, 0x1c);
+/// ```
+pub fn parse_section_table_entry(input: &str) -> IResult<&str, SectionTableEntry> {
+    let mut parser = tuple((
+        multispace0,
+        map_res(digit1, str::parse),
+        multispace1,
+        take_till1(|c: char| c.is_whitespace()),
+        multispace1,
+        parse_u32,
+        multispace1,
+        parse_u32,
+        multispace1,
+        parse_u32,
+        multispace1,
+        parse_u32,
+        multispace1,
+        tag("2**"),
+        map_opt(digit1, |x: &str| x.parse().ok().and_then(|x| 1u32.checked_shl(x))),
+        multispace0,
+    ));
+    let (rest, result) = parser(input)?;
+    let (_, index, _, name, _, size, _, vma, _, lma, _, file_offset, _, _, alignment, _) = result;
+    Ok((
+        rest,
+        SectionTableEntry {
+            index,
+            name: name.to_string(),
+            size,
+            vma,
+            lma,
+            file_offset,
+            alignment,
+            flags: Vec::new(),
+        },
+    ))
+}
+
+/// Parses the header line objdump prints before its output. Returns the filename and file format
+///
+/// # Arguments
+/// * 'input' - The input string to parse, e.g. "hello_world:     file format elf64-x86-64"
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::section_table::parse_file_format;
+/// let (_, (filename, format)) = parse_file_format("hello_world:     file format elf64-x86-64").unwrap();
+/// assert_eq!(filename, "hello_world");
+/// assert_eq!(format, "elf64-x86-64");
+/// ```
+pub fn parse_file_format(input: &str) -> IResult<&str, (&str, &str)> {
+    let (rest, (filename, _, _, _, format)) = tuple((
+        take_until(":"),
+        tag(":"),
+        multispace1,
+        tag("file format "),
+        not_line_ending,
+    ))(input)?;
+    Ok((rest, (filename, format.trim_end())))
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+#[test]
+fn test_parse_section_table_entry() {
+    let (_, entry) = parse_section_table_entry(
+        "  0 .interp       0000001c  0000000000000318  0000000000000318  00000318  2**0",
+    )
+    .unwrap();
+    assert_eq!(
+        entry,
+        SectionTableEntry {
+            index: 0,
+            name: ".interp".to_string(),
+            size: 0x1c,
+            vma: 0x318,
+            lma: 0x318,
+            file_offset: 0x318,
+            alignment: 1,
+            flags: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_parse_section_table_entry_with_long_name() {
+    let (_, entry) = parse_section_table_entry(
+        "  1 .note.gnu.property 00000020  0000000000000338  0000000000000338  00000338  2**3",
+    )
+    .unwrap();
+    assert_eq!(entry.name, ".note.gnu.property");
+    assert_eq!(entry.size, 0x20);
+    assert_eq!(entry.alignment, 8);
+}
+
+#[test]
+fn test_parse_symbol_table_line_is_not_a_section() {
+    assert!(parse_section_table_entry(
+        "0000000000000318 l    d  .interp	0000000000000000              .interp"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_parse_section_table_entry_out_of_range_is_an_error() {
+    assert!(parse_section_table_entry(
+        "  0 .big          100000000  0000000000000318  0000000000000318  00000318  2**0"
+    )
+    .is_err());
+    assert!(parse_section_table_entry(
+        "  0 .interp       0000001c  0000000000000318  0000000000000318  00000318  2**32"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_parse_section_flags() {
+    assert_eq!(
+        parse_section_flags("                  CONTENTS, ALLOC, LOAD, READONLY, CODE"),
+        Ok((
+            "",
+            vec![
+                SectionFlag::Contents,
+                SectionFlag::Alloc,
+                SectionFlag::Load,
+                SectionFlag::Readonly,
+                SectionFlag::Code
+            ]
+        ))
+    );
+    assert_eq!(
+        parse_section_flags("                  ALLOC, THREAD_LOCAL"),
+        Ok(("", vec![SectionFlag::Alloc, SectionFlag::ThreadLocal]))
+    );
+    assert!(parse_section_flags("    1000:	48 83 ec 08          	sub    $0x8,%rsp").is_err());
+}
+
+#[test]
+fn test_section_table_from_lines() {
+    let lines: Vec<String> = vec![
+        "Idx Name          Size      VMA               LMA               File off  Algn",
+        " 25 .bss          00000408  0000000000004020  0000000000004020  00003020  2**5",
+        "                  ALLOC",
+        " 26 .comment      00000027  0000000000000000  0000000000000000  00003020  2**0",
+        "                  CONTENTS, READONLY",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let table = SectionTable::from_lines(&lines);
+    assert_eq!(table.len(), 2);
+    let bss = table.find(".bss").unwrap();
+    assert!(bss.is_allocated());
+    assert!(!bss.has_contents());
+    assert!(bss.contains(0x4427));
+    assert!(!bss.contains(0x4428));
+    assert!(!table.find(".comment").unwrap().is_allocated());
+}
@@ -0,0 +1,206 @@
+use nom::{
+    bytes::complete::{tag, take_until},
+    character::complete::{char, digit1, not_line_ending},
+    sequence::tuple,
+    IResult,
+};
+
+use serde::Serialize;
+
+use std::{io, path::Path, slice};
+
+use crate::string_utils;
+
+#[cfg(test)]
+mod tests;
+
+/// How GCC qualifies the stack usage of a function
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameKind {
+    /// The frame always has the same size
+    Static,
+    /// The frame grows at run time by an amount the compiler can't bound, e.g. `alloca`
+    Dynamic,
+    /// The frame grows at run time, but never beyond the reported size
+    Bounded,
+}
+
+/// Stack used by a function's own frame, as reported by the compiler
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FrameSize {
+    pub bytes: u32,
+    pub kind: FrameKind,
+}
+
+/// A single line of a GCC `-fstack-usage` file
+#[derive(Debug, PartialEq)]
+pub struct StackUsageEntry {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub function: String,
+    pub frame: FrameSize,
+}
+
+/// The contents of one or more GCC `.su` files
+pub struct StackUsageTable(Vec<StackUsageEntry>);
+
+impl StackUsageTable {
+    /// Creates a new empty StackUsageTable object
+    pub fn new() -> Self {
+        StackUsageTable(Vec::new())
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, StackUsageEntry> {
+        self.0.iter()
+    }
+
+    /// Creates a stack usage table from a `.su` file
+    ///
+    /// # Arguments
+    /// * 'filename' - Path/filename to read
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let stack_usage = stack_usage::StackUsageTable::from_file("main.su")?;
+    /// ```
+    pub fn from_file(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let input = string_utils::read_lines_from_file(filename)?;
+        Ok(StackUsageTable::from_lines(&input))
+    }
+
+    /// Creates a stack usage table from lines of `.su` files. Lines that don't parse are ignored
+    ///
+    /// # Arguments
+    /// * 'lines' - Vector of lines containing the data
+    pub fn from_lines(lines: &[String]) -> Self {
+        StackUsageTable(
+            lines
+                .iter()
+                .flat_map(|x| parse_stack_usage_entry(x))
+                .map(|(_, result)| result)
+                .collect(),
+        )
+    }
+
+    /// Adds the entries of another table, e.g. to combine the `.su` files of every source file
+    pub fn extend(&mut self, other: StackUsageTable) {
+        self.0.extend(other.0);
+    }
+
+    /// Finds the entry of a function. When the source file is known it is used to pick between
+    /// static functions with the same name, otherwise the first entry with the name is returned
+    ///
+    /// # Arguments
+    /// * 'function' - Name of the function
+    /// * 'file' - Optional name of the source file the function was compiled from
+    pub fn find(&self, function: &str, file: Option<&str>) -> Option<&StackUsageEntry> {
+        let mut matching = self.iter().filter(|x| x.function == function);
+        match file {
+            Some(file) => {
+                let candidates: Vec<_> = matching.collect();
+                candidates
+                    .iter()
+                    .find(|x| same_file(&x.file, file))
+                    .or_else(|| candidates.first())
+                    .copied()
+            }
+            None => matching.next(),
+        }
+    }
+}
+
+impl Default for StackUsageTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for StackUsageTable {
+    type Target = Vec<StackUsageEntry>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Checks if two paths name the same source file, ignoring any leading directories
+pub(crate) fn same_file(x: &str, y: &str) -> bool {
+    let name = |path: &str| path.rsplit(['/', '\\']).next().unwrap_or(path).to_string();
+    name(x) == name(y)
+}
+
+/// Converts digits to a number, failing rather than panicking if they don't fit
+fn parse_number(input: &str) -> Result<u32, nom::Err<nom::error::Error<&str>>> {
+    input.parse().map_err(|_| {
+        nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Digit,
+        })
+    })
+}
+
+/// Parses the qualifier GCC prints after the frame size
+fn parse_frame_kind(input: &str) -> Option<FrameKind> {
+    match input.trim() {
+        "static" => Some(FrameKind::Static),
+        "dynamic" => Some(FrameKind::Dynamic),
+        "dynamic,bounded" => Some(FrameKind::Bounded),
+        _ => None,
+    }
+}
+
+/// Parses a frame size the way GCC prints it in `-fcallgraph-info` labels, e.g. "64 bytes (static)"
+pub(crate) fn parse_frame_size(input: &str) -> Option<FrameSize> {
+    let (rest, (bytes, _)) = tuple((digit1::<&str, nom::error::Error<&str>>, tag(" bytes (")))(input.trim()).ok()?;
+    Some(FrameSize {
+        bytes: bytes.parse().ok()?,
+        kind: parse_frame_kind(rest.strip_suffix(')')?)?,
+    })
+}
+
+/// Parses a line of a `.su` file. Returns a results type containing the parsed result if successful
+///
+/// # Arguments
+/// * 'input' - The input string to parse the entry from
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::stack_usage::{parse_stack_usage_entry, FrameKind};
+/// let (_, entry) = parse_stack_usage_entry("main.c:7:5:main\t32\tstatic").unwrap();
+/// assert_eq!(entry.function, "main");
+/// assert_eq!(entry.frame.bytes, 32);
+/// assert_eq!(entry.frame.kind, FrameKind::Static);
+/// ```
+pub fn parse_stack_usage_entry(input: &str) -> IResult<&str, StackUsageEntry> {
+    let (rest, (file, _, line, _, column, _, function, _, bytes, _, kind)) = tuple((
+        take_until(":"),
+        char(':'),
+        digit1,
+        char(':'),
+        digit1,
+        char(':'),
+        take_until("\t"),
+        char('\t'),
+        digit1,
+        char('\t'),
+        not_line_ending,
+    ))(input)?;
+    let kind = parse_frame_kind(kind).ok_or(nom::Err::Error(nom::error::Error {
+        input: kind,
+        code: nom::error::ErrorKind::Tag,
+    }))?;
+    Ok((
+        rest,
+        StackUsageEntry {
+            file: file.to_string(),
+            line: parse_number(line)?,
+            column: parse_number(column)?,
+            function: function.to_string(),
+            frame: FrameSize {
+                bytes: parse_number(bytes)?,
+                kind,
+            },
+        },
+    ))
+}
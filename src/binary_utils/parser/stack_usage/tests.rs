@@ -0,0 +1,54 @@
+use super::*;
+use crate::binary_utils::test_utils::lines;
+
+#[test]
+fn test_parse_stack_usage_entry() {
+    let (_, entry) = parse_stack_usage_entry("app.c:4:5:sum\t64\tdynamic").unwrap();
+    assert_eq!(
+        entry,
+        StackUsageEntry {
+            file: "app.c".to_string(),
+            line: 4,
+            column: 5,
+            function: "sum".to_string(),
+            frame: FrameSize {
+                bytes: 64,
+                kind: FrameKind::Dynamic
+            },
+        }
+    );
+
+    let (_, entry) = parse_stack_usage_entry("src/uart.c:20:6:uart_write\t24\tdynamic,bounded").unwrap();
+    assert_eq!(entry.file, "src/uart.c");
+    assert_eq!(entry.frame.kind, FrameKind::Bounded);
+
+    assert!(parse_stack_usage_entry("app.c:4:5:sum\t64\tsometimes").is_err());
+    assert!(parse_stack_usage_entry("graph: { title: \"app.c\"").is_err());
+    assert!(parse_stack_usage_entry("app.c:4:5:sum\t99999999999\tstatic").is_err());
+}
+
+#[test]
+fn test_parse_frame_size() {
+    assert_eq!(
+        parse_frame_size("64 bytes (static)"),
+        Some(FrameSize {
+            bytes: 64,
+            kind: FrameKind::Static
+        })
+    );
+    assert_eq!(parse_frame_size("8 bytes (dynamic,bounded)").unwrap().kind, FrameKind::Bounded);
+    assert_eq!(parse_frame_size("0 dynamic objects"), None);
+}
+
+#[test]
+fn test_find_prefers_the_same_source_file() {
+    let table = StackUsageTable::from_lines(&lines(&[
+        "src/uart.c:3:13:init\t16\tstatic",
+        "src/spi.c:5:13:init\t32\tstatic",
+        "src/spi.c:9:6:spi_write\t8\tstatic",
+    ]));
+    assert_eq!(table.find("init", Some("spi.c")).unwrap().frame.bytes, 32);
+    assert_eq!(table.find("init", Some("adc.c")).unwrap().frame.bytes, 16);
+    assert_eq!(table.find("init", None).unwrap().frame.bytes, 16);
+    assert!(table.find("main", None).is_none());
+}
@@ -1,7 +1,7 @@
 use nom::{
     bytes::complete::take_until,
     character::complete::{anychar, multispace0, one_of},
-    combinator::recognize,
+    combinator::{map_res, recognize},
     error::ErrorKind,
     multi::many1,
     sequence::tuple,
@@ -9,6 +9,7 @@ use nom::{
 };
 
 use std::{
+    collections::BTreeMap,
     fmt,
     io,
     iter::{self, FromIterator},
@@ -16,15 +17,27 @@ use std::{
     slice
 };
 
+use serde::Serialize;
+
 use horrorshow::html;
 use horrorshow::prelude::*;
 use horrorshow::helper::doctype;
 
+use super::relocations::Relocations;
+use super::section_table::SectionTable;
+use super::stack_usage::{FrameSize, StackUsageTable};
+use crate::binary_utils::report::{self, TABLE_STYLE};
 use crate::string_utils;
 
 #[cfg(test)]
 mod tests;
 
+/// Suffixes GCC and LLVM add to the functions they split or specialize, e.g. `foo.part.0`
+pub const CLONE_SUFFIXES: [&str; 6] = [".part.", ".cold", ".constprop.", ".isra.", ".lto_priv.", ".llvm."];
+
+/// Path components rustc gives closures, demangled and in legacy mangled names
+const CLOSURE_MARKERS: [&str; 2] = ["::{{closure}}", "$u7b$$u7b$closure$u7d$$u7d$"];
+
 pub struct SymbolTable(Vec<SymbolTableEntry>);
 
 impl SymbolTable {
@@ -74,7 +87,7 @@ impl SymbolTable {
     /// let lines: Vec<String> = vec!["some symbol table stuff here", "more here"];
     /// let symbol_table = symbol_table::SymbolTable::from_lines(&lines);
     /// ```
-    pub fn from_lines(lines: &Vec<String>) -> Self {
+    pub fn from_lines(lines: &[String]) -> Self {
         lines.iter()
             .map(|x| parse_symbol_table_entry(x))
             .filter(|x| x.is_ok())
@@ -101,6 +114,241 @@ impl SymbolTable {
         });
     }
 
+    /// Gets the number of bytes covered by the symbols of every allocated section. Aliases and
+    /// overlapping symbols are only counted once
+    pub fn section_sizes(&self) -> BTreeMap<String, u32> {
+        // Grouped clones count towards the section they were placed in, like in `covered_size`
+        let mut sections: BTreeMap<&str, Vec<(&str, u32, u32)>> = BTreeMap::new();
+        for entry in self.iter().filter(|x| x.is_allocated()) {
+            sections.entry(&entry.section).or_default().push((&entry.section, entry.address, entry.own_size()));
+            for clone in &entry.clones {
+                sections.entry(&clone.section).or_default().push((&clone.section, clone.address, clone.size));
+            }
+        }
+        sections
+            .into_iter()
+            .map(|(name, ranges)| (name.to_string(), covered_range_size(ranges)))
+            .collect()
+    }
+
+    /// Pairs each symbol with the source file it was compiled from. Only local symbols are given
+    /// a file, since objdump lists them after the file symbol they belong to
+    pub fn with_files(&self) -> Vec<(Option<&str>, &SymbolTableEntry)> {
+        let mut file: Option<&str> = None;
+        self.iter()
+            .filter_map(|entry| {
+                if entry.flags.symbol_type == SymbolType::File {
+                    file = Some(&entry.name);
+                    return None;
+                }
+                let file = file.filter(|_| entry.flags.scope == SymbolScope::Local);
+                Some((file, entry))
+            })
+            .collect()
+    }
+
+    /// Sums the symbol sizes per source file. Local symbols are attributed to the file symbol that
+    /// precedes them in the table, which is how objdump orders its output
+    pub fn file_sizes(&self) -> BTreeMap<String, u32> {
+        let mut files: BTreeMap<&str, Vec<&SymbolTableEntry>> = BTreeMap::new();
+        let mut file: Option<&str> = None;
+        for entry in self.iter() {
+            if entry.flags.symbol_type == SymbolType::File {
+                file = Some(&entry.name);
+            } else if let Some(name) = file {
+                if entry.flags.scope == SymbolScope::Local && entry.is_allocated() && !name.is_empty() {
+                    files.entry(name).or_default().push(entry);
+                }
+            }
+        }
+        files
+            .into_iter()
+            .map(|(name, entries)| (name.to_string(), covered_size(entries)))
+            .collect()
+    }
+
+    /// Attaches the stack frames from GCC `.su` files to the function symbols. Local functions are
+    /// matched to the source file named by the file symbol that precedes them
+    ///
+    /// # Arguments
+    /// * 'stack_usage' - Stack usage of the compiled source files
+    pub fn attach_stack_usage(&mut self, stack_usage: &StackUsageTable) {
+        let mut file: Option<String> = None;
+        for entry in self.0.iter_mut() {
+            match entry.flags.symbol_type {
+                SymbolType::File => file = Some(entry.name.clone()),
+                SymbolType::Function => {
+                    let file = match entry.flags.scope {
+                        SymbolScope::Local => file.as_deref(),
+                        _ => None,
+                    };
+                    entry.frame_size = stack_usage.find(&entry.name, file).map(|x| x.frame);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Counts the relocations that refer to each symbol. Relocations name symbols without the
+    /// visibility that objdump prints for object files, and dynamic relocations add a version.
+    /// When the relocations include dynamic ones only those are counted, like `RelocationReport`
+    ///
+    /// # Arguments
+    /// * 'relocations' - Relocations of the same binary
+    pub fn attach_relocations(&mut self, relocations: &Relocations) {
+        let dynamic = relocations.dynamic().next().is_some();
+        let mut counts: BTreeMap<&str, u32> = BTreeMap::new();
+        for symbol in relocations
+            .iter()
+            .filter(|x| x.is_dynamic() == dynamic)
+            .filter_map(|x| x.symbol.as_deref())
+        {
+            *counts.entry(symbol).or_default() += 1;
+        }
+        for entry in self.0.iter_mut() {
+            entry.relocations = counts.get(entry.plain_name()).copied().unwrap_or(0);
+        }
+    }
+
+    /// Finds the definition of a symbol by its plain name, see `SymbolTableEntry::plain_name`
+    pub fn find_defined(&self, name: &str) -> Option<&SymbolTableEntry> {
+        self.iter().find(|x| x.section != "*UND*" && x.plain_name() == name)
+    }
+
+    /// Indexes the defined symbols by their plain name, for looking up many symbols. Each name
+    /// maps to the same symbol as `find_defined`
+    pub fn defined_by_name(&self) -> BTreeMap<&str, &SymbolTableEntry> {
+        let mut defined = BTreeMap::new();
+        for entry in self.iter().filter(|x| x.section != "*UND*") {
+            defined.entry(entry.plain_name()).or_insert(entry);
+        }
+        defined
+    }
+
+    /// Checks if relocations were attached to any symbol, see `attach_relocations`
+    fn has_relocations(&self) -> bool {
+        self.iter().any(|x| x.relocations > 0)
+    }
+
+    /// Folds compiler clones and closures into the function they came from, so the size of each
+    /// function includes its clones. Clones whose origin isn't in the table, e.g. because it was
+    /// inlined everywhere, are grouped under a new symbol with the origin's name. Each grouped symbol
+    /// lists its clones in `clones`, see `clone_origin`
+    pub fn group_clones(&mut self) {
+        let entries = std::mem::take(&mut self.0);
+        // Origins by file and name, where only local symbols have a file, see `with_files`
+        let mut origins: BTreeMap<(Option<String>, String), usize> = BTreeMap::new();
+        let mut clones = Vec::new();
+        let mut file: Option<String> = None;
+        for entry in entries {
+            if entry.flags.symbol_type == SymbolType::File {
+                file = Some(entry.name.clone());
+                self.0.push(entry);
+            } else if !entry.is_allocated() || entry.is_section_symbol() {
+                self.0.push(entry);
+            } else {
+                let file = file.clone().filter(|_| entry.flags.scope == SymbolScope::Local);
+                if let Some(origin) = clone_origin(&entry.name) {
+                    clones.push((file, origin, entry));
+                } else {
+                    origins.entry((file, without_rust_hash(&entry.name))).or_insert(self.0.len());
+                    self.0.push(entry);
+                }
+            }
+        }
+        for (file, origin, mut entry) in clones {
+            let key = (file, without_rust_hash(&origin));
+            let clone = SymbolClone {
+                name: std::mem::replace(&mut entry.name, origin),
+                section: entry.section.clone(),
+                address: entry.address,
+                size: entry.alignment_or_size,
+            };
+            // A clone of a static function belongs to the one in its own file, if there is one
+            let found = origins.get(&key).or_else(|| origins.get(&(None, key.1.clone())));
+            let index = match found {
+                Some(&index) => index,
+                None => {
+                    // The first orphaned clone becomes the symbol of its origin
+                    entry.alignment_or_size = 0;
+                    entry.size_inferred = false;
+                    entry.frame_size = None;
+                    origins.insert(key, self.0.len());
+                    self.0.push(entry);
+                    self.0.len() - 1
+                }
+            };
+            let origin = &mut self.0[index];
+            origin.alignment_or_size += clone.size;
+            origin.clones.push(clone);
+        }
+    }
+
+    /// Gives zero sized symbols, like hand written assembly functions, the size up to the next
+    /// symbol in the same section, or up to the end of the section if it's the last one. Labels at
+    /// the address of or inside a sized symbol are left alone, since they only name that symbol or
+    /// a place in it. Each symbol that gets a size is marked with `size_inferred`
+    ///
+    /// # Arguments
+    /// * 'section_table' - Optional section headers, used to find the end of each section
+    pub fn infer_sizes(&mut self, section_table: Option<&SectionTable>) {
+        let mut sections: BTreeMap<&str, Vec<(u32, u32)>> = BTreeMap::new();
+        for entry in self.iter().filter(|x| x.is_allocated()) {
+            sections.entry(&entry.section).or_default().push((entry.address, entry.alignment_or_size));
+        }
+        for symbols in sections.values_mut() {
+            symbols.sort_unstable();
+        }
+        // The furthest end of any sized symbol up to each symbol of a section
+        let covered: BTreeMap<&str, Vec<u64>> = sections
+            .iter()
+            .map(|(&section, symbols)| {
+                let ends = symbols.iter().scan(0, |max, &(address, size)| {
+                    *max = (address as u64 + size as u64).max(*max);
+                    Some(*max)
+                });
+                (section, ends.collect())
+            })
+            .collect();
+
+        let mut sizes = Vec::new();
+        for (i, entry) in self.iter().enumerate() {
+            if entry.alignment_or_size > 0
+                || !entry.is_allocated()
+                || entry.is_section_symbol()
+                || entry.flags.symbol_type == SymbolType::File
+            {
+                continue;
+            }
+            let symbols = &sections[entry.section.as_str()];
+            let first = symbols.partition_point(|&(address, _)| address < entry.address);
+            let next = symbols.partition_point(|&(address, _)| address <= entry.address);
+            let inside = first > 0 && covered[entry.section.as_str()][first - 1] > entry.address as u64;
+            if inside || symbols[first..next].iter().any(|&(_, size)| size > 0) {
+                continue;
+            }
+            let end = match symbols.get(next) {
+                Some(&(address, _)) => Some(address),
+                None => section_table
+                    .and_then(|x| x.find(&entry.section))
+                    .map(|x| x.vma + x.size),
+            };
+            if let Some(end) = end.filter(|&x| x > entry.address) {
+                sizes.push((i, end - entry.address));
+            }
+        }
+        for (i, size) in sizes {
+            self.0[i].alignment_or_size = size;
+            self.0[i].size_inferred = true;
+        }
+    }
+
+    /// Gets the total size of all symbols placed in the image. Thread local sections are left out
+    /// since each thread gets its own copy, see `TlsReport`
+    pub fn total_size(&self) -> u32 {
+        image_size(&self.section_sizes())
+    }
+
     /// Converts a symbol table into an HTML formatted string
     pub fn to_html(&self) -> String {
         format!("{}", html! {
@@ -108,20 +356,7 @@ impl SymbolTable {
             html {
                 head {                
                     title : "Symbol Table";
-                    style : "table {
-                        font-family: arial, sans-serif;
-                        border-collapse: collapse;
-                    }
-                
-                    td, th {
-                        border: 1px solid #dddddd;
-                        text-align: left;
-                        padding: 8px;
-                    }
-                
-                    tr:nth-child(even) {
-                        background-color: #dddddd;
-                    }";
+                    style : TABLE_STYLE;
                 }
                 body {
                     // attributes
@@ -133,6 +368,9 @@ impl SymbolTable {
                                 th: "Section";
                                 th: "Address";
                                 th: "Size";
+                                @ if self.has_relocations() {
+                                    th: "Relocations";
+                                }
                             }                                          
                         }
                         tbody {
@@ -141,7 +379,21 @@ impl SymbolTable {
                                     td: Raw(format!("{}", self[i].name));
                                     td: Raw(format!("{}", self[i].section));
                                     td: Raw(format!("{:08x}", self[i].address));
-                                    td: Raw(format!("{}", self[i].alignment_or_size));
+                                    td: Raw(self[i].size_string(self[i].alignment_or_size));
+                                    @ if self.has_relocations() {
+                                        td: self[i].relocations;
+                                    }
+                                }
+                                @ for clone in &self[i].clones {
+                                    tr {
+                                        td: Raw(format!("&nbsp;&nbsp;+ {}", clone.name));
+                                        td;
+                                        td: Raw(format!("{:08x}", clone.address));
+                                        td: clone.size;
+                                        @ if self.has_relocations() {
+                                            td;
+                                        }
+                                    }
                                 }
                             }
                         }                    
@@ -153,7 +405,8 @@ impl SymbolTable {
 
     /// Gets the maximum length of all names contained in the symbol table
     fn get_max_name_length(&self) -> usize {
-        self.iter().map(|x| x.name.len()).max().unwrap()
+        let clones = self.iter().flat_map(|x| &x.clones).map(|x| x.name.len() + "  + ".len());
+        self.iter().map(|x| x.name.len()).chain(clones).max().unwrap()
     }
 
     /// Gets the maximum length of all symbol location names
@@ -167,6 +420,12 @@ impl SymbolTable {
     }
 }
 
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::ops::Deref for SymbolTable {
     type Target = Vec<SymbolTableEntry>;
     fn deref(&self) -> &Self::Target {
@@ -203,23 +462,28 @@ impl fmt::Debug for SymbolTable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let max_name_width = self.get_max_name_length();
         let max_section_width = self.get_max_section_name_length();
-        let max_width = max_name_width + max_section_width + 19; // Hack for now for formatting, fix later
-        let banner_break: String = iter::repeat("-").take(max_width).collect();
-        f.write_fmt(format_args!("{}\r\n", banner_break)).unwrap();
-        f.write_fmt(format_args!(
-            "{:0n_width$} {:0s_width$} {:8} {:8}\r\n",
+        let relocations = self.has_relocations();
+        let mut max_width = max_name_width + max_section_width + 19; // Hack for now for formatting, fix later
+        let mut heading = format!(
+            "{:0n_width$} {:0s_width$} {:8} {:8}",
             "Name",
             "Section",
             "Address",
             "Size",
             n_width = max_name_width,
             s_width = max_section_width
-        ))
-        .unwrap();
+        );
+        if relocations {
+            max_width += 7;
+            heading += " Relocs";
+        }
+        let banner_break: String = iter::repeat("-").take(max_width).collect();
+        f.write_fmt(format_args!("{}\r\n", banner_break)).unwrap();
+        f.write_fmt(format_args!("{}\r\n", heading)).unwrap();
         f.write_fmt(format_args!("{}\r\n", banner_break)).unwrap();
 
         for i in &self.0 {
-            let result = f.write_str(&i.to_string(max_name_width, max_section_width));
+            let result = f.write_str(&i.to_string(max_name_width, max_section_width, relocations));
             if result.is_err() {
                 return Err(std::fmt::Error);
             }
@@ -235,19 +499,97 @@ pub struct SymbolTableEntry {
     pub section: String,
     pub alignment_or_size: u32,
     pub name: String,
+    /// Stack frame reported by the compiler, see `SymbolTable::attach_stack_usage`
+    pub frame_size: Option<FrameSize>,
+    /// Whether the size was inferred from the next symbol, see `SymbolTable::infer_sizes`
+    pub size_inferred: bool,
+    /// Number of relocations that refer to the symbol, see `SymbolTable::attach_relocations`
+    pub relocations: u32,
+    /// Clones folded into the symbol, whose sizes are included in its size, see
+    /// `SymbolTable::group_clones`
+    pub clones: Vec<SymbolClone>,
+}
+
+/// A clone or closure of a function, and where it was placed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolClone {
+    pub name: String,
+    /// Section the clone was placed in, which can differ from its origin's, e.g. `.text.unlikely`
+    pub section: String,
+    pub address: u32,
+    pub size: u32,
 }
 
 impl SymbolTableEntry {
-    fn to_string(&self, name_width: usize, section_width: usize) -> String {
-        format!(
-            "{:0n_width$} {:0s_width$} {:08x} {:08x}\r\n",
+    /// Checks if the symbol is placed in a real section of the image, rather than being absolute,
+    /// undefined or a common symbol whose size field holds an alignment
+    pub fn is_allocated(&self) -> bool {
+        !matches!(self.section.as_str(), "*ABS*" | "*UND*" | "*COM*")
+    }
+
+    /// Checks if the symbol is the symbol objdump emits for the start of each section
+    pub fn is_section_symbol(&self) -> bool {
+        self.flags.debugging == SymbolDebugging::Debug && self.flags.symbol_type == SymbolType::Regular
+    }
+
+    /// Checks if the symbol is thread local. The address of a thread local symbol is its offset in
+    /// each thread's TLS block rather than a memory address
+    pub fn is_thread_local(&self) -> bool {
+        is_thread_local_section(&self.section)
+    }
+
+    /// Gets the name of the symbol without the visibility objdump prints before it in object files,
+    /// e.g. ".hidden helper", or the version it prints after it, e.g. "strlen@GLIBC_2.2.5"
+    pub fn plain_name(&self) -> &str {
+        let name = [".hidden ", ".protected ", ".internal "]
+            .iter()
+            .find_map(|x| self.name.strip_prefix(x))
+            .unwrap_or(&self.name);
+        name.split('@').next().unwrap_or(name)
+    }
+
+    /// Checks if the symbol is global but can't be seen outside the linked image, which objdump only
+    /// shows for object files since the linker turns these symbols into local ones
+    pub fn is_hidden(&self) -> bool {
+        self.name.starts_with(".hidden ") || self.name.starts_with(".internal ")
+    }
+
+    /// Adds a marker to a formatted size if the size was inferred rather than read from the table
+    pub fn size_string(&self, size: impl fmt::Display) -> String {
+        report::size_string(size, self.size_inferred)
+    }
+
+    /// Gets the size of the symbol without the clones grouped into it
+    pub fn own_size(&self) -> u32 {
+        self.alignment_or_size - self.clones.iter().map(|x| x.size).sum::<u32>()
+    }
+
+    fn to_string(&self, name_width: usize, section_width: usize, relocations: bool) -> String {
+        let mut string = format!(
+            "{:0n_width$} {:0s_width$} {:08x} ",
             self.name,
             self.section,
             self.address,
-            self.alignment_or_size,
             n_width = name_width,
             s_width = section_width
-        )
+        );
+        let size = format!("{:08x}", self.alignment_or_size);
+        // The inferred marker goes after the relocation count so the columns stay aligned
+        let columns = if relocations { format!("{} {:>6}", size, self.relocations) } else { size };
+        string += &self.size_string(columns);
+        string += "\r\n";
+        for clone in &self.clones {
+            string += &format!(
+                "{:0n_width$} {:0s_width$} {:08x} {:08x}\r\n",
+                format!("  + {}", clone.name),
+                "",
+                clone.address,
+                clone.size,
+                n_width = name_width,
+                s_width = section_width
+            );
+        }
+        string
     }
 }
 
@@ -309,10 +651,117 @@ pub enum SymbolType {
     Regular,
 }
 
-/// Parses an unsigned decimal value from a string
-fn parse_u32(input: &str) -> IResult<&str, u32> {
-    recognize(many1(one_of("0123456789abcdefABCDEF")))(input)
-        .map(|(i, o)| (i, u32::from_str_radix(o, 16).unwrap()))
+/// Gets the number of bytes covered by a set of symbols, counting bytes shared by aliased or
+/// overlapping symbols only once. Symbols only overlap within their own section, since every
+/// section of an object file starts at zero
+///
+/// # Arguments
+/// * 'entries' - The symbols to measure
+pub fn covered_size<'a>(entries: impl IntoIterator<Item = &'a SymbolTableEntry>) -> u32 {
+    // Grouped clones are measured where they were placed, not at the address of their origin
+    covered_range_size(entries.into_iter().flat_map(|x| {
+        let clones = x.clones.iter().map(|clone| (clone.section.as_str(), clone.address, clone.size));
+        std::iter::once((x.section.as_str(), x.address, x.own_size())).chain(clones)
+    }))
+}
+
+/// Gets the number of bytes covered by a set of ranges, counting bytes shared by overlapping
+/// ranges of the same section only once, see `covered_size`
+///
+/// # Arguments
+/// * 'ranges' - The section, address and size of each range
+pub fn covered_range_size<'a>(ranges: impl IntoIterator<Item = (&'a str, u32, u32)>) -> u32 {
+    let mut ranges: Vec<(&str, u64, u64)> = ranges
+        .into_iter()
+        .filter(|&(_, _, size)| size > 0)
+        .map(|(section, address, size)| (section, address as u64, address as u64 + size as u64))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut total = 0;
+    let mut covered_to = (None, 0);
+    for (section, start, end) in ranges {
+        if covered_to.0 != Some(section) {
+            covered_to = (Some(section), 0);
+        }
+        let start = start.max(covered_to.1);
+        if end > start {
+            total += end - start;
+            covered_to.1 = end;
+        }
+    }
+    total as u32
+}
+
+/// Gets the function a clone or closure was made from, or none if the symbol isn't a clone. For
+/// Rust closures the hash of the closure is dropped along with the closure's path component
+///
+/// # Examples
+/// ```
+/// use binarytools::binary_utils::parser::symbol_table::clone_origin;
+/// assert_eq!(clone_origin("parse.isra.0.cold"), Some("parse".to_string()));
+/// assert_eq!(clone_origin("app::main::{{closure}}"), Some("app::main".to_string()));
+/// assert_eq!(clone_origin("parse"), None);
+/// ```
+pub fn clone_origin(name: &str) -> Option<String> {
+    if let Some(i) = name.find(CLOSURE_MARKERS[0]) {
+        return Some(name[..i].to_string());
+    }
+    if let Some(i) = name.find(CLOSURE_MARKERS[1]) {
+        // Legacy mangling prefixes the component with its length, e.g. "28_$u7b$...", and the
+        // underscore that escapes the leading '$' is part of the component
+        let start = if name[..i].ends_with('_') { i - 1 } else { i };
+        let length = i + CLOSURE_MARKERS[1].len() - start;
+        let path = &name[..start];
+        let path = path.strip_suffix(length.to_string().as_str()).unwrap_or(path);
+        return Some(format!("{}E", path));
+    }
+    CLONE_SUFFIXES.iter().filter_map(|x| name.find(x)).min().map(|i| name[..i].to_string())
+}
+
+/// Removes the hash rustc adds to each function, e.g. "::h0123456789abcdef" when demangled or
+/// "17h0123456789abcdef" before the final "E" when mangled, so a closure's origin can be matched to
+/// its function
+fn without_rust_hash(name: &str) -> String {
+    let is_hash = |x: &str| x.len() == 17 && x.starts_with('h') && x[1..].chars().all(|x| x.is_ascii_hexdigit());
+    if let Some(path) = name.strip_suffix('E') {
+        let split = path.len().saturating_sub(19);
+        if path.is_char_boundary(split) && path[split..].starts_with("17") && is_hash(&path[split + 2..]) {
+            return format!("{}E", &path[..split]);
+        }
+    }
+    match name.rfind("::") {
+        Some(i) if is_hash(&name[i + 2..]) => name[..i].to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Gets the size of the image from the size of each of its sections, leaving out the thread local
+/// sections like `SymbolTable::total_size`
+///
+/// # Arguments
+/// * 'section_sizes' - Size of each section, see `SymbolTable::section_sizes`
+pub fn image_size(section_sizes: &BTreeMap<String, u32>) -> u32 {
+    section_sizes
+        .iter()
+        .filter(|(name, _)| !is_thread_local_section(name))
+        .map(|(_, size)| size)
+        .sum()
+}
+
+/// Checks if a section name is one of the thread local storage sections, `.tdata` or `.tbss`, or
+/// an input section that is merged into one of them
+pub fn is_thread_local_section(name: &str) -> bool {
+    [".tdata", ".tbss"]
+        .iter()
+        .any(|x| name == *x || name.starts_with(&format!("{}.", x)))
+}
+
+/// Parses an unsigned hexadecimal value from a string, failing if it does not fit in 32 bits
+pub(crate) fn parse_u32(input: &str) -> IResult<&str, u32> {
+    map_res(recognize(many1(one_of("0123456789abcdefABCDEF"))), |x| {
+        u32::from_str_radix(x, 16)
+    })(input)
 }
 
 /// Parses the symbol table scope bit out of the bit flags sequence
@@ -463,6 +912,10 @@ pub fn parse_symbol_table_entry(input: &str) -> IResult<&str, SymbolTableEntry>
             section: section.to_string(),
             alignment_or_size,
             name: name.iter().collect::<String>(),
+            frame_size: None,
+            size_inferred: false,
+            relocations: 0,
+            clones: Vec::new(),
         },
     ))
 }
@@ -1,11 +1,13 @@
 use nom::error::ErrorKind;
 use super::*;
+use crate::binary_utils::test_utils::lines;
 
 
 #[test]
 fn test_parse_decimal_from_string() {
     assert_eq!(parse_u32("12345"), Ok(("", 74565)));
     assert!(parse_u32("gabcdeg").is_err());
+    assert!(parse_u32("100000000").is_err());
 }
 
 #[test]
@@ -217,3 +219,264 @@ fn test_parse_symbol_table_entry_with_empty_flag_start() {
         }
     );
 }
+
+#[test]
+fn test_covered_size_counts_shared_bytes_once() {
+    let entries: Vec<SymbolTableEntry> = [
+        "00001000 g     F .text	00000040 outer",
+        "00001000 g     F .text	00000040 outer_alias",
+        "00001010 l     F .text	00000010 inner",
+        "00001030 g     F .text	00000020 straddling",
+        "00001080 g     F .text	00000000 label",
+        "00001080 g     F .text	00000008 last",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    assert_eq!(covered_size(&entries), 0x58);
+}
+
+#[test]
+fn test_total_size_leaves_out_thread_local_sections() {
+    let symbol_table = SymbolTable::from_lines(&lines(&[
+        "08000000 g     F .text	00000020 main",
+        "00000000 g     O .tdata	00000008 errno_value",
+        "00000008 g     O .tbss	00000010 scratch",
+    ]));
+    assert_eq!(symbol_table.section_sizes()[".tbss"], 0x10);
+    assert_eq!(symbol_table.total_size(), 0x20);
+}
+
+#[test]
+fn test_covered_size_keeps_sections_apart() {
+    // Every section of an object file starts at zero
+    let entries: Vec<SymbolTableEntry> = [
+        "0000000000000000 g     F .text	0000000000000020 main",
+        "0000000000000000 g     O .data	0000000000000010 table",
+        "0000000000000000 l     O .bss	0000000000000008 counter",
+        "0000000000000008 l     O .bss	0000000000000008 total",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    assert_eq!(covered_size(&entries), 0x40);
+}
+
+#[test]
+fn test_attach_stack_usage() {
+    let mut symbol_table: SymbolTable = [
+        "00000000 l    df *ABS*	00000000 uart.c",
+        "08000100 l     F .text	00000010 init",
+        "00000000 l    df *ABS*	00000000 spi.c",
+        "08000110 l     F .text	00000010 init",
+        "08000120 g     F .text	00000020 main",
+        "20000000 g     O .data	00000004 counter",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    let stack_usage = StackUsageTable::from_lines(&[
+        "src/spi.c:5:13:init\t32\tstatic".to_string(),
+        "src/uart.c:3:13:init\t16\tstatic".to_string(),
+        "src/main.c:9:5:main\t48\tdynamic,bounded".to_string(),
+    ]);
+    symbol_table.attach_stack_usage(&stack_usage);
+
+    let frames: Vec<Option<u32>> = symbol_table.iter().map(|x| x.frame_size.map(|x| x.bytes)).collect();
+    assert_eq!(frames, vec![None, Some(16), None, Some(32), Some(48), None]);
+}
+
+#[test]
+fn test_infer_sizes() {
+    let mut symbol_table: SymbolTable = [
+        "00000000 l    df *ABS*	00000000 start.S",
+        "08000000 l    d  .text	00000000 .text",
+        "08000000 g     F .text	00000000 Reset_Handler",
+        "08000010 g     F .text	00000020 main",
+        "08000010 g       .text	00000000 main_label",
+        "08000030 g     F .text	00000000 Default_Handler",
+        "20000000 g     O .bss	00000000 heap",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    let section_table = SectionTable::from_lines(&[
+        "Idx Name          Size      VMA       LMA       File off  Algn".to_string(),
+        "  0 .text         00000034  08000000  08000000  00010000  2**2".to_string(),
+    ]);
+    symbol_table.infer_sizes(Some(&section_table));
+
+    let sizes: Vec<(u32, bool)> = symbol_table
+        .iter()
+        .map(|x| (x.alignment_or_size, x.size_inferred))
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![
+            (0, false),
+            (0, false),
+            (0x10, true),
+            (0x20, false),
+            // Labels of a sized symbol keep their zero size
+            (0, false),
+            // The last symbol of a section ends with the section
+            (4, true),
+            // Without a section header the end of .bss is unknown
+            (0, false),
+        ]
+    );
+    assert!(format!("{:?}", symbol_table).contains("00000010 (inferred)"));
+    assert!(symbol_table.to_html().contains("16 (inferred)"));
+}
+
+#[test]
+fn test_infer_sizes_skips_labels_inside_sized_symbols() {
+    let mut symbol_table: SymbolTable = [
+        "00001000 g     F .text	00000010 foo",
+        "00001004 l       .text	00000000 foo_loop",
+        "00001010 g     F .text	00000000 bar",
+        "00001018 g     F .text	00000008 baz",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    symbol_table.infer_sizes(None);
+
+    let sizes: Vec<(u32, bool)> = symbol_table
+        .iter()
+        .map(|x| (x.alignment_or_size, x.size_inferred))
+        .collect();
+    assert_eq!(sizes, vec![(0x10, false), (0, false), (8, true), (8, false)]);
+}
+
+#[test]
+fn test_attach_relocations() {
+    let mut symbol_table: SymbolTable = [
+        "0000000000000000 g     F .text	0000000000000010 .hidden helper",
+        "0000000000000010 g     F .text	0000000000000020 api_entry",
+        "0000000000000000       F *UND*	0000000000000000 strlen@GLIBC_2.2.5",
+        "0000000000004000 g     O .bss	0000000000000004 counter",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    let relocations = Relocations::from_lines(&[
+        "RELOCATION RECORDS FOR [.text]:".to_string(),
+        "0000000000000005 R_X86_64_PLT32    helper-0x0000000000000004".to_string(),
+        "0000000000000015 R_X86_64_PLT32    helper-0x0000000000000004".to_string(),
+        "0000000000000025 R_X86_64_PLT32    strlen-0x0000000000000004".to_string(),
+        "0000000000000035 R_X86_64_PC32     .data-0x0000000000000004".to_string(),
+    ]);
+    symbol_table.attach_relocations(&relocations);
+
+    let counts: Vec<u32> = symbol_table.iter().map(|x| x.relocations).collect();
+    assert_eq!(counts, vec![2, 0, 1, 0]);
+    let listing = format!("{:?}", symbol_table);
+    assert!(listing.contains("Size     Relocs\r\n"));
+    assert!(listing.contains(" 00000010      2\r\n"));
+    assert!(symbol_table.to_html().contains("<th>Relocations</th>"));
+    assert_eq!(symbol_table.defined_by_name()["helper"].address, 0);
+    assert!(symbol_table.iter().next().unwrap().is_hidden());
+    assert_eq!(symbol_table.find_defined("helper").map(|x| x.address), Some(0));
+    assert!(symbol_table.find_defined("strlen").is_none());
+}
+
+#[test]
+fn test_clone_origin() {
+    assert_eq!(clone_origin("parse.part.0"), Some("parse".to_string()));
+    assert_eq!(clone_origin("parse.cold"), Some("parse".to_string()));
+    assert_eq!(clone_origin("_ZL5scaleii.constprop.0"), Some("_ZL5scaleii".to_string()));
+    assert_eq!(clone_origin("state.lto_priv.0"), Some("state".to_string()));
+    assert_eq!(clone_origin("app::main::{{closure}}::{{closure}}"), Some("app::main".to_string()));
+    // Digits at the end of the function's own name are not part of the closure's length prefix
+    assert_eq!(
+        clone_origin("_ZN6crypto6sha25628_$u7b$$u7b$closure$u7d$$u7d$17h0123456789abcdefE"),
+        Some("_ZN6crypto6sha256E".to_string())
+    );
+    assert_eq!(
+        clone_origin("_ZN3app4main28_$u7b$$u7b$closure$u7d$$u7d$17h0123456789abcdefE"),
+        Some("_ZN3app4mainE".to_string())
+    );
+    assert_eq!(clone_origin("_ZN3app4main17h0123456789abcdefE"), None);
+}
+
+#[test]
+fn test_without_rust_hash() {
+    assert_eq!(without_rust_hash("_ZN3app4main17h0123456789abcdefE"), "_ZN3app4mainE");
+    assert_eq!(without_rust_hash("app::main::h0123456789abcdef"), "app::main");
+    assert_eq!(without_rust_hash("_ZN3app4mainE"), "_ZN3app4mainE");
+    assert_eq!(without_rust_hash("main"), "main");
+}
+
+#[test]
+fn test_group_clones_of_static_functions() {
+    let mut symbol_table = SymbolTable::from_lines(&lines(&[
+        "00000000 l    df *ABS*	00000000 a.c",
+        "00001000 l     F .text	00000020 init",
+        "00001020 l     F .text	00000010 init.part.0",
+        "00000000 l    df *ABS*	00000000 b.c",
+        "00001030 l     F .text	00000030 init",
+        "00000000 l     F .text.unlikely	00000008 init.cold",
+        "00001060 l     F .text	00000004 helper.isra.0",
+        "00000000 l    df *ABS*	00000000 c.c",
+        "00001070 l     F .text	00000004 helper.isra.0",
+    ]));
+    let sizes = symbol_table.section_sizes();
+    symbol_table.group_clones();
+
+    let grouped: Vec<(&str, u32, Vec<&str>)> = symbol_table
+        .iter()
+        .filter(|x| x.is_allocated())
+        .map(|x| (x.name.as_str(), x.alignment_or_size, x.clones.iter().map(|x| x.name.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        grouped,
+        vec![
+            ("init", 0x30, vec!["init.part.0"]),
+            ("init", 0x38, vec!["init.cold"]),
+            ("helper", 4, vec!["helper.isra.0"]),
+            ("helper", 4, vec!["helper.isra.0"]),
+        ]
+    );
+    assert_eq!(symbol_table.section_sizes(), sizes);
+}
+
+#[test]
+fn test_group_clones() {
+    let mut symbol_table: SymbolTable = [
+        "00000000 l    df *ABS*	00000000 parser.c",
+        "00001000 g     F .text	00000040 parse",
+        "00001040 l     F .text	00000010 parse.part.0",
+        "00001050 l     F .text	00000008 parse.cold",
+        "00001060 l     F .text	00000020 check.isra.0",
+        "00001080 l     F .text	00000010 check.constprop.1",
+        "00001090 g     F .text	00000030 _ZN3app4main17h0123456789abcdefE",
+        "000010c0 l     F .text	00000010 _ZN3app4main28_$u7b$$u7b$closure$u7d$$u7d$17hfedcba9876543210E",
+        "00002000 g     O .data	00000004 counter",
+    ]
+    .iter()
+    .map(|x| parse_symbol_table_entry(x).unwrap().1)
+    .collect();
+    let total = symbol_table.total_size();
+    symbol_table.group_clones();
+
+    let sizes: Vec<(&str, u32, usize)> = symbol_table
+        .iter()
+        .map(|x| (x.name.as_str(), x.alignment_or_size, x.clones.len()))
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![
+            ("parser.c", 0, 0),
+            ("parse", 0x58, 2),
+            ("_ZN3app4main17h0123456789abcdefE", 0x40, 1),
+            ("counter", 4, 0),
+            ("check", 0x30, 2),
+        ]
+    );
+    let check = &symbol_table[4];
+    assert_eq!((check.address, check.own_size()), (0x1060, 0));
+    assert_eq!(check.clones[1].name, "check.constprop.1");
+    // Clones are still measured where they were placed
+    assert_eq!(symbol_table.total_size(), total);
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+use std::{fmt, str::FromStr};
+
+/// Shared CSS used by every HTML table the tool writes
+pub const TABLE_STYLE: &str = "table {
+    font-family: arial, sans-serif;
+    border-collapse: collapse;
+}
+
+td, th {
+    border: 1px solid #dddddd;
+    text-align: left;
+    padding: 8px;
+}
+
+tr:nth-child(even) {
+    background-color: #dddddd;
+}";
+
+/// The output formats every report can be rendered in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Html,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "html" => Ok(OutputFormat::Html),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!("unknown output format: {}", other)),
+        }
+    }
+}
+
+/// A report that can be written as plain text, HTML, JSON or CSV
+pub trait Report: fmt::Display + Serialize {
+    /// Converts the report into an HTML formatted string
+    fn to_html(&self) -> String;
+
+    /// Converts the report into comma separated values with a header row
+    fn to_csv(&self) -> String;
+
+    /// Converts the report into a JSON formatted string
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Renders the report in the requested output format
+    fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.to_string(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Csv => self.to_csv(),
+        }
+    }
+}
+
+/// Formats a size, marking sizes that were inferred from the next symbol rather than read from
+/// the symbol table, see `SymbolTable::infer_sizes`
+pub fn size_string(size: impl fmt::Display, inferred: bool) -> String {
+    if inferred {
+        format!("{} (inferred)", size)
+    } else {
+        size.to_string()
+    }
+}
+
+/// Formats a value as a CSV field, quoting it if it contains a separator, quote or newline
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Calculates what percentage a part is of a total, treating an empty total as zero
+pub fn percent(part: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
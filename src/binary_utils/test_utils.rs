@@ -0,0 +1,4 @@
+/// Converts objdump output written inline in a test into the lines the parsers take
+pub fn lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|x| x.to_string()).collect()
+}
@@ -1,7 +1,40 @@
-use std::{fs::File, io::{self, BufRead, Write}};
+use std::{collections::BTreeMap, fs::File, io::{self, BufRead, Write}};
+use binarytools::binary_utils::analysis::aliases::AliasReport;
+use binarytools::binary_utils::analysis::budget::Budget;
+use binarytools::binary_utils::analysis::call_graph::{CallGraph, CallGraphReport};
+use binarytools::binary_utils::analysis::code_hash::{self, CodeDiffReport};
+use binarytools::binary_utils::analysis::compare::CompareReport;
+use binarytools::binary_utils::analysis::components::{ComponentReport, ComponentRules};
+use binarytools::binary_utils::analysis::constructors::ConstructorReport;
+use binarytools::binary_utils::analysis::diff::DiffReport;
+use binarytools::binary_utils::analysis::gaps::GapReport;
+use binarytools::binary_utils::analysis::history::{History, HistoryQuery, HistoryStore, Snapshot};
+use binarytools::binary_utils::analysis::icf::IcfReport;
+use binarytools::binary_utils::analysis::instruction_mix::InstructionMixReport;
+use binarytools::binary_utils::analysis::kinds::{self, KindReport, SymbolKind};
+use binarytools::binary_utils::analysis::pareto::ParetoReport;
+use binarytools::binary_utils::analysis::relocations::RelocationReport;
+use binarytools::binary_utils::analysis::size::SizeReport;
+use binarytools::binary_utils::analysis::stack::{self, StackReport};
+use binarytools::binary_utils::analysis::summary::Summary;
+use binarytools::binary_utils::analysis::symbol_keys::SymbolKeys;
+use binarytools::binary_utils::analysis::tls::TlsReport;
+use binarytools::binary_utils::analysis::unreachable::{Roots, UnreachableReport};
+use binarytools::binary_utils::analysis::unwind::UnwindReport;
+use binarytools::binary_utils::analysis::weak::{ObjectSymbols, WeakReport};
+use binarytools::binary_utils::parser::call_graph_info::CallGraphInfo;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::eh_frame::EhFrame;
+use binarytools::binary_utils::parser::map_file::MapFile;
+use binarytools::binary_utils::parser::relocations::Relocations;
+use binarytools::binary_utils::parser::section_contents::SectionContents;
+use binarytools::binary_utils::parser::section_table::{self, SectionTable};
+use binarytools::binary_utils::parser::stack_usage::StackUsageTable;
 use binarytools::binary_utils::parser::symbol_table::{SymbolTable, SymbolTableEntry, SymbolType};
+use binarytools::binary_utils::report::{OutputFormat, Report};
+use binarytools::string_utils;
 extern crate clap;
-use clap::{App, Arg};
+use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand};
 extern crate atty;
 
 fn main() {
@@ -23,7 +56,12 @@ fn main() {
             .long("input")
             .value_name("input")
             .help("The disassembled binary file to parse")
-            .required(false))
+            .required(false)
+            .global(true))
+        .arg(Arg::with_name("infer-sizes")
+            .long("infer-sizes")
+            .help("Give zero sized symbols the size up to the next symbol or the end of their section. Include `objdump -h` output to find section ends")
+            .global(true))
         .arg(Arg::with_name("filter")
             .short("f")
             .long("filter")
@@ -36,29 +74,426 @@ fn main() {
             .value_name("filter_section")
             .help("Filter by section (e.g., .text, .bss, etc.)")
             .required(false))        
+        .arg(Arg::with_name("top")
+            .long("top")
+            .value_name("count")
+            .help("Only list this many of the largest symbols")
+            .global(true))
+        .arg(Arg::with_name("group-clones")
+            .long("group-clones")
+            .help("Fold compiler clones like foo.part.0, foo.cold and foo.constprop.3, and Rust closures, into the function they came from in the symbol listing and the check, record, summary, pareto and components commands")
+            .global(true))
+        .arg(Arg::with_name("kind")
+            .long("kind")
+            .value_name("kind")
+            .help("Only keep symbols of these kinds, e.g. vtable, typeinfo or clone. See the kinds command for the full list")
+            .multiple(true)
+            .number_of_values(1)
+            .global(true))
         .arg(Arg::with_name("html")
             .short("o")
             .long("html")
             .value_name("html")
             .help("Write formatted HTML output to a file: --html=filename.html")
-            .required(false));            
+            .required(false))
+        .subcommand(SubCommand::with_name("check")
+            .about("Checks the symbol table against a size budget and exits non-zero if any limit is exceeded")
+            .arg(Arg::with_name("budget")
+                .short("b")
+                .long("budget")
+                .value_name("budget")
+                .help("TOML file with the size limits to check against")
+                .required(true)))
+        .subcommand(SubCommand::with_name("record")
+            .about("Appends a labelled snapshot of the section, file and symbol sizes to a history store")
+            .arg(Arg::with_name("store")
+                .long("store")
+                .value_name("store")
+                .help("JSON lines file holding the size history")
+                .required(true))
+            .arg(Arg::with_name("build")
+                .long("build")
+                .value_name("build")
+                .help("Name of the build being recorded")
+                .required(true))
+            .arg(Arg::with_name("commit")
+                .long("commit")
+                .value_name("commit")
+                .help("Commit id the build was made from")
+                .required(false)))
+        .subcommand(SubCommand::with_name("history")
+            .about("Shows how a section, file or symbol changed size across the recorded snapshots")
+            .arg(Arg::with_name("store")
+                .long("store")
+                .value_name("store")
+                .help("JSON lines file holding the size history")
+                .required(true))
+            .arg(Arg::with_name("section")
+                .long("section")
+                .value_name("section")
+                .help("Section name to show the history of"))
+            .arg(Arg::with_name("file")
+                .long("file")
+                .value_name("file")
+                .help("Source file to show the history of"))
+            .arg(Arg::with_name("symbol")
+                .long("symbol")
+                .value_name("symbol")
+                .help("Symbol name to show the history of"))
+            .arg(Arg::with_name("build")
+                .long("build")
+                .value_name("build")
+                .help("Only show the snapshots of this build. Deltas are always against the previous snapshot of the same build")
+                .required(false))
+            .group(ArgGroup::with_name("query")
+                .args(&["section", "file", "symbol"])
+                .required(true))
+            .arg(Arg::with_name("min-delta")
+                .long("min-delta")
+                .value_name("bytes")
+                .help("Only show snapshots where the size changed by at least this many bytes")
+                .required(false)))
+        .subcommand(SubCommand::with_name("diff")
+            .about("Compares the input, as the new build, with the symbol table of an old build. Symbols are matched by keys that ignore the numbers and hashes compilers add to names, like completed.8060 or Rust's ::h0123456789abcdef")
+            .arg(Arg::with_name("old")
+                .long("old")
+                .value_name("file")
+                .help("objdump output of the old build")
+                .required(true))
+            .arg(Arg::with_name("keys")
+                .long("keys")
+                .value_name("keys")
+                .help("TOML file choosing the compilers whose built-in rules to use and adding regular expressions of its own"))
+            .arg(Arg::with_name("code")
+                .long("code")
+                .help("Compare the code of each function instead of its size, listing the functions that changed, only moved or are identical. Requires `objdump -d -t` output of both builds"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("compare")
+            .about("Compares the symbol sizes of several builds, like -O2, -Os and -Oz or different compilers and configurations, as a matrix with the smallest variant of each symbol and section marked")
+            .arg(Arg::with_name("variant")
+                .long("variant")
+                .value_name("label=file")
+                .help("objdump output of a variant and the label to show it as. Give one for each variant")
+                .multiple(true)
+                .number_of_values(1)
+                .required(true))
+            .arg(Arg::with_name("keys")
+                .long("keys")
+                .value_name("keys")
+                .help("TOML file choosing the compilers whose built-in rules to use and adding regular expressions of its own"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("summary")
+            .about("Summarizes the symbol sizes per section. Include `objdump -h` output to add the section header sizes")
+            .arg(Arg::with_name("format")
+                .long("format")
+                .value_name("format")
+                .help("Output format. Options: text, html, json, csv, or berkeley and sysv for GNU size compatible output")
+                .possible_values(&["text", "html", "json", "csv", "berkeley", "sysv"])
+                .default_value("text")))
+        .subcommand(SubCommand::with_name("pareto")
+            .about("Ranks symbols by size with their cumulative share of a section or the image, and how many make up the threshold")
+            .arg(Arg::with_name("section")
+                .long("section")
+                .value_name("section")
+                .help("Only rank the symbols of this section, e.g. .text"))
+            .arg(Arg::with_name("threshold")
+                .long("threshold")
+                .value_name("percent")
+                .help("Percentage of the total to count the largest symbols for")
+                .default_value("80"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("kinds")
+            .about("Groups symbols by the compiler artifact they are, like vtables, typeinfo, guard variables, string pools, jump tables, closures, drop glue and clones. Kinds: vtable, typeinfo, guard, string, jump_table, rust_anon, closure, drop_glue, clone, function, data, other")
+            .arg(Arg::with_name("section")
+                .long("section")
+                .value_name("section")
+                .help("Only group the symbols of this section, e.g. .rodata"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("components")
+            .about("Rolls symbol sizes up per component and owner using a rules file")
+            .arg(Arg::with_name("rules")
+                .short("r")
+                .long("rules")
+                .value_name("rules")
+                .help("TOML file mapping symbol, file, object and namespace patterns to components")
+                .required(true))
+            .arg(Arg::with_name("map")
+                .long("map")
+                .value_name("file")
+                .help("GNU ld map file, used to match the object path patterns"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("tls")
+            .about("Reports the thread local storage template, the block each thread gets and a RAM estimate for a number of threads. Include `objdump -h` output for the exact block layout")
+            .arg(Arg::with_name("threads")
+                .long("threads")
+                .value_name("count")
+                .help("Number of threads to estimate RAM for, counting the main thread")
+                .default_value("1"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("gaps")
+            .about("Lists the padding and unlabelled bytes between symbols in each section. Include `objdump -h` output to find gaps at section boundaries")
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("aliases")
+            .about("Lists symbols that share an address or overlap, and how much they inflate summed section sizes")
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("icf")
+            .about("Finds functions with identical bodies that identical code folding could merge. Requires `objdump -d` output")
+            .arg(Arg::with_name("min-size")
+                .long("min-size")
+                .value_name("bytes")
+                .help("Ignore functions smaller than this many bytes")
+                .default_value("0"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("mix")
+            .about("Lists the instruction count, encoding sizes, branches, loads, stores and literal pools of each function. Requires `objdump -d` output")
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("callgraph")
+            .about("Lists the callers, callees and cumulative code size of each function. Requires `objdump -d` and `objdump -t` output")
+            .arg(Arg::with_name("function")
+                .long("function")
+                .value_name("name")
+                .help("Only show this function and the functions it can reach"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("stack")
+            .about("Calculates the worst case stack usage of each entry point from the stack frames and call graph. Requires `objdump -d` output")
+            .arg(Arg::with_name("entry")
+                .long("entry")
+                .value_name("name")
+                .help("Entry point to analyze, e.g. main or an interrupt handler. Defaults to every function that is never called")
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("su")
+                .long("su")
+                .value_name("file")
+                .help("GCC -fstack-usage output to use instead of the frames estimated from the disassembly")
+                .multiple(true))
+            .arg(Arg::with_name("ci")
+                .long("ci")
+                .value_name("file")
+                .help("GCC -fcallgraph-info output to add calls and frames from")
+                .multiple(true))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("unreachable")
+            .about("Lists functions and objects that nothing reaches from the entry points, vector tables and constructors. Requires `objdump -d -t -s` output")
+            .arg(Arg::with_name("root")
+                .long("root")
+                .value_name("name")
+                .help("Extra symbol to treat as reachable, e.g. a function only called through a register")
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("exported")
+                .long("exported")
+                .help("Treat every global symbol as reachable, e.g. for a shared library"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("ctors")
+            .about("Lists the functions in the init and fini arrays, the code they reach and its total size. Requires `objdump -d -t -s` output")
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("unwind")
+            .about("Attributes .eh_frame, .eh_frame_hdr, .gcc_except_table and ARM exception table bytes to the functions they describe. Requires `objdump -h -t --dwarf=frames` output, plus `-s` for ARM")
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("relocs")
+            .about("Counts the dynamic relocations per symbol, estimates their startup cost and lists exported symbols that could be made hidden to avoid the PLT and GOT. Requires `objdump -h -t -R` output, or `objdump -t -r` for an object file")
+            .arg(Arg::with_name("bind-now")
+                .long("bind-now")
+                .help("Resolve the PLT at startup, as with `-z now` or LD_BIND_NOW"))
+            .arg(format_arg()))
+        .subcommand(SubCommand::with_name("weak")
+            .about("Lists weak definitions left in the image and weak references that resolved to 0. Requires `objdump -t` output")
+            .arg(Arg::with_name("objects")
+                .long("objects")
+                .value_name("file")
+                .help("`objdump -t` output of the linked objects and archives, used to find which definition won")
+                .multiple(true))
+            .arg(format_arg()));
 
     let matches = app.clone().get_matches();
 
+    // The history query only reads the store, so it doesn't need any input
+    if let ("history", Some(sub_matches)) = matches.subcommand() {
+        show_history(sub_matches);
+        return;
+    }
+    // Comparing variants reads each variant from its own file
+    if let ("compare", Some(sub_matches)) = matches.subcommand() {
+        print_comparison(sub_matches);
+        return;
+    }
+
     // Check if we already read the input from a pipe, otherwise get it from a file
-    let symbol_table = if lines.len() > 0 {
-        SymbolTable::from_lines(&lines)
-    } else {
+    if lines.is_empty() {
         match matches.value_of("input") {
-            Some(file) => SymbolTable::from_file(file).expect("Could not parse input file as a symbol table"),
+            Some(file) => lines = string_utils::read_lines_from_file(file).expect("Could not read input file"),
             None => {
                 eprintln!("ERROR: missing input file");
                 app.print_long_help().unwrap();                
                 std::process::exit(1);
             }
         }
+    }
+    let mut symbol_table = SymbolTable::from_lines(&lines);
+    let section_table = SectionTable::from_lines(&lines);
+    if matches.is_present("infer-sizes") {
+        symbol_table.infer_sizes(Some(&section_table));
+    }
+    symbol_table.attach_relocations(&Relocations::from_lines(&lines));
+    // Percentages stay relative to the whole sections when only some kinds of symbols are kept
+    let section_sizes = symbol_table.section_sizes();
+    symbol_table = filter_kinds(symbol_table, &matches);
+    // Only the size reports group clones, the others need each symbol where it was placed
+    let size_report = matches!(
+        matches.subcommand_name(),
+        None | Some("check") | Some("record") | Some("summary") | Some("pareto") | Some("components") | Some("diff")
+    );
+    if matches.is_present("group-clones") && size_report {
+        symbol_table.group_clones();
+    }
+
+    match matches.subcommand() {
+        ("check", Some(sub_matches)) => check_budget(&symbol_table, sub_matches),
+        ("record", Some(sub_matches)) => record_snapshot(&symbol_table, sub_matches),
+        ("diff", Some(sub_matches)) => print_diff(&symbol_table, &lines, sub_matches),
+        ("summary", Some(sub_matches)) => {
+            // Name the binary the same way as `size`, falling back to the name of the input file
+            let filename = lines.iter()
+                .find_map(|x| section_table::parse_file_format(x).ok())
+                .map(|(_, (name, _))| name)
+                .or_else(|| matches.value_of("input"))
+                .unwrap_or("(stdin)");
+            print_summary(filename, &symbol_table, &section_table, &section_sizes, sub_matches)
+        }
+        ("pareto", Some(sub_matches)) => {
+            let top = matches.value_of("top").map(|x| x.parse().expect("Top must be a number of symbols"));
+            let threshold = sub_matches.value_of("threshold").unwrap().parse().expect("Threshold must be a percentage");
+            let report = ParetoReport::new(symbol_table, Some(&section_sizes), sub_matches.value_of("section"), top, threshold);
+            print_report(&report, sub_matches);
+        }
+        ("kinds", Some(sub_matches)) => {
+            let report = KindReport::new(&symbol_table, Some(&section_table), Some(&section_sizes), sub_matches.value_of("section"));
+            print_report(&report, sub_matches);
+        }
+        ("components", Some(sub_matches)) => print_components(&symbol_table, &section_sizes, sub_matches),
+        ("tls", Some(sub_matches)) => {
+            let threads = sub_matches.value_of("threads").unwrap().parse().expect("Threads must be a number");
+            print_report(&TlsReport::new(&symbol_table, Some(&section_table), threads), sub_matches);
+        }
+        ("gaps", Some(sub_matches)) => {
+            let disassembly = Disassembly::from_lines(&lines);
+            let report = GapReport::new(&symbol_table, Some(&section_table), Some(&disassembly));
+            print_report(&report, sub_matches);
+        }
+        ("aliases", Some(sub_matches)) => print_report(&AliasReport::new(&symbol_table), sub_matches),
+        ("icf", Some(sub_matches)) => {
+            let min_size = sub_matches.value_of("min-size").unwrap().parse().expect("Minimum size must be a number of bytes");
+            let report = IcfReport::new(&Disassembly::from_lines(&lines), min_size);
+            print_report(&report, sub_matches);
+        }
+        ("mix", Some(sub_matches)) => print_report(&InstructionMixReport::new(&Disassembly::from_lines(&lines)), sub_matches),
+        ("callgraph", Some(sub_matches)) => {
+            let disassembly = Disassembly::from_lines(&lines);
+            let graph = CallGraph::new(&disassembly, &symbol_table);
+            let function = sub_matches.value_of("function");
+            if let Some(name) = function {
+                if graph.find(name).is_none() {
+                    eprintln!("ERROR: function {} not found in the disassembly", name);
+                    std::process::exit(1);
+                }
+            }
+            print_report(&CallGraphReport::new(&graph, function), sub_matches);
+        }
+        ("stack", Some(sub_matches)) => print_stack_usage(symbol_table, &lines, sub_matches),
+        ("unreachable", Some(sub_matches)) => {
+            let roots = Roots {
+                symbols: sub_matches.values_of("root").into_iter().flatten().collect(),
+                exported: sub_matches.is_present("exported"),
+            };
+            let disassembly = Disassembly::from_lines(&lines);
+            let contents = SectionContents::from_lines(&lines);
+            print_report(&UnreachableReport::new(&symbol_table, &disassembly, &contents, &roots), sub_matches);
+        }
+        ("ctors", Some(sub_matches)) => {
+            let disassembly = Disassembly::from_lines(&lines);
+            let graph = CallGraph::new(&disassembly, &symbol_table);
+            let contents = SectionContents::from_lines(&lines);
+            print_report(&ConstructorReport::new(&graph, &contents, &symbol_table), sub_matches);
+        }
+        ("unwind", Some(sub_matches)) => {
+            let eh_frame = EhFrame::from_lines(&lines);
+            let contents = SectionContents::from_lines(&lines);
+            print_report(&UnwindReport::new(&symbol_table, &section_table, &eh_frame, &contents), sub_matches);
+        }
+        ("relocs", Some(sub_matches)) => {
+            let relocations = Relocations::from_lines(&lines);
+            let report = RelocationReport::new(&symbol_table, &section_table, &relocations, sub_matches.is_present("bind-now"));
+            print_report(&report, sub_matches);
+        }
+        ("weak", Some(sub_matches)) => {
+            let mut objects = Vec::new();
+            for file in sub_matches.values_of("objects").into_iter().flatten() {
+                match string_utils::read_lines_from_file(file) {
+                    Ok(lines) => objects.extend(ObjectSymbols::from_lines(&lines)),
+                    Err(e) => {
+                        eprintln!("ERROR: could not read {}: {}", file, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            print_report(&WeakReport::new(&symbol_table, &objects), sub_matches);
+        }
+        _ => print_symbol_table(symbol_table, &matches),
+    }
+}
+
+/// Creates the output format option shared by every report subcommand
+fn format_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("format")
+        .long("format")
+        .value_name("format")
+        .help("Output format. Options: text, html, json, csv")
+        .possible_values(&["text", "html", "json", "csv"])
+        .default_value("text")
+}
+
+/// Prints a report in the output format selected on the command line
+fn print_report(report: &impl Report, matches: &ArgMatches) {
+    let format: OutputFormat = matches.value_of("format").unwrap().parse().unwrap();
+    print!("{}", report.render(format));
+}
+
+/// Prints the worst case stack usage, using the compiler's stack usage and call graph files when given
+fn print_stack_usage(mut symbol_table: SymbolTable, lines: &[String], matches: &ArgMatches) {
+    let load = |name: &str| -> Vec<String> {
+        let mut lines = Vec::new();
+        for file in matches.values_of(name).into_iter().flatten() {
+            match string_utils::read_lines_from_file(file) {
+                Ok(x) => lines.extend(x),
+                Err(e) => {
+                    eprintln!("ERROR: could not read {}: {}", file, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        lines
     };
-    
+    let stack_usage = StackUsageTable::from_lines(&load("su"));
+    let info = CallGraphInfo::from_lines(&load("ci"));
+    symbol_table.attach_stack_usage(&stack_usage);
+
+    let disassembly = Disassembly::from_lines(lines);
+    let mut graph = CallGraph::new(&disassembly, &symbol_table);
+    graph.add_call_graph_info(&info, &symbol_table);
+    let mut frames = stack::disassembly_frames(&disassembly, &graph);
+    stack::apply_compiler_frames(&mut frames, &graph, &symbol_table, Some(&info));
+
+    let entries: Vec<&str> = matches.values_of("entry").map(|x| x.collect()).unwrap_or_default();
+    if let Some(entry) = entries.iter().find(|x| graph.find(x).is_none()) {
+        eprintln!("ERROR: function {} not found in the call graph", entry);
+        std::process::exit(1);
+    }
+    print_report(&StackReport::from_frames(&graph, &frames, &entries), matches);
+}
+
+/// Filters, sorts and prints the symbol table as text or HTML
+fn print_symbol_table(symbol_table: SymbolTable, matches: &ArgMatches) {
     let filter: Option<fn(&SymbolTableEntry) -> bool> = match matches.value_of("filter") {
         Some("objects")   => Some(|x| x.flags.symbol_type == SymbolType::Object),
         Some("functions") => Some(|x| x.flags.symbol_type == SymbolType::Function),
@@ -78,7 +513,10 @@ fn main() {
             .collect::<SymbolTable>();        
     }
     filtered.sort_by_size_descending();
-    
+    if let Some(top) = matches.value_of("top") {
+        filtered.truncate(top.parse().expect("Top must be a number of symbols"));
+    }
+
     match matches.value_of("html") {
         Some(filename) => {
             let mut object_table = File::create(filename).expect(&format!("Could not create file: {}", filename));
@@ -88,3 +526,165 @@ fn main() {
     }
 }
 
+/// Evaluates the symbol table against a budget file, exiting with the budget's exit code on failure
+fn check_budget(symbol_table: &SymbolTable, matches: &ArgMatches) {
+    let filename = matches.value_of("budget").unwrap();
+    let budget = Budget::from_file(filename).unwrap_or_else(|e| {
+        eprintln!("ERROR: could not load budget file {}: {}", filename, e);
+        std::process::exit(1);
+    });
+    let report = budget.evaluate(symbol_table);
+    print!("{}", report);
+    if !report.passed() {
+        std::process::exit(report.exit_code);
+    }
+}
+
+/// Attributes the symbol table to the components of a rules file, reading object paths from a map file if given
+fn print_components(symbol_table: &SymbolTable, section_sizes: &BTreeMap<String, u32>, matches: &ArgMatches) {
+    let filename = matches.value_of("rules").unwrap();
+    let rules = ComponentRules::from_file(filename).unwrap_or_else(|e| {
+        eprintln!("ERROR: could not load rules file {}: {}", filename, e);
+        std::process::exit(1);
+    });
+    let map_file = matches.value_of("map").map(|filename| {
+        MapFile::from_file(filename).unwrap_or_else(|e| {
+            eprintln!("ERROR: could not read map file {}: {}", filename, e);
+            std::process::exit(1);
+        })
+    });
+    print_report(&ComponentReport::new(symbol_table, &rules, map_file.as_ref(), Some(section_sizes)), matches);
+}
+
+/// Appends a snapshot of the symbol table sizes to the history store
+fn record_snapshot(symbol_table: &SymbolTable, matches: &ArgMatches) {
+    let store = HistoryStore::new(matches.value_of("store").unwrap());
+    let snapshot = Snapshot::new(symbol_table, matches.value_of("build").unwrap(), matches.value_of("commit"));
+    store.record(&snapshot).unwrap_or_else(|e| {
+        eprintln!("ERROR: could not record snapshot: {}", e);
+        std::process::exit(1);
+    });
+}
+
+/// Compares the symbol table, or the code of each function, with an old build, using the key rules file if given
+fn print_diff(symbol_table: &SymbolTable, lines: &[String], matches: &ArgMatches) {
+    let keys = load_keys(matches);
+    let old_lines = read_build(matches.value_of("old").unwrap());
+    // The old build gets the same kind filter and clone grouping as the input
+    let mut old = filter_kinds(build_symbol_table(&old_lines, matches.is_present("infer-sizes")), matches);
+    if matches.is_present("group-clones") {
+        old.group_clones();
+    }
+    if matches.is_present("code") {
+        let old_hashes = code_hash::hash_functions(&Disassembly::from_lines(&old_lines), &old, &keys);
+        let new_hashes = code_hash::hash_functions(&Disassembly::from_lines(lines), symbol_table, &keys);
+        print_report(&CodeDiffReport::new(&old_hashes, &new_hashes), matches);
+    } else {
+        print_report(&DiffReport::new(&old, symbol_table, &keys), matches);
+    }
+}
+
+/// Compares the symbol sizes of every variant given on the command line
+fn print_comparison(matches: &ArgMatches) {
+    let keys = load_keys(matches);
+    let mut labels = Vec::new();
+    let mut tables = Vec::new();
+    for variant in matches.values_of("variant").unwrap() {
+        // Variants without a label are shown by their file name
+        let (label, filename) = variant.split_once('=').unwrap_or((variant, variant));
+        labels.push(label);
+        // Every variant gets the same kind filter and clone grouping as the input of the other commands
+        let mut symbol_table = filter_kinds(build_symbol_table(&read_build(filename), matches.is_present("infer-sizes")), matches);
+        if matches.is_present("group-clones") {
+            symbol_table.group_clones();
+        }
+        tables.push(symbol_table);
+    }
+    if tables.len() < 2 {
+        eprintln!("ERROR: compare needs at least two variants");
+        std::process::exit(1);
+    }
+    let variants: Vec<(&str, &SymbolTable)> = labels.into_iter().zip(&tables).collect();
+    print_report(&CompareReport::new(&variants, &keys), matches);
+}
+
+/// Keeps only the symbols of the kinds given with the global `--kind` option, if any
+fn filter_kinds(symbol_table: SymbolTable, matches: &ArgMatches) -> SymbolTable {
+    let kinds: Vec<SymbolKind> = match matches.values_of("kind") {
+        Some(values) => values
+            .map(|x| x.parse().unwrap_or_else(|e| {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(1);
+            }))
+            .collect(),
+        None => return symbol_table,
+    };
+    // File symbols are kept so local symbols can still be attributed to their source file
+    symbol_table.into_iter()
+        .filter(|x| x.flags.symbol_type == SymbolType::File || kinds.contains(&kinds::classify_symbol(x)))
+        .collect()
+}
+
+/// Loads the symbol key rules file if given, or the built-in rules of every compiler
+fn load_keys(matches: &ArgMatches) -> SymbolKeys {
+    match matches.value_of("keys") {
+        Some(filename) => SymbolKeys::from_file(filename).unwrap_or_else(|e| {
+            eprintln!("ERROR: could not load keys file {}: {}", filename, e);
+            std::process::exit(1);
+        }),
+        None => SymbolKeys::default(),
+    }
+}
+
+/// Reads the objdump output of another build
+fn read_build(filename: &str) -> Vec<String> {
+    string_utils::read_lines_from_file(filename).unwrap_or_else(|e| {
+        eprintln!("ERROR: could not read {}: {}", filename, e);
+        std::process::exit(1);
+    })
+}
+
+/// Creates the symbol table of another build, inferring missing sizes the same way as for the input
+fn build_symbol_table(lines: &[String], infer_sizes: bool) -> SymbolTable {
+    let mut symbol_table = SymbolTable::from_lines(lines);
+    if infer_sizes {
+        symbol_table.infer_sizes(Some(&SectionTable::from_lines(lines)));
+    }
+    symbol_table
+}
+
+/// Prints how a section, file or symbol changed across the recorded snapshots
+fn show_history(matches: &ArgMatches) {
+    let store = HistoryStore::new(matches.value_of("store").unwrap());
+    let snapshots = store.snapshots().unwrap_or_else(|e| {
+        eprintln!("ERROR: could not read history store: {}", e);
+        std::process::exit(1);
+    });
+    let query = if let Some(section) = matches.value_of("section") {
+        HistoryQuery::Section(section.to_string())
+    } else if let Some(file) = matches.value_of("file") {
+        HistoryQuery::File(file.to_string())
+    } else {
+        HistoryQuery::Symbol(matches.value_of("symbol").unwrap().to_string())
+    };
+    let mut history = History::new(&snapshots, query, matches.value_of("build"));
+    if let Some(min_delta) = matches.value_of("min-delta") {
+        history = history.changes(min_delta.parse().expect("Minimum delta must be a number of bytes"));
+    }
+    print!("{}", history);
+}
+
+/// Prints the per section totals of the symbol table
+fn print_summary(
+    filename: &str,
+    symbol_table: &SymbolTable,
+    section_table: &SectionTable,
+    section_sizes: &BTreeMap<String, u32>,
+    matches: &ArgMatches,
+) {
+    match matches.value_of("format").unwrap() {
+        "berkeley" => print!("{}", SizeReport::new(filename, symbol_table, Some(section_table)).to_berkeley()),
+        "sysv" => print!("{}", SizeReport::new(filename, symbol_table, Some(section_table)).to_sysv()),
+        _ => print_report(&Summary::new(symbol_table, Some(section_table), Some(section_sizes)), matches),
+    }
+}
@@ -41,3 +41,41 @@ pub fn split_into_tuple(s: &str, d: char) -> Option<(&str, &str)> {
         None => None,
     }
 }
+
+/// Match a string against a simple wildcard pattern where '*' matches any run of characters and
+/// '?' matches exactly one character. Returns true if the whole string matches the pattern.
+///
+/// # Arguments
+/// * 'pattern' - The wildcard pattern to match against
+/// * 's' - The string slice to test
+///
+/// # Examples
+/// ```
+/// use binarytools::string_utils;
+/// assert!(string_utils::matches_wildcard("print_*", "print_buffer"));
+/// assert!(string_utils::matches_wildcard("*.c", "hello_world.c"));
+/// assert!(string_utils::matches_wildcard("f?o", "foo"));
+/// assert!(!string_utils::matches_wildcard("print_*", "sprint_buffer"));
+/// ```
+pub fn matches_wildcard(pattern: &str, s: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut p, mut i) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, i));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            i = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
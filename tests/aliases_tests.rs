@@ -0,0 +1,14 @@
+use binarytools::binary_utils::analysis::aliases::AliasReport;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_data_start_aliases() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/hello_world.txt")?;
+    let report = AliasReport::new(&symbol_table);
+    let group = report.groups.iter().find(|x| x.address == 0x4010).unwrap();
+    assert_eq!(group.section, ".data");
+    assert_eq!(group.canonical, "__data_start");
+    assert_eq!(group.aliases, vec!["data_start".to_string()]);
+    assert!(report.overlaps.is_empty());
+    Ok(())
+}
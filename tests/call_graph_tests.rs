@@ -0,0 +1,19 @@
+use binarytools::binary_utils::analysis::call_graph::CallGraph;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_call_graph_from_file() -> Result<(), std::io::Error> {
+    let disassembly = Disassembly::from_file("tests/hello_world.txt")?;
+    let symbol_table = SymbolTable::from_file("tests/hello_world.txt")?;
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+
+    let main = graph.find("main").unwrap();
+    assert_eq!(graph.names(&main.callees), vec!["printf@plt", "snprintf@plt"]);
+    // frame_dummy tail calls register_tm_clones
+    let callers: Vec<&str> = graph.callers(graph.find("register_tm_clones").unwrap()).iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(callers, vec!["frame_dummy"]);
+    assert_eq!(main.size, 0x48);
+    assert_eq!(graph.cumulative_size(main, &graph.reachable(main)), 0x48 + 0x10 + 0x10 + 0x10);
+    Ok(())
+}
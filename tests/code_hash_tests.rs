@@ -0,0 +1,34 @@
+use binarytools::binary_utils::analysis::code_hash::{self, CodeDiffReport, CodeStatus};
+use binarytools::binary_utils::analysis::symbol_keys::SymbolKeys;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_code_diff_from_files() -> Result<(), std::io::Error> {
+    let keys = SymbolKeys::default();
+    let hashes = |filename: &str| -> Result<_, std::io::Error> {
+        let lines = string_utils::read_lines_from_file(filename)?;
+        Ok(code_hash::hash_functions(
+            &Disassembly::from_lines(&lines),
+            &SymbolTable::from_lines(&lines),
+            &keys,
+        ))
+    };
+    let report = CodeDiffReport::new(&hashes("tests/code_old.txt")?, &hashes("tests/code_new.txt")?);
+
+    let names = |status| -> Vec<&str> { report.with_status(status).map(|x| x.key.as_str()).collect() };
+    // main calls clamp instead of legacy, and threshold compares against 12 instead of 10
+    assert_eq!(names(CodeStatus::Changed), vec!["main", "threshold"]);
+    // lookup was pushed back by clamp, but calls the same function and reads the same variables
+    assert_eq!(names(CodeStatus::Moved), vec!["lookup"]);
+    assert_eq!(names(CodeStatus::Added), vec!["clamp"]);
+    assert_eq!(names(CodeStatus::Removed), vec!["legacy"]);
+    assert!(names(CodeStatus::Identical).contains(&"scale"));
+    assert_eq!(report.with_status(CodeStatus::Identical).count(), 6);
+
+    let lookup = report.find("lookup").unwrap();
+    assert_eq!((lookup.old_address, lookup.new_address), (Some(0x11a0), Some(0x11c0)));
+    assert_eq!(lookup.old_hash, lookup.new_hash);
+    Ok(())
+}
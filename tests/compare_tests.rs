@@ -0,0 +1,28 @@
+use binarytools::binary_utils::analysis::compare::CompareReport;
+use binarytools::binary_utils::analysis::symbol_keys::SymbolKeys;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_compare_optimization_levels() -> Result<(), std::io::Error> {
+    let tables = [
+        SymbolTable::from_file("tests/compare_O0.txt")?,
+        SymbolTable::from_file("tests/compare_O2.txt")?,
+        SymbolTable::from_file("tests/compare_Os.txt")?,
+    ];
+    let variants: Vec<(&str, &SymbolTable)> = ["O0", "O2", "Os"].iter().copied().zip(&tables).collect();
+    let report = CompareReport::new(&variants, &SymbolKeys::default());
+
+    assert_eq!(report.variants, vec!["O0", "O2", "Os"]);
+    let main = report.find("main").unwrap();
+    assert_eq!((main.sizes.clone(), main.best), (vec![Some(74), Some(43), Some(37)], Some(2)));
+    // The static counter is keyed by its file, and has the same size everywhere
+    assert_eq!(report.find("v3.c:counter").unwrap().best, None);
+    // helper is inlined once optimization is on
+    let partial: Vec<&str> = report.partial().map(|x| x.key.as_str()).collect();
+    assert_eq!(partial, vec!["v3.c:helper"]);
+
+    let text = report.sections.iter().find(|x| x.section == ".text").unwrap();
+    assert_eq!((text.sizes.clone(), text.best), (vec![249, 129, 122], Some(2)));
+    assert_eq!(report.totals, vec![306, 186, 179]);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use binarytools::binary_utils::analysis::components::{ComponentReport, ComponentRules, UNASSIGNED};
+use binarytools::binary_utils::parser::map_file::MapFile;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::binary_utils::report::Report;
+
+#[test]
+fn test_component_report_from_file() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/components.txt")?;
+    let rules = ComponentRules::from_file("tests/components.toml")?;
+    let map_file = MapFile::from_file("tests/components.map")?;
+    let report = ComponentReport::new(&symbol_table, &rules, Some(&map_file), None);
+
+    let sizes: Vec<(&str, u32)> = report.components.iter().map(|x| (x.component.as_str(), x.size)).collect();
+    assert_eq!(
+        sizes,
+        vec![
+            ("freertos", 480),
+            ("lwip", 192),
+            ("vendor_hal", 84),
+            ("app", 64),
+            ("sensor", 52),
+            (UNASSIGNED, 8),
+        ]
+    );
+    // The local uart_state object is only attributed through the archive member that defines it
+    assert_eq!(report.find("vendor_hal").unwrap().section_size(".bss"), 4);
+
+    let owners: Vec<(&str, u32)> = report.owners.iter().map(|x| (x.owner.as_str(), x.size)).collect();
+    assert_eq!(
+        owners,
+        vec![("@rtos-team", 480), ("@networking", 244), ("@platform", 84), ("@app-team", 64)]
+    );
+    assert_eq!(report.total_size, 880);
+    Ok(())
+}
+
+#[test]
+fn test_object_rules_need_a_map_file() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/components.txt")?;
+    let rules = ComponentRules::from_file("tests/components.toml")?;
+    let report = ComponentReport::new(&symbol_table, &rules, None, None);
+
+    assert!(report.find("vendor_hal").is_none());
+    assert_eq!(report.find(UNASSIGNED).unwrap().size, 92);
+    Ok(())
+}
+
+#[test]
+fn test_map_file_from_file() -> Result<(), std::io::Error> {
+    let map_file = MapFile::from_file("tests/components.map")?;
+
+    // Sections discarded by the linker are not part of the map
+    assert!(!map_file.iter().any(|x| x.section == ".text.HAL_UART_Receive"));
+    assert_eq!(map_file.len(), 14);
+    let memcpy = map_file.find(0x08000404).unwrap();
+    assert_eq!(memcpy.object, "/opt/gcc-arm/arm-none-eabi/lib/thumb/libc_nano.a(memcpy.o)");
+    Ok(())
+}
+
+#[test]
+fn test_component_json() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/components.txt")?;
+    let rules = ComponentRules::from_file("tests/components.toml")?;
+    let json = ComponentReport::new(&symbol_table, &rules, None, None).to_json();
+
+    assert!(json.contains("\"component\": \"freertos\""));
+    assert!(json.contains("\"owner\": \"@rtos-team\""));
+    Ok(())
+}
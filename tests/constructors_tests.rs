@@ -0,0 +1,37 @@
+use binarytools::binary_utils::analysis::call_graph::CallGraph;
+use binarytools::binary_utils::analysis::constructors::{ConstructorReport, Phase};
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::section_contents::SectionContents;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_constructor_report_from_file() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/unreachable.txt")?;
+    let disassembly = Disassembly::from_file("tests/unreachable.txt")?;
+    let contents = SectionContents::from_file("tests/unreachable.txt")?;
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = ConstructorReport::new(&graph, &contents, &symbol_table);
+
+    let functions: Vec<(Phase, Option<&str>)> = report
+        .constructors
+        .iter()
+        .map(|x| (x.phase, x.function.as_deref()))
+        .collect();
+    assert_eq!(
+        functions,
+        vec![
+            (Phase::Init, Some("frame_dummy")),
+            (Phase::Init, Some("setup")),
+            (Phase::Fini, Some("__do_global_dtors_aux")),
+        ]
+    );
+    // frame_dummy tail calls register_tm_clones
+    assert_eq!(report.constructors[0].reachable, vec!["register_tm_clones".to_string()]);
+    assert_eq!(report.table_size, 0x18);
+    let cumulative_size = |name: &str| {
+        let node = graph.find(name).unwrap();
+        graph.cumulative_size(node, &graph.reachable(node))
+    };
+    assert_eq!(report.init_size, cumulative_size("frame_dummy") + cumulative_size("setup"));
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use binarytools::binary_utils::analysis::diff::{DiffReport, DiffStatus};
+use binarytools::binary_utils::analysis::symbol_keys::SymbolKeys;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_diff_report_from_files() -> Result<(), std::io::Error> {
+    let old = SymbolTable::from_lines(&string_utils::read_lines_from_file("tests/diff_old.txt")?);
+    let new = SymbolTable::from_lines(&string_utils::read_lines_from_file("tests/diff_new.txt")?);
+    let report = DiffReport::new(&old, &new, &SymbolKeys::default());
+
+    // Only the real changes are left once the static local numbers and Rust hashes are ignored
+    let changes: Vec<(&str, DiffStatus, i64)> = report
+        .symbols
+        .iter()
+        .map(|x| (x.key.as_str(), x.status, x.delta))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("dma_poll", DiffStatus::Added, 0x44),
+            ("legacy_poll", DiffStatus::Removed, -0x20),
+            ("sensor_read", DiffStatus::Changed, 0x18),
+        ]
+    );
+    assert_eq!(report.unchanged, 8);
+    assert_eq!(report.delta, 0x3c);
+
+    let keys: SymbolKeys = "compilers = []".parse()?;
+    let noisy = DiffReport::new(&old, &new, &keys);
+    assert_eq!(noisy.symbols.len(), 11);
+    assert_eq!(noisy.find("uart.c:rx_buffer.1").map(|x| x.status), None);
+    Ok(())
+}
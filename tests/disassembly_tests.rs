@@ -0,0 +1,12 @@
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+
+#[test]
+fn test_read_disassembly_from_file() -> Result<(), std::io::Error> {
+    let disassembly = Disassembly::from_file("tests/hello_world.txt")?;
+    assert_eq!(disassembly.format, Some("elf64-x86-64".to_string()));
+    let main = disassembly.find("main").unwrap();
+    assert_eq!(main.section, ".text");
+    assert_eq!(main.address, 0x1060);
+    assert!(main.size() >= 0x48);
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use binarytools::binary_utils::analysis::history::{History, HistoryQuery, HistoryStore, Snapshot};
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_record_and_read_history_store() -> Result<(), std::io::Error> {
+    let path = std::env::temp_dir().join(format!("binary-tools-history-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = HistoryStore::new(&path);
+    let symbol_table = SymbolTable::from_file("tests/symbol_table.txt")?;
+    store.record(&Snapshot::new(&symbol_table, "debug", Some("abc123")))?;
+    store.record(&Snapshot::new(&symbol_table, "release", None))?;
+
+    let snapshots = store.snapshots()?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].commit, Some("abc123".to_string()));
+    assert_eq!(snapshots[1].build, "release");
+
+    let history = History::new(&snapshots, HistoryQuery::Section(".tbss".to_string()), None);
+    assert_eq!(history.points.len(), 2);
+    assert_eq!(history.points[1].delta, 0);
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use binarytools::binary_utils::analysis::instruction_mix::InstructionMixReport;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+
+#[test]
+fn test_instruction_mix_from_file() -> Result<(), std::io::Error> {
+    let disassembly = Disassembly::from_file("tests/hello_world.txt")?;
+    let report = InstructionMixReport::new(&disassembly);
+
+    let main = &report.functions.iter().find(|x| x.function == "main").unwrap().mix;
+    assert!(main.instructions > 0);
+    assert!(main.branches >= 2);
+    assert_eq!(main.literal_pool_bytes, 0);
+
+    let instructions: u32 = report.functions.iter().map(|x| x.mix.instructions).sum();
+    assert_eq!(report.total.instructions, instructions);
+    assert!(report.total.code_bytes <= disassembly.iter().map(|x| x.size()).sum());
+    Ok(())
+}
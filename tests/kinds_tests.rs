@@ -0,0 +1,31 @@
+use binarytools::binary_utils::analysis::kinds::{KindReport, SymbolKind};
+use binarytools::binary_utils::parser::section_table::SectionTable;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_kind_report_from_file() -> Result<(), std::io::Error> {
+    let lines = string_utils::read_lines_from_file("tests/kinds.txt")?;
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let section_table = SectionTable::from_lines(&lines);
+    let report = KindReport::new(&symbol_table, Some(&section_table), None, Some(".rodata"));
+
+    let rodata: Vec<(SymbolKind, u32, usize)> = report.sections[0]
+        .kinds
+        .iter()
+        .map(|x| (x.kind, x.size, x.symbols))
+        .collect();
+    assert_eq!(
+        rodata,
+        vec![(SymbolKind::JumpTable, 24, 1), (SymbolKind::Typeinfo, 23, 3), (SymbolKind::Data, 4, 1)]
+    );
+    // Percentages are of the 64 byte section header, not just the bytes covered by symbols
+    assert_eq!(report.total_size, 64);
+    assert_eq!(report.find(".rodata", SymbolKind::JumpTable).unwrap().percent, 37.5);
+
+    let image = KindReport::new(&symbol_table, Some(&section_table), None, None);
+    assert_eq!(image.find(".data.rel.ro", SymbolKind::Vtable).unwrap().size, 80);
+    assert_eq!(image.find(".bss", SymbolKind::Guard).unwrap().symbols, 1);
+    assert_eq!(image.find(".text", SymbolKind::Clone).unwrap().size, 18);
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use binarytools::binary_utils::analysis::pareto::ParetoReport;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_pareto_from_file() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/symbol_table.txt")?;
+    let total = symbol_table.total_size();
+    let report = ParetoReport::new(symbol_table, None, None, Some(10), 80.0);
+
+    assert_eq!(report.entries.len(), 10);
+    assert_eq!(report.total_size, total);
+    assert!(report.entries.windows(2).all(|x| x[0].size >= x[1].size));
+    assert!(report.entries.windows(2).all(|x| x[1].cumulative_size == x[0].cumulative_size + x[1].size));
+    assert!(report.threshold_count > 0 && report.threshold_count <= report.symbol_count);
+    Ok(())
+}
+
+#[test]
+fn test_pareto_with_grouped_clones_from_file() -> Result<(), std::io::Error> {
+    let mut symbol_table = SymbolTable::from_file("tests/kinds.txt")?;
+    let text_size = symbol_table.section_sizes()[".text"];
+    symbol_table.group_clones();
+    let report = ParetoReport::new(symbol_table, None, Some(".text"), None, 80.0);
+
+    // scale() itself was only kept as a constant propagated clone
+    let scale = report.entries.iter().find(|x| x.name == "_ZL5scaleii").unwrap();
+    assert_eq!(scale.size, 0x12);
+    assert_eq!(scale.clones[0].name, "_ZL5scaleii.constprop.0");
+    assert!(report.entries.iter().all(|x| !x.name.contains(".constprop")));
+    // Grouping moves sizes between rows without changing the section total
+    assert_eq!(report.total_size, text_size);
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use binarytools::binary_utils::analysis::relocations::RelocationReport;
+use binarytools::binary_utils::parser::relocations::Relocations;
+use binarytools::binary_utils::parser::section_table::SectionTable;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_relocation_report_from_file() -> Result<(), std::io::Error> {
+    let lines = string_utils::read_lines_from_file("tests/relocations.txt")?;
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let section_table = SectionTable::from_lines(&lines);
+    let relocations = Relocations::from_lines(&lines);
+    assert_eq!(relocations.len(), 16);
+    // The relocation records don't look like symbols
+    assert_eq!(symbol_table.iter().filter(|x| x.name.contains("R_X86_64")).count(), 0);
+
+    let report = RelocationReport::new(&symbol_table, &section_table, &relocations, false);
+    assert!(report.dynamic);
+    assert_eq!((report.relative, report.symbol_lookups, report.lazy_lookups), (6, 6, 4));
+    assert_eq!(report.dirty_pages, 2);
+    let candidates: Vec<&str> = report.candidates().map(|x| x.symbol.as_str()).collect();
+    assert_eq!(candidates, vec!["api_entry", "counter", "helper", "names"]);
+    assert!(!report.find("strlen").unwrap().candidate);
+
+    let sizes: Vec<(&str, u32)> = report.sections.iter().map(|x| (x.section.as_str(), x.size)).collect();
+    assert_eq!(
+        sizes,
+        vec![(".plt", 0x50), (".plt.got", 8), (".got", 0x30), (".got.plt", 0x38), (".rela.dyn", 0x120), (".rela.plt", 0x60)]
+    );
+
+    let bind_now = RelocationReport::new(&symbol_table, &section_table, &relocations, true);
+    assert_eq!((bind_now.symbol_lookups, bind_now.lazy_lookups), (10, 0));
+    Ok(())
+}
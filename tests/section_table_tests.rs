@@ -0,0 +1,12 @@
+use binarytools::binary_utils::parser::section_table::SectionTable;
+
+#[test]
+fn test_read_section_table_from_file() -> Result<(), std::io::Error> {
+    let section_table = SectionTable::from_file("tests/hello_world.txt")?;
+    assert_eq!(27, section_table.len());
+    let text = section_table.find(".text").unwrap();
+    assert_eq!(text.size, 0x139);
+    assert!(text.is_code());
+    assert!(!section_table.find(".bss").unwrap().has_contents());
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use binarytools::binary_utils::analysis::size::SizeReport;
+use binarytools::binary_utils::parser::section_table::SectionTable;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+// Expected outputs are from GNU size 2.40 run on the binary that tests/hello_world.txt was dumped from
+#[test]
+fn test_berkeley_format_matches_gnu_size() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/hello_world.txt")?;
+    let section_table = SectionTable::from_file("tests/hello_world.txt")?;
+    let report = SizeReport::new("hello_world", &symbol_table, Some(&section_table));
+    assert_eq!(
+        report.to_berkeley(),
+        "   text\t   data\t    bss\t    dec\t    hex\tfilename\n   1446\t    592\t   1032\t   3070\t    bfe\thello_world\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sysv_format_matches_gnu_size() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/hello_world.txt")?;
+    let section_table = SectionTable::from_file("tests/hello_world.txt")?;
+    let report = SizeReport::new("hello_world", &symbol_table, Some(&section_table));
+    let sysv = report.to_sysv();
+    let lines: Vec<&str> = sysv.lines().collect();
+    assert_eq!(lines[0], "hello_world  :");
+    assert_eq!(lines[1], "section              size    addr");
+    assert_eq!(lines[2], ".interp                28     792");
+    assert_eq!(lines[16], ".text                 313    4192");
+    assert_eq!(lines[28], ".comment               39       0");
+    assert_eq!(lines[29], "Total                3109");
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use binarytools::binary_utils::analysis::call_graph::CallGraph;
+use binarytools::binary_utils::analysis::stack::StackReport;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+#[test]
+fn test_stack_usage_from_file() -> Result<(), std::io::Error> {
+    let disassembly = Disassembly::from_file("tests/hello_world.txt")?;
+    let symbol_table = SymbolTable::from_file("tests/hello_world.txt")?;
+    let graph = CallGraph::new(&disassembly, &symbol_table);
+    let report = StackReport::new(&disassembly, &graph, &["main", "_start"]);
+
+    let main = report.entries.iter().find(|x| x.entry == "main").unwrap();
+    assert_eq!(main.path[..2], ["main".to_string(), "printf@plt".to_string()]);
+    assert!(main.worst_case > main.frame_size);
+    assert!(main.is_bounded());
+
+    // _start calls __libc_start_main through the GOT
+    let start = report.entries.iter().find(|x| x.entry == "_start").unwrap();
+    assert_eq!(start.indirect_calls, vec!["_start".to_string()]);
+    assert!(!start.is_bounded());
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use binarytools::binary_utils::analysis::call_graph::CallGraph;
+use binarytools::binary_utils::analysis::stack::{self, StackReport};
+use binarytools::binary_utils::parser::call_graph_info::CallGraphInfo;
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::stack_usage::{FrameKind, StackUsageTable};
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+// tests/stack_usage.su and tests/stack_usage.ci were written by GCC 12 with
+// `-O0 -fstack-usage -fcallgraph-info=su,da` for a file with recursion, alloca, a VLA and a function pointer call
+#[test]
+fn test_read_stack_usage_from_file() -> Result<(), std::io::Error> {
+    let stack_usage = StackUsageTable::from_file("tests/stack_usage.su")?;
+    assert_eq!(stack_usage.len(), 4);
+    let sum = stack_usage.find("sum", None).unwrap();
+    assert_eq!(sum.frame.bytes, 64);
+    assert_eq!(sum.frame.kind, FrameKind::Dynamic);
+    Ok(())
+}
+
+#[test]
+fn test_stack_usage_from_compiler_output_only() -> Result<(), std::io::Error> {
+    let info = CallGraphInfo::from_file("tests/stack_usage.ci")?;
+    assert!(info.has_indirect_calls("main"));
+
+    let symbol_table = SymbolTable::new();
+    let disassembly = Disassembly::new();
+    let mut graph = CallGraph::new(&disassembly, &symbol_table);
+    graph.add_call_graph_info(&info, &symbol_table);
+    let mut frames = stack::disassembly_frames(&disassembly, &graph);
+    stack::apply_compiler_frames(&mut frames, &graph, &symbol_table, Some(&info));
+
+    let report = StackReport::from_frames(&graph, &frames, &[]);
+    assert_eq!(report.entries.len(), 1);
+    let main = &report.entries[0];
+    assert_eq!(main.entry, "main");
+    assert_eq!(main.worst_case, 32 + 64);
+    assert_eq!(main.recursion, vec!["depth".to_string()]);
+    assert_eq!(main.indirect_calls, vec!["main".to_string()]);
+    assert_eq!(main.dynamic_frames, vec!["sum".to_string(), "vla".to_string()]);
+    assert_eq!(main.estimated_frames, vec!["memset".to_string(), "printf".to_string()]);
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use binarytools::binary_utils::parser::section_table;
 use binarytools::binary_utils::parser::symbol_table;
 use binarytools::string_utils;
 
@@ -20,3 +21,19 @@ fn test_symbol_table_from_iterable_of_string() -> Result<(), std::io::Error> {
     assert_eq!(1761, symbol_table.len());
     Ok(())
 }
+
+#[test]
+fn test_infer_sizes_from_file() -> Result<(), std::io::Error> {
+    let mut symbol_table = symbol_table::SymbolTable::from_file("tests/hello_world.txt")?;
+    let section_table = section_table::SectionTable::from_file("tests/hello_world.txt")?;
+    symbol_table.infer_sizes(Some(&section_table));
+
+    let size = |name: &str| {
+        let entry = symbol_table.iter().find(|x| x.name == name).unwrap();
+        (entry.alignment_or_size, entry.size_inferred)
+    };
+    assert_eq!(size("deregister_tm_clones"), (0x30, true));
+    assert_eq!(size(".hidden _init"), (0x17, true));
+    assert_eq!(size("main"), (0x48, false));
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use binarytools::binary_utils::analysis::tls::TlsReport;
+use binarytools::binary_utils::parser::section_table::SectionTable;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_tls_report_from_file() -> Result<(), std::io::Error> {
+    let lines = string_utils::read_lines_from_file("tests/tls.txt")?;
+    let symbol_table = SymbolTable::from_lines(&lines);
+    let section_table = SectionTable::from_lines(&lines);
+    let report = TlsReport::new(&symbol_table, Some(&section_table), 4);
+
+    let symbols: Vec<(&str, u32)> = report.symbols.iter().map(|x| (x.name.as_str(), x.offset)).collect();
+    assert_eq!(symbols, vec![("name", 0x00), ("counter", 0x20), ("depth", 0x30), ("scratch", 0x40)]);
+    assert_eq!(report.template_size, 0x24);
+    assert_eq!(report.zero_size, 0x90);
+    // .tbss starts on the next 16 byte boundary after .tdata, so the block is larger than the sum
+    assert_eq!(report.per_thread, 0xc0);
+    assert_eq!(report.tls_total, 4 * 0xc0);
+
+    // Static RAM is the same no matter how many threads run
+    let single = TlsReport::new(&symbol_table, Some(&section_table), 1);
+    assert_eq!(single.static_ram, report.static_ram);
+    assert_eq!(report.ram_estimate - single.ram_estimate, 3 * 0xc0);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use binarytools::binary_utils::analysis::unreachable::{Kind, Roots, UnreachableReport};
+use binarytools::binary_utils::parser::disassembly::Disassembly;
+use binarytools::binary_utils::parser::section_contents::SectionContents;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+
+fn unreachable(roots: &Roots) -> Result<UnreachableReport, std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/unreachable.txt")?;
+    let disassembly = Disassembly::from_file("tests/unreachable.txt")?;
+    let contents = SectionContents::from_file("tests/unreachable.txt")?;
+    Ok(UnreachableReport::new(&symbol_table, &disassembly, &contents, roots))
+}
+
+fn names(report: &UnreachableReport, kind: Kind) -> Vec<&str> {
+    let mut names: Vec<&str> = report
+        .symbols
+        .iter()
+        .filter(|x| x.kind == kind)
+        .map(|x| x.name.as_str())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn test_unreachable_from_file() -> Result<(), std::io::Error> {
+    let report = unreachable(&Roots::default())?;
+
+    // on_start is loaded as an immediate in main and setup is only reached through .init_array.
+    // on_stop is kept by the linker because the handlers table refers to it, but nothing reads
+    // the table
+    assert_eq!(
+        names(&report, Kind::Function),
+        vec![".hidden _dl_relocate_static_pie", "helper", "on_stop", "unused_function"]
+    );
+    assert_eq!(names(&report, Kind::Object), vec!["handlers", "unused_table"]);
+    assert_eq!(report.function_size, 1 + 0xe + 0x11 + 0x1a);
+    assert_eq!(report.object_size, 0x10 + 0x40);
+    Ok(())
+}
+
+#[test]
+fn test_unreachable_with_roots_from_file() -> Result<(), std::io::Error> {
+    let report = unreachable(&Roots {
+        symbols: vec!["handlers"],
+        exported: false,
+    })?;
+    assert_eq!(names(&report, Kind::Object), vec!["unused_table"]);
+    assert!(!names(&report, Kind::Function).contains(&"on_stop"));
+
+    let report = unreachable(&Roots {
+        symbols: Vec::new(),
+        exported: true,
+    })?;
+    assert!(report.symbols.is_empty());
+    Ok(())
+}
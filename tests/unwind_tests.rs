@@ -0,0 +1,33 @@
+use binarytools::binary_utils::analysis::unwind::UnwindReport;
+use binarytools::binary_utils::parser::eh_frame::EhFrame;
+use binarytools::binary_utils::parser::section_contents::SectionContents;
+use binarytools::binary_utils::parser::section_table::SectionTable;
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_unwind_report_from_file() -> Result<(), std::io::Error> {
+    let lines = string_utils::read_lines_from_file("tests/unwind.txt")?;
+    let eh_frame = EhFrame::from_lines(&lines);
+    let report = UnwindReport::new(
+        &SymbolTable::from_lines(&lines),
+        &SectionTable::from_lines(&lines),
+        &eh_frame,
+        &SectionContents::from_lines(&lines),
+    );
+    assert_eq!(eh_frame.fdes().count(), 7);
+
+    // Only the functions with a try block or a throw have an LSDA
+    let helper = report.find("_Z6helperi").unwrap();
+    assert_eq!((helper.fde, helper.search_table, helper.lsda), (36, 8, 12));
+    let guarded = report.find("_Z7guardedi").unwrap();
+    assert_eq!((guarded.fde, guarded.lsda), (28, 20));
+    assert_eq!(report.find("plain").unwrap().lsda, 0);
+    assert!(report.find(".plt").is_some());
+
+    let shared: Vec<(&str, u32)> = report.sections.iter().map(|x| (x.section.as_str(), x.shared)).collect();
+    // Three CIEs and the zero terminator, and the search table header
+    assert_eq!(shared, vec![(".eh_frame", 84), (".eh_frame_hdr", 12), (".gcc_except_table", 0)]);
+    assert_eq!(report.total_size, 0x11c + 0x44 + 0x20);
+    Ok(())
+}
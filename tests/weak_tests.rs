@@ -0,0 +1,24 @@
+use binarytools::binary_utils::analysis::weak::{ObjectSymbols, WeakReport};
+use binarytools::binary_utils::parser::symbol_table::SymbolTable;
+use binarytools::string_utils;
+
+#[test]
+fn test_weak_report_from_file() -> Result<(), std::io::Error> {
+    let symbol_table = SymbolTable::from_file("tests/weak.txt")?;
+    let objects = ObjectSymbols::from_lines(&string_utils::read_lines_from_file("tests/weak_objects.txt")?);
+    let report = WeakReport::new(&symbol_table, &objects);
+
+    // SysTick_Handler was never overridden, so it is still the default handler
+    let systick = report.definitions.iter().find(|x| x.name == "SysTick_Handler").unwrap();
+    assert_eq!(systick.aliases, vec!["Default_Handler".to_string()]);
+    assert!(!report.definitions.iter().any(|x| x.name == "UART_Handler"));
+    assert!(report.unresolved.iter().any(|x| x.name == "optional_hook"));
+
+    let overridden: Vec<(&str, Option<&str>)> = report
+        .overrides
+        .iter()
+        .map(|x| (x.name.as_str(), x.winner.as_deref()))
+        .collect();
+    assert_eq!(overridden, vec![("UART_Handler", Some("main.o")), ("board_init", Some("main.o"))]);
+    Ok(())
+}